@@ -0,0 +1,177 @@
+use crystal_cif_io::{
+    data_dict::{
+        core_cif::atom_site::{expand_to_p1, DEFAULT_SITE_TOLERANCE},
+        DataReadError, Structure,
+    },
+    diff::json_string,
+    CifDocument, DataBlock, DataBlockMember, DataItems, Value,
+};
+
+/// The document as a JSON array of data blocks.
+pub(crate) fn document_json(document: &CifDocument) -> String {
+    let blocks: Vec<String> = document
+        .data_blocks()
+        .map_or(&[][..], |blocks| blocks.as_slice())
+        .iter()
+        .map(block_json)
+        .collect();
+    format!("[\n{}\n]\n", blocks.join(",\n"))
+}
+
+/// A data block as a JSON object of its name and items: single values map
+/// to values and loop columns to arrays. `?` and `.` are `null`, numbers
+/// with an su are kept as strings like `"1.234(5)"`.
+pub(crate) fn block_json(block: &DataBlock) -> String {
+    let mut items = Vec::new();
+    for member in block.members() {
+        match member {
+            DataBlockMember::DataItems(DataItems::SingleValue(item)) => items.push(format!(
+                "    {}: {}",
+                json_string(item.tag().as_str()),
+                json_value(item.value())
+            )),
+            DataBlockMember::DataItems(DataItems::MultiValues(columns)) => {
+                items.extend(columns.columns().iter().map(|column| {
+                    let values: Vec<String> = column.values().iter().map(json_value).collect();
+                    format!(
+                        "    {}: [{}]",
+                        json_string(column.tag().as_str()),
+                        values.join(", ")
+                    )
+                }))
+            }
+            DataBlockMember::SaveFrame(_) => {}
+        }
+    }
+    format!(
+        "{{\n  \"name\": {},\n  \"items\": {{\n{}\n  }}\n}}",
+        json_string(block.heading()),
+        items.join(",\n")
+    )
+}
+
+fn json_value(value: &Value) -> String {
+    match value {
+        Value::Inapplicable | Value::Unknown => "null".to_string(),
+        Value::Numeric(n) if n.std_uncertainty().is_none() && is_json_number(&n.to_string()) => {
+            n.to_string()
+        }
+        v => json_string(&v.text()),
+    }
+}
+
+/// Whether the CIF number is also a JSON number, unlike `.5` or `+1`.
+fn is_json_number(number: &str) -> bool {
+    let digits = number.strip_prefix('-').unwrap_or(number);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && !digits.ends_with('.')
+        && !digits.contains(".e")
+        && !digits.contains(".E")
+        && (digits.len() == 1 || !digits.starts_with('0') || digits[1..].starts_with('.'))
+}
+
+/// The structure of the data block with its sites expanded to the full
+/// unit cell.
+pub(crate) fn unit_cell_structure(block: &DataBlock) -> Result<Structure, DataReadError> {
+    let expanded = expand_to_p1(block, DEFAULT_SITE_TOLERANCE)?;
+    Structure::try_from(&expanded)
+}
+
+/// Cell vectors in Å with `a` along x and `b` in the xy plane.
+fn lattice_vectors(structure: &Structure) -> [[f64; 3]; 3] {
+    let [a, b, c, alpha, beta, gamma] = structure.cell().values();
+    let [cos_alpha, cos_beta, cos_gamma] = [alpha, beta, gamma].map(|x| x.to_radians().cos());
+    let sin_gamma = gamma.to_radians().sin();
+    let cy = (cos_alpha - cos_beta * cos_gamma) / sin_gamma;
+    let cz = (1.0 - cos_beta.powi(2) - cy.powi(2)).sqrt();
+    [
+        [a, 0.0, 0.0],
+        [b * cos_gamma, b * sin_gamma, 0.0],
+        [c * cos_beta, c * cy, c * cz],
+    ]
+}
+
+fn fract_values(structure: &Structure) -> Vec<[f64; 3]> {
+    structure
+        .sites()
+        .iter()
+        .map(|site| site.fract().map(|x| x.as_f64()))
+        .collect()
+}
+
+/// XYZ file of every site of the structure in Cartesian coordinates.
+pub(crate) fn xyz(structure: &Structure) -> String {
+    let lattice = lattice_vectors(structure);
+    let mut lines = vec![
+        structure.sites().len().to_string(),
+        structure.name().to_string(),
+    ];
+    lines.extend(
+        structure
+            .sites()
+            .iter()
+            .zip(fract_values(structure))
+            .map(|(site, fract)| {
+                let cartesian: [f64; 3] =
+                    std::array::from_fn(|i| (0..3).map(|k| fract[k] * lattice[k][i]).sum());
+                format!(
+                    "{:<2} {:>12.6} {:>12.6} {:>12.6}",
+                    site.element().to_string(),
+                    cartesian[0],
+                    cartesian[1],
+                    cartesian[2]
+                )
+            }),
+    );
+    lines.join("\n") + "\n"
+}
+
+/// VASP POSCAR with the sites grouped by element in order of appearance.
+pub(crate) fn poscar(structure: &Structure) -> String {
+    let fract = fract_values(structure);
+    let mut elements = Vec::new();
+    structure.sites().iter().for_each(|site| {
+        if !elements.contains(&site.element()) {
+            elements.push(site.element());
+        }
+    });
+    let mut lines = vec![structure.name().to_string(), "1.0".to_string()];
+    lines.extend(
+        lattice_vectors(structure)
+            .iter()
+            .map(|v| format!("{:>14.8} {:>14.8} {:>14.8}", v[0], v[1], v[2])),
+    );
+    lines.push(
+        elements
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+            .join(" "),
+    );
+    lines.push(
+        elements
+            .iter()
+            .map(|e| {
+                structure
+                    .sites()
+                    .iter()
+                    .filter(|site| site.element() == *e)
+                    .count()
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join(" "),
+    );
+    lines.push("Direct".to_string());
+    for element in elements.iter() {
+        lines.extend(
+            structure
+                .sites()
+                .iter()
+                .zip(fract.iter())
+                .filter(|(site, _)| site.element() == *element)
+                .map(|(_, x)| format!("{:>12.8} {:>12.8} {:>12.8}", x[0], x[1], x[2])),
+        );
+    }
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,496 @@
+//! `cif`: validate, reformat, query and convert CIF files.
+
+use std::{
+    fs::{read_to_string, write, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use crystal_cif_io::{
+    data_dict::{density_volume_mismatches, Structure},
+    diff::{diff_with_options, DiffOptions},
+    query::Query,
+    reader::CifBlockReader,
+    split::{join, split, unique_names},
+    tolerant,
+    write::{write_document, QuoteStyle, WriteOptions},
+    CifDocument, DataBlock, DataBlockMember, DataItems, Value,
+};
+
+mod convert;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "cif",
+    version,
+    about = "Validate, format, query and convert CIF files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check the syntax of the files; exits with 1 if any file fails.
+    Validate {
+        files: Vec<PathBuf>,
+        /// Also read the cell, symmetry and atom sites of every data block
+        /// and compare the reported volume and density with calculated ones.
+        #[arg(long)]
+        dictionary: bool,
+    },
+    /// Rewrite the files in the layout of this crate's writer.
+    Fmt {
+        files: Vec<PathBuf>,
+        /// Only report files which would change; exits with 1 if any would.
+        #[arg(long)]
+        check: bool,
+        /// Print the formatted document instead of writing it back.
+        #[arg(long, conflicts_with = "check")]
+        stdout: bool,
+        /// Pad the tags of single items to this width.
+        #[arg(long, default_value_t = 33)]
+        tag_width: usize,
+        /// Indent loop tags and rows by this many spaces.
+        #[arg(long, default_value_t = 0)]
+        indent: usize,
+        /// Pad loop values to the widest value of their column.
+        #[arg(long)]
+        align: bool,
+        /// How to quote quoted strings.
+        #[arg(long, value_enum, default_value_t = Quotes::Preserve)]
+        quotes: Quotes,
+    },
+    /// Print the values of a tag, one per line.
+    Get {
+        file: PathBuf,
+        /// With or without the leading underscore, e.g. `_cell_length_a`.
+        tag: String,
+        /// Only look in this data block.
+        #[arg(long)]
+        block: Option<String>,
+    },
+    /// List the names of the data blocks.
+    Blocks { file: PathBuf },
+    /// Print the values selected by a query like
+    /// `*/_atom_site[_atom_site_type_symbol='Fe']/_atom_site_fract_x`, with
+    /// the block, tag and loop row of each.
+    Query { query: String, files: Vec<PathBuf> },
+    /// Show what changed between two files; exits with 1 if anything did.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Print the changes as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Numbers differing by at most this much are taken as equal.
+        #[arg(long, default_value_t = 0.0)]
+        tolerance: f64,
+        /// Numbers within this many combined su are taken as equal.
+        #[arg(long)]
+        su: Option<f64>,
+    },
+    /// Write every data block to its own file, named by its block code.
+    Split {
+        file: PathBuf,
+        /// Directory of the files; the current one by default.
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+    },
+    /// Concatenate files, renaming repeated block codes.
+    Join {
+        files: Vec<PathBuf>,
+        /// Write to this file instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Convert a data block to another format.
+    Convert {
+        file: PathBuf,
+        #[arg(long = "to", value_enum)]
+        format: Format,
+        /// The data block to convert; defaults to the first with atom sites
+        /// for `xyz` and `poscar`.
+        #[arg(long)]
+        block: Option<String>,
+        /// Write to this file instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Quotes {
+    /// Keep the quotes of the input.
+    Preserve,
+    /// `'`, unless the content needs `"`.
+    Single,
+    /// `"`, unless the content needs `'`.
+    Double,
+}
+
+impl From<Quotes> for QuoteStyle {
+    fn from(quotes: Quotes) -> Self {
+        match quotes {
+            Quotes::Preserve => QuoteStyle::Preserve,
+            Quotes::Single => QuoteStyle::Single,
+            Quotes::Double => QuoteStyle::Double,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// The whole document, tags mapped to values or arrays of loop values.
+    Json,
+    /// Cartesian coordinates of all atoms of the unit cell.
+    Xyz,
+    /// VASP structure file with the cell and fractional coordinates.
+    Poscar,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Validate { files, dictionary } => validate(&files, dictionary),
+        Command::Fmt {
+            files,
+            check,
+            stdout,
+            tag_width,
+            indent,
+            align,
+            quotes,
+        } => {
+            let options = WriteOptions::default()
+                .with_tag_width(tag_width)
+                .with_loop_indent(indent)
+                .with_aligned_loops(align)
+                .with_quotes(quotes.into());
+            format_files(&files, check, stdout, &options)
+        }
+        Command::Get { file, tag, block } => get(&file, &tag, block.as_deref()),
+        Command::Blocks { file } => blocks(&file),
+        Command::Query { query, files } => run_query(&query, &files),
+        Command::Diff {
+            old,
+            new,
+            json,
+            tolerance,
+            su,
+        } => run_diff(&old, &new, json, tolerance, su),
+        Command::Split { file, dir } => run_split(&file, &dir),
+        Command::Join { files, output } => run_join(&files, output.as_deref()),
+        Command::Convert {
+            file,
+            format,
+            block,
+            output,
+        } => convert(&file, format, block.as_deref(), output.as_deref()),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Read and parse a CIF file. A syntax error is reported at the first item
+/// which [`tolerant::parse`] has to skip.
+fn read_document(path: &Path) -> Result<CifDocument<'static>, String> {
+    parse_document(path).map_err(|mut errors| errors.remove(0))
+}
+
+/// Read and parse a CIF file, reporting every item which
+/// [`tolerant::parse`] has to skip. The strict parser only sees files
+/// without any.
+fn parse_document(path: &Path) -> Result<CifDocument<'static>, Vec<String>> {
+    let content =
+        read_to_string(path).map_err(|e| vec![format!("cannot read {}: {e}", path.display())])?;
+    let diagnostics = tolerant::parse(&content).1;
+    if !diagnostics.is_empty() {
+        return Err(diagnostics
+            .iter()
+            .map(|diagnostic| format!("{}:{diagnostic}", path.display()))
+            .collect());
+    }
+    let mut input = content.as_str();
+    match CifDocument::parse_from_str(&mut input) {
+        Ok(document) if input.trim().is_empty() => Ok(document.into_owned()),
+        _ => Err(vec![format!("{}: syntax error", path.display())]),
+    }
+}
+
+fn data_blocks<'b, 'a>(document: &'b CifDocument<'a>) -> &'b [DataBlock<'a>] {
+    document
+        .data_blocks()
+        .map_or(&[], |blocks| blocks.as_slice())
+}
+
+fn validate(files: &[PathBuf], dictionary: bool) -> Result<bool, String> {
+    let mut valid = true;
+    for path in files {
+        let document = match parse_document(path) {
+            Ok(document) => document,
+            Err(errors) => {
+                errors.iter().for_each(|error| println!("{error}"));
+                valid = false;
+                continue;
+            }
+        };
+        let mut problems = Vec::new();
+        if dictionary {
+            for block in data_blocks(&document) {
+                problems.extend(
+                    dictionary_problems(block)
+                        .into_iter()
+                        .map(|problem| format!("data_{}: {problem}", block.heading())),
+                );
+            }
+        }
+        if problems.is_empty() {
+            println!("{}: ok", path.display());
+        } else {
+            valid = false;
+            problems
+                .iter()
+                .for_each(|problem| println!("{}: {problem}", path.display()));
+        }
+    }
+    Ok(valid)
+}
+
+fn dictionary_problems(block: &DataBlock) -> Vec<String> {
+    let mut problems = Vec::new();
+    if find_values(block, "atom_site_label").is_some() {
+        if let Err(e) = Structure::try_from(block) {
+            problems.push(e.to_string());
+        }
+    }
+    match density_volume_mismatches(block) {
+        Ok(mismatches) => problems.extend(mismatches.iter().map(|check| {
+            format!(
+                "_{} is {}, calculated {}",
+                check.tag(),
+                check.reported(),
+                check.calculated()
+            )
+        })),
+        Err(e) => problems.push(e.to_string()),
+    }
+    problems
+}
+
+fn format_files(
+    files: &[PathBuf],
+    check: bool,
+    stdout: bool,
+    options: &WriteOptions,
+) -> Result<bool, String> {
+    let mut unchanged = true;
+    for path in files {
+        let document = read_document(path)?;
+        let formatted = write_document(&document, options);
+        if stdout {
+            println!("{formatted}");
+        } else if check {
+            let content =
+                read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            if content.trim_end() != formatted.trim_end() {
+                println!("{} would be reformatted", path.display());
+                unchanged = false;
+            }
+        } else {
+            write(path, formatted + "\n")
+                .map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        }
+    }
+    Ok(unchanged)
+}
+
+/// The values of `tag` in a data block, as a single value or loop column;
+/// tags are compared case-insensitively.
+fn find_values<'a>(block: &'a DataBlock<'a>, tag: &str) -> Option<Vec<&'a Value<'a>>> {
+    block.members().iter().find_map(|member| match member {
+        DataBlockMember::DataItems(DataItems::SingleValue(item))
+            if item.tag().as_str().eq_ignore_ascii_case(tag) =>
+        {
+            Some(vec![item.value()])
+        }
+        DataBlockMember::DataItems(DataItems::MultiValues(columns)) => columns
+            .columns()
+            .iter()
+            .find(|column| column.tag().as_str().eq_ignore_ascii_case(tag))
+            .map(|column| column.values().iter().collect()),
+        _ => None,
+    })
+}
+
+fn get(file: &Path, tag: &str, block_name: Option<&str>) -> Result<bool, String> {
+    let document = read_document(file)?;
+    let tag = tag.trim_start_matches('_');
+    let blocks: Vec<&DataBlock> = data_blocks(&document)
+        .iter()
+        .filter(|block| block_name.is_none_or(|name| block.heading() == name))
+        .collect();
+    let mut found = false;
+    for block in blocks.iter() {
+        if let Some(values) = find_values(block, tag) {
+            found = true;
+            values.iter().for_each(|value| match blocks.len() {
+                1 => println!("{}", value.text()),
+                _ => println!("data_{}\t{}", block.heading(), value.text()),
+            });
+        }
+    }
+    if !found {
+        eprintln!("_{tag} not found in {}", file.display());
+    }
+    Ok(found)
+}
+
+/// Streams the blocks, so that archives of any size can be listed.
+fn blocks(file: &Path) -> Result<bool, String> {
+    let opened = File::open(file).map_err(|e| format!("cannot read {}: {e}", file.display()))?;
+    for block in CifBlockReader::new(BufReader::new(opened)) {
+        let block = block.map_err(|e| format!("{}: {e}", file.display()))?;
+        println!("{}", block.heading());
+    }
+    Ok(true)
+}
+
+fn run_query(query: &str, files: &[PathBuf]) -> Result<bool, String> {
+    let query = query.parse::<Query>().map_err(|e| e.to_string())?;
+    let mut found = false;
+    for path in files {
+        let document = read_document(path)?;
+        for selected in query.evaluate(&document) {
+            found = true;
+            let location = match selected.row() {
+                Some(row) => format!("_{}[{row}]", selected.tag()),
+                None => format!("_{}", selected.tag()),
+            };
+            let line = format!(
+                "{}\t{location}\t{}",
+                selected.block(),
+                selected.value().text()
+            );
+            match files.len() {
+                1 => println!("{line}"),
+                _ => println!("{}\t{line}", path.display()),
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn run_diff(
+    old: &Path,
+    new: &Path,
+    json: bool,
+    tolerance: f64,
+    su: Option<f64>,
+) -> Result<bool, String> {
+    let mut options = DiffOptions::default().with_tolerance(tolerance);
+    if let Some(su) = su {
+        options = options.with_su_multiple(su);
+    }
+    let changes = diff_with_options(&read_document(old)?, &read_document(new)?, &options);
+    match json {
+        true => println!("{}", changes.to_json()),
+        false => print!("{changes}"),
+    }
+    Ok(changes.is_empty())
+}
+
+fn run_split(file: &Path, dir: &Path) -> Result<bool, String> {
+    let document = read_document(file)?;
+    let parts = split(&document);
+    // Codes like `a/b` and `a_b` give the same file name, renamed as the
+    // block codes are.
+    let names: Vec<String> = parts
+        .iter()
+        .map(|(code, _)| {
+            code.chars()
+                .map(
+                    |c| match c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                        true => c,
+                        false => '_',
+                    },
+                )
+                .collect()
+        })
+        .collect();
+    let names = unique_names(names.iter().map(String::as_str));
+    for ((_, single), name) in parts.iter().zip(names) {
+        let path = dir.join(format!("{name}.cif"));
+        write(&path, single.to_string() + "\n")
+            .map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        println!("{}", path.display());
+    }
+    Ok(true)
+}
+
+fn run_join(files: &[PathBuf], output: Option<&Path>) -> Result<bool, String> {
+    let documents = files
+        .iter()
+        .map(|path| read_document(path))
+        .collect::<Result<Vec<CifDocument>, String>>()?;
+    let joined = join(&documents).to_string() + "\n";
+    match output {
+        Some(path) => {
+            write(path, joined).map_err(|e| format!("cannot write {}: {e}", path.display()))?
+        }
+        None => print!("{joined}"),
+    }
+    Ok(true)
+}
+
+fn convert(
+    file: &Path,
+    format: Format,
+    block_name: Option<&str>,
+    output: Option<&Path>,
+) -> Result<bool, String> {
+    let document = read_document(file)?;
+    let converted = match format {
+        Format::Json => match block_name {
+            Some(name) => {
+                let block = data_blocks(&document)
+                    .iter()
+                    .find(|block| block.heading() == name)
+                    .ok_or_else(|| format!("no data block {name} in {}", file.display()))?;
+                convert::block_json(block)
+            }
+            None => convert::document_json(&document),
+        },
+        Format::Xyz | Format::Poscar => {
+            let block = data_blocks(&document)
+                .iter()
+                .find(|block| match block_name {
+                    Some(name) => block.heading() == name,
+                    None => find_values(block, "atom_site_label").is_some(),
+                })
+                .ok_or_else(|| format!("no data block with atom sites in {}", file.display()))?;
+            let structure = convert::unit_cell_structure(block)
+                .map_err(|e| format!("data_{}: {e}", block.heading()))?;
+            match format {
+                Format::Xyz => convert::xyz(&structure),
+                _ => convert::poscar(&structure),
+            }
+        }
+    };
+    match output {
+        Some(path) => {
+            write(path, converted).map_err(|e| format!("cannot write {}: {e}", path.display()))?
+        }
+        None => print!("{converted}"),
+    }
+    Ok(true)
+}
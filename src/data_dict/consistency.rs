@@ -0,0 +1,177 @@
+use crate::{
+    data_dict::{
+        core_cif::{
+            cell::{measured, CellParameters},
+            chemical::{read_formula_sum, read_formula_weight},
+        },
+        reading::{optional_single_numeric, optional_single_parsed},
+        DataReadError,
+    },
+    grammar::{DataBlock, Numeric},
+};
+
+/// Grams per mole of atomic mass units per Å³ in Mg m⁻³: `10²⁴ / N_A`.
+const DENSITY_FACTOR: f64 = 1.660_539;
+
+/// Number of combined standard uncertainties by which a reported value may
+/// differ from the calculated one.
+const SU_MULTIPLE: f64 = 3.0;
+
+/// A value reported in a data block against the value calculated from other
+/// items.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivedValueCheck {
+    tag: &'static str,
+    reported: Numeric,
+    calculated: Numeric,
+}
+
+impl DerivedValueCheck {
+    pub fn tag(&self) -> &str {
+        self.tag
+    }
+
+    pub fn reported(&self) -> Numeric {
+        self.reported
+    }
+
+    pub fn calculated(&self) -> Numeric {
+        self.calculated
+    }
+
+    /// Reported minus calculated value.
+    pub fn difference(&self) -> f64 {
+        self.reported.as_f64() - self.calculated.as_f64()
+    }
+
+    /// Largest difference still taken as consistent: three times the
+    /// combined su of both values, but at least the rounding of the reported
+    /// value to its decimal places.
+    pub fn tolerance(&self) -> f64 {
+        let su = |n: Numeric| n.su_value().unwrap_or(0.0);
+        let combined = (su(self.reported).powi(2) + su(self.calculated).powi(2)).sqrt();
+        let rounding = 0.5 * 10f64.powi(-(self.reported.decimal_places() as i32));
+        (SU_MULTIPLE * combined).max(rounding)
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.difference().abs() <= self.tolerance()
+    }
+}
+
+/// Calculated density in Mg m⁻³ of `z` formula units of `formula_weight`
+/// g/mol in a cell of `volume` Å³, with the su of the volume propagated.
+pub fn crystal_density(z: u32, formula_weight: f64, volume: Numeric) -> Numeric {
+    let density = z as f64 * formula_weight * DENSITY_FACTOR / volume.as_f64();
+    let su = volume.su_value().unwrap_or(0.0) * density / volume.as_f64();
+    measured(density, su, 3)
+}
+
+/// `_exptl_crystal_density_diffrn` from the cell, `_cell_formula_units_Z`
+/// and `_chemical_formula_weight`, or the weight of `_chemical_formula_sum`
+/// when no weight is given.
+pub fn calculated_density(data_block: &DataBlock) -> Result<Numeric, DataReadError> {
+    let volume = CellParameters::from_data_block(data_block)?.volume();
+    let z = optional_single_parsed::<u32>(data_block, "cell_formula_units_Z")?
+        .ok_or_else(|| DataReadError::MissingTag("cell_formula_units_Z".to_string()))?;
+    let formula_weight = match read_formula_weight(data_block)? {
+        Some(weight) => weight.as_f64(),
+        None => read_formula_sum(data_block)?
+            .ok_or_else(|| DataReadError::MissingTag("chemical_formula_weight".to_string()))?
+            .molar_mass(),
+    };
+    Ok(crystal_density(z, formula_weight, volume))
+}
+
+/// `_cell_volume` as reported against the volume of the cell parameters;
+/// `None` when no volume is reported.
+pub fn check_cell_volume(
+    data_block: &DataBlock,
+) -> Result<Option<DerivedValueCheck>, DataReadError> {
+    optional_single_numeric(data_block, "cell_volume")?
+        .map(|reported| {
+            Ok(DerivedValueCheck {
+                tag: "cell_volume",
+                reported,
+                calculated: CellParameters::from_data_block(data_block)?.volume(),
+            })
+        })
+        .transpose()
+}
+
+/// `_exptl_crystal_density_diffrn` as reported against
+/// [`calculated_density`]; `None` when no density is reported.
+pub fn check_crystal_density(
+    data_block: &DataBlock,
+) -> Result<Option<DerivedValueCheck>, DataReadError> {
+    optional_single_numeric(data_block, "exptl_crystal_density_diffrn")?
+        .map(|reported| {
+            Ok(DerivedValueCheck {
+                tag: "exptl_crystal_density_diffrn",
+                reported,
+                calculated: calculated_density(data_block)?,
+            })
+        })
+        .transpose()
+}
+
+/// The reported cell volume and density which disagree with the values
+/// calculated from the rest of the data block.
+pub fn density_volume_mismatches(
+    data_block: &DataBlock,
+) -> Result<Vec<DerivedValueCheck>, DataReadError> {
+    Ok([
+        check_cell_volume(data_block)?,
+        check_crystal_density(data_block)?,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|check| !check.is_consistent())
+    .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, SyntacticUnit};
+
+    use super::{calculated_density, check_cell_volume, density_volume_mismatches};
+
+    const INPUT: &str = r#"data_I
+_chemical_formula_sum            'C22 H50 N4 O7'
+_chemical_formula_weight          482.66
+_cell_length_a                    8.6559(9)
+_cell_length_b                    9.3275(9)
+_cell_length_c                    10.1044(12)
+_cell_angle_alpha                 113.379(5)
+_cell_angle_beta                  108.908(4)
+_cell_angle_gamma                 100.345(4)
+_cell_volume                      662.31(12)
+_cell_formula_units_Z             1
+_exptl_crystal_density_diffrn     1.210
+"#;
+
+    #[test]
+    fn density_and_volume() {
+        let mut input = INPUT;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let density = calculated_density(&block).unwrap();
+        assert!((density.as_f64() - 1.2101).abs() < 1e-3);
+        let volume = check_cell_volume(&block).unwrap().unwrap();
+        assert!(volume.is_consistent());
+        assert!(density_volume_mismatches(&block).unwrap().is_empty());
+
+        let typo = INPUT
+            .replace("662.31(12)", "626.31(12)")
+            .replace("1.210", "1.120");
+        let mut input = typo.as_str();
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let mismatches = density_volume_mismatches(&block).unwrap();
+        let tags: Vec<&str> = mismatches.iter().map(|check| check.tag()).collect();
+        assert_eq!(tags, ["cell_volume", "exptl_crystal_density_diffrn"]);
+
+        let no_z = INPUT.replace("_cell_formula_units_Z             1\n", "");
+        let mut input = no_z.as_str();
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        assert!(density_volume_mismatches(&block).is_err());
+    }
+}
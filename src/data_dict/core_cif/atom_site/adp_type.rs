@@ -20,19 +20,19 @@ impl Display for AdpType {
     }
 }
 
-impl From<AdpType> for UnquotedString {
+impl From<AdpType> for UnquotedString<'_> {
     fn from(value: AdpType) -> Self {
         UnquotedString::new(format!("{value}"))
     }
 }
 
-impl From<AdpType> for CharString {
+impl From<AdpType> for CharString<'_> {
     fn from(value: AdpType) -> Self {
         CharString::Unquoted(UnquotedString::from(value))
     }
 }
 
-impl From<AdpType> for Value {
+impl From<AdpType> for Value<'_> {
     fn from(value: AdpType) -> Self {
         Value::CharString(CharString::from(value))
     }
@@ -1,17 +1,48 @@
-use chemrust_core::data::{atom::CoreAtomData, geom::coordinates::CoordData::Fractional};
+use std::fmt::Display;
+
+use chemrust_core::data::{
+    atom::CoreAtomData,
+    geom::coordinates::CoordData::{self, Fractional},
+    lattice::{CrystalModel, UnitCellParameters},
+    symmetry::SymmetryInfo,
+};
 
 use crate::{
-    data_dict::{core_cif::atom_site::adp_type::AdpType, LoopValueTerm},
-    grammar::{CharString, DataItems, Float, LoopUnit, Numeric, UnquotedString},
+    data_dict::{
+        core_cif::{
+            atom_site::adp_type::AdpType,
+            cell::metric_tensor,
+            space_group::{chemrust_impl::model_space_group, SiteSymmetryFinder},
+        },
+        LoopValueTerm,
+    },
+    grammar::{CharString, DataItems, Float, LoopUnit, Numeric, UnquotedString, UnsignedInteger},
     DataBlock, DataBlockHeading, DataBlockMember,
 };
 
 use super::{
     label_symbol::{TypeSymbol, TypeSymbolCode},
+    symmetry_multiplicity::SymMultiplicity,
     AtomSiteLoopItem,
 };
 
-pub(crate) fn basic_atom_site_data<T: CoreAtomData>(atom_data: &T) -> DataItems {
+/// The lattice bases of the model cannot be inverted to convert Cartesian
+/// coordinates to fractional ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SingularCellError;
+
+impl Display for SingularCellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "singular lattice bases, cannot convert to fractional coordinates"
+        )
+    }
+}
+
+impl std::error::Error for SingularCellError {}
+
+pub(crate) fn basic_atom_site_data<T: CoreAtomData>(atom_data: &T) -> DataItems<'static> {
     let (labels, symbols): (Vec<CharString>, Vec<TypeSymbol>) = atom_data
         .symbols_repr()
         .iter()
@@ -54,9 +85,16 @@ pub(crate) fn basic_atom_site_data<T: CoreAtomData>(atom_data: &T) -> DataItems
             AtomSiteLoopItem::Cartn_z(z),
         )
     };
-    let u_iso = AtomSiteLoopItem::U_iso_or_equiv(vec![Float(0.0); atom_data.coords_repr().len()]);
-    let adp_type = AtomSiteLoopItem::Adp_type(vec![AdpType::Uiso; atom_data.coords_repr().len()]);
-    let occupancy = AtomSiteLoopItem::Occupancy(vec![Float(1.0); atom_data.coords_repr().len()]);
+    let u_iso = AtomSiteLoopItem::U_iso_or_equiv(vec![
+        Some(Numeric::from(Float(0.0)));
+        atom_data.coords_repr().len()
+    ]);
+    let adp_type =
+        AtomSiteLoopItem::Adp_type(vec![Some(AdpType::Uiso); atom_data.coords_repr().len()]);
+    let occupancy = AtomSiteLoopItem::Occupancy(vec![
+        Numeric::from(Float(1.0));
+        atom_data.coords_repr().len()
+    ]);
     let columns =
         [labels, symbols, x, y, z, u_iso, adp_type, occupancy].map(|item| item.to_loop_column());
     let loop_unit = LoopUnit::builder()
@@ -65,7 +103,106 @@ pub(crate) fn basic_atom_site_data<T: CoreAtomData>(atom_data: &T) -> DataItems
     DataItems::MultiValues(loop_unit.into())
 }
 
-pub fn from_atom_data(value: &impl CoreAtomData) -> Vec<DataBlockMember> {
+/// Atom sites of the asymmetric unit of the model: one site for each set of
+/// atoms of the same element related by the operators of its space group,
+/// with the multiplicity, site symmetry order and Wyckoff letter of the site,
+/// `?` when the position is not tabulated.
+pub(crate) fn asymmetric_unit_atom_site_data<T: CrystalModel + SymmetryInfo>(
+    model: &T,
+    tolerance: f64,
+) -> Result<DataItems<'static>, SingularCellError> {
+    let cell = model.get_cell_parameters();
+    let atom_data = model.get_atom_data();
+    let to_fractional = cell.lattice_bases().try_inverse();
+    let positions = atom_data
+        .coords_repr()
+        .iter()
+        .map(|cd| match cd {
+            CoordData::Fractional(p) => Ok([p.x, p.y, p.z]),
+            CoordData::Cartesian(p) => {
+                let f = to_fractional.ok_or(SingularCellError)? * p.coords;
+                Ok([f.x, f.y, f.z])
+            }
+        })
+        .collect::<Result<Vec<[f64; 3]>, SingularCellError>>()?;
+    let metric = metric_tensor(&[
+        cell.length_a(),
+        cell.length_b(),
+        cell.length_c(),
+        cell.angle_alpha(),
+        cell.angle_beta(),
+        cell.angle_gamma(),
+    ]);
+    let (operators, setting) = model_space_group(model);
+    let finder = SiteSymmetryFinder::new(operators, metric, tolerance);
+    let finder = match setting {
+        Some(setting) => finder.with_setting(setting),
+        None => finder,
+    };
+    let symbols = atom_data.symbols_repr();
+    let labels = atom_data.labels_repr();
+    let sites = finder.asymmetric_unit(&symbols, &positions);
+    let label = AtomSiteLoopItem::Label(
+        sites
+            .iter()
+            .map(|(i, _)| {
+                let label = labels
+                    .get(*i)
+                    .cloned()
+                    .flatten()
+                    .unwrap_or_else(|| format!("{}{}", symbols[*i], i));
+                CharString::from(label)
+            })
+            .collect(),
+    );
+    let symbol = AtomSiteLoopItem::Type_symbol(
+        sites
+            .iter()
+            .map(|(i, _)| TypeSymbol::new(TypeSymbolCode::ElementSymbol(symbols[*i]), None))
+            .collect(),
+    );
+    let [x, y, z] = [0, 1, 2].map(|k| {
+        sites
+            .iter()
+            .map(|(i, _)| Numeric::from(positions[*i][k]))
+            .collect::<Vec<Numeric>>()
+    });
+    let wyckoff_letters = sites
+        .iter()
+        .map(|(_, site)| site.wyckoff_letter().ok())
+        .collect();
+    let n = sites.len();
+    let columns = [
+        label,
+        symbol,
+        AtomSiteLoopItem::Fract_x(x),
+        AtomSiteLoopItem::Fract_y(y),
+        AtomSiteLoopItem::Fract_z(z),
+        AtomSiteLoopItem::U_iso_or_equiv(vec![Some(Numeric::from(Float(0.0))); n]),
+        AtomSiteLoopItem::Adp_type(vec![Some(AdpType::Uiso); n]),
+        AtomSiteLoopItem::Occupancy(vec![Numeric::from(Float(1.0)); n]),
+        AtomSiteLoopItem::Symmetry_multiplicity(
+            sites
+                .iter()
+                .map(|(_, site)| SymMultiplicity::new(site.multiplicity() as u8))
+                .collect(),
+        ),
+        AtomSiteLoopItem::Site_symmetry_order(
+            sites
+                .iter()
+                .map(|(_, site)| UnsignedInteger(site.order() as u32))
+                .collect(),
+        ),
+        AtomSiteLoopItem::Wyckoff_symbol(wyckoff_letters),
+    ]
+    .map(|item| item.to_loop_column());
+    let loop_unit = LoopUnit::builder()
+        .with_value_columns(columns.to_vec())
+        .build();
+    Ok(DataItems::MultiValues(loop_unit.into()))
+}
+
+pub fn from_atom_data(value: &impl CoreAtomData) -> Vec<DataBlockMember<'static>> {
     let atom_data = basic_atom_site_data(value);
     vec![DataBlockMember::DataItems(atom_data)]
 }
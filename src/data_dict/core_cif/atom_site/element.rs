@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use castep_periodic_table::{
+    data::ELEMENT_TABLE,
+    element::{ElementSymbol, LookupElement},
+};
+
+use crate::{
+    data_dict::{
+        reading::{find_column, optional_char_string, required_text},
+        DataReadError,
+    },
+    grammar::{DataBlock, Value},
+};
+
+use super::{TypeSymbol, TypeSymbolCode};
+
+/// Where the element of an atom site was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementSource {
+    /// `_atom_site_type_symbol` names an element, as `Fe` or `O2-`.
+    TypeSymbol,
+    /// The element is guessed from the leading letters of
+    /// `_atom_site_type_symbol`, as `Ow` or `C1`.
+    TypeSymbolPrefix,
+    /// The element is guessed from the leading letters of the label, as
+    /// `C12A` or `O1W`.
+    LabelPrefix,
+}
+
+/// The element of an atom site with its oxidation state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedElement {
+    element: ElementSymbol,
+    oxidation_state: Option<i32>,
+    source: ElementSource,
+    alternative: Option<ElementSymbol>,
+}
+
+impl ResolvedElement {
+    pub fn element(&self) -> ElementSymbol {
+        self.element
+    }
+
+    pub fn oxidation_state(&self) -> Option<i32> {
+        self.oxidation_state
+    }
+
+    pub fn source(&self) -> ElementSource {
+        self.source
+    }
+
+    /// The one-letter element the symbol could also stand for when its case
+    /// does not tell, as `C` for `CL3`, where the two-letter `Cl` is taken.
+    pub fn alternative(&self) -> Option<ElementSymbol> {
+        self.alternative
+    }
+
+    pub fn is_ambiguous(&self) -> bool {
+        self.alternative.is_some()
+    }
+
+    /// The covalent radius of the element in Ångström.
+    pub fn covalent_radius(&self) -> f64 {
+        ELEMENT_TABLE.get_by_symbol(self.element).covalent_radius()
+    }
+
+    /// The type symbol of the element with its oxidation state.
+    pub fn type_symbol(&self) -> TypeSymbol {
+        TypeSymbol::new(
+            TypeSymbolCode::ElementSymbol(self.element),
+            self.oxidation_state,
+        )
+    }
+}
+
+/// Resolve the element of an atom site from its type symbol, or from the
+/// label when the type symbol is absent or names no element. `None` when
+/// neither starts with an element symbol.
+pub fn resolve_element(type_symbol: Option<&str>, label: &str) -> Option<ResolvedElement> {
+    let from_type_symbol = type_symbol.and_then(|symbol| {
+        let parsed = TypeSymbol::from_str(symbol).ok()?;
+        match parsed.code() {
+            TypeSymbolCode::ElementSymbol(element) => Some(ResolvedElement {
+                element: *element,
+                oxidation_state: parsed.oxidation_state(),
+                source: ElementSource::TypeSymbol,
+                alternative: None,
+            }),
+            TypeSymbolCode::Custom(_) => {
+                element_from_prefix(symbol, ElementSource::TypeSymbolPrefix)
+            }
+        }
+    });
+    from_type_symbol.or_else(|| element_from_prefix(label, ElementSource::LabelPrefix))
+}
+
+/// The element of the leading letters of a symbol. A two-letter element is
+/// preferred over a one-letter one; when the second letter is upper case,
+/// as in `CL3` or `NA1`, the one-letter reading is kept as the alternative.
+fn element_from_prefix(symbol: &str, source: ElementSource) -> Option<ResolvedElement> {
+    let mut letters = symbol.chars().take_while(|c| c.is_ascii_alphabetic());
+    let first = letters.next()?.to_ascii_uppercase();
+    let one_letter = ElementSymbol::from_str(&first.to_string()).ok();
+    let second = letters.next();
+    let two_letter = second.and_then(|second| {
+        ElementSymbol::from_str(&format!("{first}{}", second.to_ascii_lowercase())).ok()
+    });
+    let resolved = |element, alternative| {
+        Some(ResolvedElement {
+            element,
+            oxidation_state: None,
+            source,
+            alternative,
+        })
+    };
+    match (two_letter, one_letter) {
+        (Some(two), one) if second.is_some_and(|c| c.is_ascii_uppercase()) => resolved(two, one),
+        (Some(two), _) => resolved(two, None),
+        (None, Some(one)) => resolved(one, None),
+        (None, None) => None,
+    }
+}
+
+/// Resolve the element of every site of the `_atom_site_` loop with
+/// [`resolve_element`], in the order of the loop.
+pub fn resolve_site_elements(
+    data_block: &DataBlock,
+) -> Result<Vec<ResolvedElement>, DataReadError> {
+    let columns = data_block["atom_site_label"]
+        .as_multi_values()
+        .ok_or_else(|| DataReadError::MissingTag("atom_site_label".to_string()))?;
+    let label = find_column(columns, "atom_site_label")
+        .ok_or_else(|| DataReadError::MissingTag("atom_site_label".to_string()))?;
+    let type_symbol = find_column(columns, "atom_site_type_symbol");
+    (0..label.values().len())
+        .map(|row| {
+            let symbol = optional_char_string(type_symbol, row)?;
+            let label_text = required_text(label, row)?;
+            resolve_element(symbol.as_ref().map(|s| s.as_ref()), &label_text).ok_or_else(|| {
+                match symbol {
+                    Some(symbol) => DataReadError::invalid(
+                        "atom_site_type_symbol",
+                        Some(row),
+                        &Value::CharString(symbol),
+                    ),
+                    None => {
+                        DataReadError::invalid("atom_site_label", Some(row), &label.values()[row])
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use castep_periodic_table::element::ElementSymbol;
+
+    use crate::data_dict::core_cif::atom_site::TypeSymbol;
+
+    use super::{resolve_element, ElementSource};
+
+    #[test]
+    fn element_resolution() {
+        let resolve = |type_symbol: Option<&str>, label: &str| {
+            let resolved = resolve_element(type_symbol, label).unwrap();
+            (
+                resolved.element(),
+                resolved.oxidation_state(),
+                resolved.source(),
+                resolved.alternative(),
+            )
+        };
+        assert_eq!(
+            resolve(Some("Fe3+"), "Fe1"),
+            (ElementSymbol::Fe, Some(3), ElementSource::TypeSymbol, None)
+        );
+        assert_eq!(
+            resolve(Some("O2-"), "O1"),
+            (ElementSymbol::O, Some(-2), ElementSource::TypeSymbol, None)
+        );
+        assert_eq!(resolve(Some("Ow"), "O1W").0, ElementSymbol::O);
+        assert_eq!(
+            resolve(None, "C12A"),
+            (ElementSymbol::C, None, ElementSource::LabelPrefix, None)
+        );
+        assert_eq!(resolve(None, "O1W").0, ElementSymbol::O);
+        assert_eq!(resolve(None, "Cl3").0, ElementSymbol::Cl);
+        assert_eq!(resolve(None, "Co1").3, None);
+        assert_eq!(
+            resolve(None, "CL3"),
+            (
+                ElementSymbol::Cl,
+                None,
+                ElementSource::LabelPrefix,
+                Some(ElementSymbol::C)
+            )
+        );
+        assert!(resolve_element(None, "Q1").is_none());
+        assert!(resolve_element(None, "1").is_none());
+
+        let symbols = ["Fe3+", "O2-", "Na+", "Cl"].map(|s| s.parse::<TypeSymbol>().unwrap());
+        let written: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+        assert_eq!(written, ["Fe3+", "O2-", "Na1+", "Cl"]);
+    }
+}
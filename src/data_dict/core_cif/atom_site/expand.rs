@@ -0,0 +1,609 @@
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+};
+
+use crate::{
+    data_dict::{
+        core_cif::{
+            cell::{measured, CellParameters},
+            geom::SiteSymmetryCode,
+            space_group::{
+                periodic_distance, space_group_operators, wrap, CrystalSystemCif, ITNumber,
+                SpaceGroupItem, SpaceGroupLoopItem, SymmetryOperator,
+            },
+        },
+        reading::{find_column, find_loop, required_column, required_numeric, required_text},
+        DataReadError, LoopValueTerm, SingleValueTerm,
+    },
+    grammar::{
+        CharString, DataBlock, DataBlockHeading, DataBlockMember, DataItems, LoopColumn,
+        LoopColumns, Numeric, UnsignedInteger, Value,
+    },
+};
+
+/// Default distance in Å under which two symmetry images of a site are
+/// taken as the same atom.
+pub const DEFAULT_SITE_TOLERANCE: f64 = 0.01;
+
+/// `_atom_site_` columns which no longer hold after expansion.
+const SYMMETRY_DEPENDENT_COLUMNS: [&str; 7] = [
+    "atom_site_Cartn_x",
+    "atom_site_Cartn_y",
+    "atom_site_Cartn_z",
+    "atom_site_site_symmetry_multiplicity",
+    "atom_site_site_symmetry_order",
+    "atom_site_symmetry_multiplicity",
+    "atom_site_Wyckoff_symbol",
+];
+
+const FRACT_COLUMNS: [&str; 3] = [
+    "atom_site_fract_x",
+    "atom_site_fract_y",
+    "atom_site_fract_z",
+];
+
+/// Prefixes of the `_atom_site_aniso_` tensor columns, followed by one of
+/// [`ANISO_INDICES`].
+const ANISO_PREFIXES: [&str; 2] = ["atom_site_aniso_U_", "atom_site_aniso_B_"];
+const ANISO_INDICES: [&str; 6] = ["11", "22", "33", "12", "13", "23"];
+const ANISO_AXES: [(usize, usize); 6] = [(0, 0), (1, 1), (2, 2), (0, 1), (0, 2), (1, 2)];
+
+/// A symmetry image of a listed site.
+struct Image {
+    row: usize,
+    label: String,
+    /// Wrapped into `[0, 1)`.
+    position: [f64; 3],
+    fract: [Numeric; 3],
+    rotation: [[f64; 3]; 3],
+}
+
+/// The listed sites and all their images.
+struct Expansion {
+    rows: HashMap<String, usize>,
+    positions: Vec<[f64; 3]>,
+    images: Vec<Image>,
+}
+
+impl Expansion {
+    fn images_of(&self, row: usize) -> impl Iterator<Item = &Image> {
+        self.images.iter().filter(move |image| image.row == row)
+    }
+
+    /// The image at the position given by the site symmetry code, with the
+    /// lattice translation from it.
+    fn image_at(
+        &self,
+        label: &str,
+        code: &SiteSymmetryCode,
+        operator: &SymmetryOperator,
+        metric: &[[f64; 3]; 3],
+    ) -> Option<(&Image, [i32; 3])> {
+        let row = *self.rows.get(label)?;
+        let image = operator.apply(&self.positions[row]);
+        let position: [f64; 3] = std::array::from_fn(|i| image[i] + code.translation()[i] as f64);
+        let wrapped = position.map(wrap);
+        let nearest = self.images_of(row).min_by(|a, b| {
+            periodic_distance(metric, &wrapped, &a.position).total_cmp(&periodic_distance(
+                metric,
+                &wrapped,
+                &b.position,
+            ))
+        })?;
+        let translation =
+            std::array::from_fn(|i| (position[i] - nearest.position[i]).round() as i32);
+        Some((nearest, translation))
+    }
+}
+
+/// Expand the atom sites of the data block to the full unit cell, as in
+/// space group P1.
+///
+/// Every symmetry operator of [`space_group_operators`] is applied to each
+/// site and the result wrapped into `[0, 1)`. Images closer than
+/// `tolerance` Å to an earlier image of the same site, as on special
+/// positions, are merged. The first image keeps the label, later ones are
+/// labelled `<label>_2`, `<label>_3`..., skipping labels already in use.
+/// The space group items are replaced by those of P1.
+///
+/// Anisotropic displacement parameters are rotated with their site and
+/// listed under the image labels. Atoms of `_geom_` loops with a site
+/// symmetry column are renamed to the image they refer to, the code
+/// keeping only the lattice translation. Distances and displacement
+/// parameters are taken in fractional units when the cell is not given.
+pub fn expand_to_p1<'a>(
+    data_block: &DataBlock<'a>,
+    tolerance: f64,
+) -> Result<DataBlock<'a>, DataReadError> {
+    let operators = space_group_operators(data_block)?;
+    let columns = find_loop(data_block, "atom_site_label")
+        .ok_or_else(|| DataReadError::MissingTag("atom_site_label".to_string()))?;
+    let cell = CellParameters::from_data_block(data_block).ok();
+    let metric = cell.as_ref().map_or(
+        [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        |cell| cell.metric_tensor(),
+    );
+    let reciprocal = cell.as_ref().map_or([1.0; 3], |cell| {
+        cell.reciprocal_lengths().map(|length| length.as_f64())
+    });
+    let expansion = site_images(columns, &operators, &metric, tolerance)?;
+    let site_rows: Vec<(usize, &Image)> = expansion
+        .images
+        .iter()
+        .map(|image| (image.row, image))
+        .collect();
+    let atom_sites = DataItems::MultiValues(expanded_columns(
+        columns,
+        "atom_site_label",
+        &site_rows,
+        &reciprocal,
+    )?);
+
+    let mut members = Vec::new();
+    let mut p1_position = None;
+    for member in data_block.members() {
+        match member {
+            DataBlockMember::DataItems(DataItems::SingleValue(item))
+                if is_space_group_tag(item.tag().as_ref()) =>
+            {
+                p1_position.get_or_insert(members.len());
+            }
+            DataBlockMember::DataItems(DataItems::MultiValues(loop_columns)) => {
+                let has_column = |tag: &str| find_column(loop_columns, tag).is_some();
+                let mut tags = loop_columns.columns().iter().map(|c| c.tag().as_ref());
+                let item = if has_column("atom_site_label") {
+                    atom_sites.clone()
+                } else if tags.any(is_space_group_tag) {
+                    p1_position.get_or_insert(members.len());
+                    continue;
+                } else if has_column("atom_site_aniso_label") {
+                    DataItems::MultiValues(expanded_aniso(loop_columns, &expansion, &reciprocal)?)
+                } else if loop_columns
+                    .columns()
+                    .iter()
+                    .any(|c| is_geom_tag(c.tag().as_ref()))
+                {
+                    DataItems::MultiValues(relabelled_geom(
+                        loop_columns,
+                        &expansion,
+                        &operators,
+                        &metric,
+                    )?)
+                } else {
+                    DataItems::MultiValues(loop_columns.clone())
+                };
+                members.push(DataBlockMember::DataItems(item));
+            }
+            member => members.push(member.clone()),
+        }
+    }
+    let p1_position = p1_position.unwrap_or(members.len());
+    members.splice(
+        p1_position..p1_position,
+        p1_space_group_items()
+            .into_iter()
+            .map(DataBlockMember::DataItems),
+    );
+    Ok(DataBlock::from_heading_members((
+        DataBlockHeading::new(data_block.heading().to_string()),
+        members,
+    )))
+}
+
+fn has_prefix(tag: &str, prefix: &str) -> bool {
+    tag.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+fn is_space_group_tag(tag: &str) -> bool {
+    has_prefix(tag, "space_group_") || has_prefix(tag, "symmetry_")
+}
+
+fn is_geom_tag(tag: &str) -> bool {
+    has_prefix(tag, "geom_")
+}
+
+fn site_images(
+    columns: &LoopColumns,
+    operators: &[SymmetryOperator],
+    metric: &[[f64; 3]; 3],
+    tolerance: f64,
+) -> Result<Expansion, DataReadError> {
+    let label = required_column(columns, "atom_site_label")?;
+    let fract = [
+        required_column(columns, FRACT_COLUMNS[0])?,
+        required_column(columns, FRACT_COLUMNS[1])?,
+        required_column(columns, FRACT_COLUMNS[2])?,
+    ];
+    let labels = (0..label.values().len())
+        .map(|row| required_text(label, row))
+        .collect::<Result<Vec<String>, DataReadError>>()?;
+    let mut taken: HashSet<String> = labels.iter().cloned().collect();
+    let mut expansion = Expansion {
+        rows: HashMap::new(),
+        positions: Vec::new(),
+        images: Vec::new(),
+    };
+    for (row, site_label) in labels.into_iter().enumerate() {
+        let coord = [
+            required_numeric(fract[0], row)?,
+            required_numeric(fract[1], row)?,
+            required_numeric(fract[2], row)?,
+        ];
+        let position = coord.map(|x| x.as_f64());
+        let su = coord.map(|x| x.su_value().unwrap_or(0.0));
+        let mut positions: Vec<[f64; 3]> = Vec::new();
+        let mut suffix = 1;
+        for operator in operators {
+            let image = operator.apply(&position).map(wrap);
+            if positions
+                .iter()
+                .any(|other| periodic_distance(metric, &image, other) < tolerance)
+            {
+                continue;
+            }
+            positions.push(image);
+            let rotation = operator.rotation();
+            let fract = std::array::from_fn(|i| {
+                let su = rotation[i]
+                    .iter()
+                    .zip(su.iter())
+                    .map(|(r, s)| (r * s).powi(2))
+                    .sum::<f64>()
+                    .sqrt();
+                measured(
+                    image[i],
+                    su,
+                    exact_decimals(image[i], coord[i].decimal_places()),
+                )
+            });
+            let label = if positions.len() == 1 {
+                site_label.clone()
+            } else {
+                loop {
+                    suffix += 1;
+                    let label = format!("{site_label}_{suffix}");
+                    if taken.insert(label.clone()) {
+                        break label;
+                    }
+                }
+            };
+            expansion.images.push(Image {
+                row,
+                label,
+                position: image,
+                fract,
+                rotation,
+            });
+        }
+        expansion.rows.entry(site_label).or_insert(row);
+        expansion.positions.push(position);
+    }
+    Ok(expansion)
+}
+
+/// At least `decimals` places, more when needed to write `x` exactly, as for
+/// `0.5` generated from `0` by a translation.
+fn exact_decimals(x: f64, decimals: usize) -> usize {
+    (decimals..decimals.max(6))
+        .find(|&d| {
+            let scale = 10_f64.powi(d as i32);
+            ((x * scale).round() / scale - x).abs() < 1e-9
+        })
+        .unwrap_or(decimals.max(6))
+}
+
+/// One row per image, copied from `rows`, the loop row of each image, with
+/// the label, coordinates and anisotropic displacement parameters of the
+/// image.
+fn expanded_columns<'a>(
+    columns: &LoopColumns<'a>,
+    label_tag: &str,
+    rows: &[(usize, &Image)],
+    reciprocal: &[f64; 3],
+) -> Result<LoopColumns<'a>, DataReadError> {
+    let expanded = columns
+        .columns()
+        .iter()
+        .filter(|column| {
+            !SYMMETRY_DEPENDENT_COLUMNS
+                .iter()
+                .any(|tag| tag.eq_ignore_ascii_case(column.tag().as_ref()))
+        })
+        .map(|column| {
+            let tag = column.tag().as_ref();
+            let fract = FRACT_COLUMNS
+                .iter()
+                .position(|fract| fract.eq_ignore_ascii_case(tag));
+            let aniso = aniso_component(tag);
+            let values = rows
+                .iter()
+                .map(|&(row, image)| {
+                    let value = column.values().get(row).cloned().unwrap_or(Value::Unknown);
+                    Ok(match (fract, aniso) {
+                        _ if tag.eq_ignore_ascii_case(label_tag) => {
+                            Value::CharString(CharString::from(image.label.as_str()))
+                        }
+                        (Some(i), _) => Value::Numeric(image.fract[i]),
+                        (_, Some((prefix, k)))
+                            if !matches!(value, Value::Unknown | Value::Inapplicable) =>
+                        {
+                            let u =
+                                rotated_aniso(columns, prefix, row, &image.rotation, reciprocal)?;
+                            Value::Numeric(u[k])
+                        }
+                        _ => value,
+                    })
+                })
+                .collect::<Result<Vec<Value>, DataReadError>>()?;
+            Ok(LoopColumn::new(column.tag().clone(), values))
+        })
+        .collect::<Result<Vec<LoopColumn>, DataReadError>>()?;
+    Ok(LoopColumns::new(expanded))
+}
+
+/// The `_atom_site_aniso_` loop with a row for each image of its sites.
+fn expanded_aniso<'a>(
+    columns: &LoopColumns<'a>,
+    expansion: &Expansion,
+    reciprocal: &[f64; 3],
+) -> Result<LoopColumns<'a>, DataReadError> {
+    let label = required_column(columns, "atom_site_aniso_label")?;
+    let mut rows = Vec::new();
+    for row in 0..label.values().len() {
+        let site = expansion
+            .rows
+            .get(&required_text(label, row)?)
+            .ok_or_else(|| {
+                DataReadError::invalid("atom_site_aniso_label", Some(row), &label.values()[row])
+            })?;
+        rows.extend(expansion.images_of(*site).map(|image| (row, image)));
+    }
+    expanded_columns(columns, "atom_site_aniso_label", &rows, reciprocal)
+}
+
+/// The `(prefix, index)` of an `_atom_site_aniso_U_ij` or `_B_ij` tag, the
+/// index counting in the order of [`ANISO_INDICES`].
+fn aniso_component(tag: &str) -> Option<(&'static str, usize)> {
+    ANISO_PREFIXES.into_iter().find_map(|prefix| {
+        let ij = tag
+            .get(prefix.len()..)
+            .filter(|_| has_prefix(tag, prefix))?;
+        ANISO_INDICES
+            .iter()
+            .position(|index| *index == ij)
+            .map(|k| (prefix, k))
+    })
+}
+
+/// The displacement tensor of the row rotated as the fractional
+/// coordinates, `R U* Rᵀ` for `U* = N U N` with `N` the reciprocal cell
+/// lengths, with su propagated as for independent components.
+fn rotated_aniso(
+    columns: &LoopColumns,
+    prefix: &str,
+    row: usize,
+    rotation: &[[f64; 3]; 3],
+    reciprocal: &[f64; 3],
+) -> Result<[Numeric; 6], DataReadError> {
+    let mut u = [Numeric::from(0.0); 6];
+    for (k, ij) in ANISO_INDICES.iter().enumerate() {
+        u[k] = required_numeric(required_column(columns, &format!("{prefix}{ij}"))?, row)?;
+    }
+    let decimals = u.iter().map(Numeric::decimal_places).max().unwrap_or(0);
+    Ok(std::array::from_fn(|m| {
+        let (i, j) = ANISO_AXES[m];
+        let (value, variance) =
+            u.iter()
+                .zip(ANISO_AXES)
+                .fold((0.0, 0.0), |(value, variance), (component, (p, q))| {
+                    let mut coefficient = rotation[i][p] * rotation[j][q];
+                    if p != q {
+                        coefficient += rotation[i][q] * rotation[j][p];
+                    }
+                    coefficient *= reciprocal[p] * reciprocal[q] / (reciprocal[i] * reciprocal[j]);
+                    (
+                        value + coefficient * component.as_f64(),
+                        variance + (coefficient * component.su_value().unwrap_or(0.0)).powi(2),
+                    )
+                });
+        measured(value, variance.sqrt(), decimals)
+    }))
+}
+
+/// A `_geom_` loop with every atom that has a site symmetry column renamed
+/// to the image at its position, the code keeping only the lattice
+/// translation from that image, `.` for none.
+fn relabelled_geom<'a>(
+    columns: &LoopColumns<'a>,
+    expansion: &Expansion,
+    operators: &[SymmetryOperator],
+    metric: &[[f64; 3]; 3],
+) -> Result<LoopColumns<'a>, DataReadError> {
+    let mut relabelled = columns.columns().to_vec();
+    for (label_index, label) in columns.columns().iter().enumerate() {
+        let tag = label.tag().as_ref().to_ascii_lowercase();
+        let Some((prefix, atom)) = tag.split_once("_atom_site_label_") else {
+            continue;
+        };
+        let symmetry_tag = format!("{prefix}_site_symmetry_{atom}");
+        let Some(symmetry_index) = columns
+            .columns()
+            .iter()
+            .position(|column| column.tag().as_ref().eq_ignore_ascii_case(&symmetry_tag))
+        else {
+            continue;
+        };
+        let symmetry = &columns.columns()[symmetry_index];
+        let mut labels = Vec::new();
+        let mut codes = Vec::new();
+        for row in 0..label.values().len() {
+            let code = match symmetry.values().get(row) {
+                None | Some(Value::Unknown | Value::Inapplicable) => SiteSymmetryCode::identity(),
+                Some(value) => required_text(symmetry, row)?
+                    .parse::<SiteSymmetryCode>()
+                    .ok()
+                    .filter(|code| code.operator() <= operators.len())
+                    .ok_or_else(|| {
+                        DataReadError::invalid(symmetry.tag().as_ref(), Some(row), value)
+                    })?,
+            };
+            let operator = &operators[code.operator() - 1];
+            let (image, translation) = expansion
+                .image_at(&required_text(label, row)?, &code, operator, metric)
+                .ok_or_else(|| {
+                    DataReadError::invalid(label.tag().as_ref(), Some(row), &label.values()[row])
+                })?;
+            labels.push(Value::CharString(CharString::from(image.label.as_str())));
+            codes.push(match translation {
+                [0, 0, 0] => Value::Inapplicable,
+                translation => SiteSymmetryCode::new(NonZeroUsize::MIN, translation).into(),
+            });
+        }
+        relabelled[label_index] = LoopColumn::new(label.tag().clone(), labels);
+        relabelled[symmetry_index] = LoopColumn::new(symmetry.tag().clone(), codes);
+    }
+    Ok(LoopColumns::new(relabelled))
+}
+
+fn p1_space_group_items() -> Vec<DataItems<'static>> {
+    let symops = LoopColumns::new(vec![
+        SpaceGroupLoopItem::Symop_id(vec![UnsignedInteger(1)]).to_loop_column(),
+        SpaceGroupLoopItem::Symop_operation_xyz(vec![CharString::from(
+            SymmetryOperator::identity().to_string(),
+        )])
+        .to_loop_column(),
+    ]);
+    vec![
+        SpaceGroupItem::Crystal_system(CrystalSystemCif::Triclinic).to_single_value_data(),
+        SpaceGroupItem::IT_number(ITNumber::new(1)).to_single_value_data(),
+        SpaceGroupItem::Name_H_M_alt("P 1".to_string()).to_single_value_data(),
+        SpaceGroupItem::Name_Hall("P 1".to_string()).to_single_value_data(),
+        DataItems::MultiValues(symops),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data_dict::core_cif::space_group::resolve_space_group,
+        grammar::{DataBlock, SyntacticUnit},
+    };
+
+    use super::{expand_to_p1, DEFAULT_SITE_TOLERANCE};
+
+    #[test]
+    fn expand_p21_c() {
+        let mut input = r#"data_test
+_cell_length_a 5.0
+_cell_length_b 6.0
+_cell_length_c 7.0
+_cell_angle_alpha 90
+_cell_angle_beta 100
+_cell_angle_gamma 90
+_symmetry_space_group_name_H-M 'P 21/c'
+loop_
+_symmetry_equiv_pos_as_xyz
+x,y,z
+-x,y+1/2,-z+1/2
+-x,-y,-z
+x,-y+1/2,z+1/2
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+_atom_site_Wyckoff_symbol
+Fe1 Fe 0 0 0 a
+O1 O 0.1234(5) 0.25 0.8 e
+Fe1_2 Fe 0.5 0.5 0.5 b
+loop_
+_atom_site_aniso_label
+_atom_site_aniso_U_11
+_atom_site_aniso_U_22
+_atom_site_aniso_U_33
+_atom_site_aniso_U_12
+_atom_site_aniso_U_13
+_atom_site_aniso_U_23
+O1 0.010 0.020 0.030 0.001 0.002 0.003(1)
+loop_
+_geom_bond_atom_site_label_1
+_geom_bond_atom_site_label_2
+_geom_bond_site_symmetry_2
+_geom_bond_distance
+Fe1 O1 . 2.0
+Fe1 O1 2_656 2.1
+Fe1 O1 2_555 2.2
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let expanded = expand_to_p1(&block, DEFAULT_SITE_TOLERANCE).unwrap();
+        let sites = expanded["atom_site_label"].as_multi_values().unwrap();
+        let labels: Vec<String> = sites
+            .find_loop_column_by_tag("atom_site_label")
+            .unwrap()
+            .values()
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(
+            labels,
+            ["Fe1", "Fe1_3", "O1", "O1_2", "O1_3", "O1_4", "Fe1_2", "Fe1_2_2"]
+        );
+        let x: Vec<String> = sites
+            .find_loop_column_by_tag("atom_site_fract_x")
+            .unwrap()
+            .values()
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(x[..4], ["0", "0", "0.1234(5)", "0.8766(5)"]);
+        let y = sites.find_loop_column_by_tag("atom_site_fract_y").unwrap();
+        assert_eq!(y.values()[1].to_string(), "0.5");
+        assert!(sites
+            .find_loop_column_by_tag("atom_site_Wyckoff_symbol")
+            .is_none());
+        let aniso = expanded["atom_site_aniso_label"].as_multi_values().unwrap();
+        let column = |tag: &str| -> Vec<String> {
+            aniso
+                .find_loop_column_by_tag(tag)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+        assert_eq!(
+            column("atom_site_aniso_label"),
+            ["O1", "O1_2", "O1_3", "O1_4"]
+        );
+        assert_eq!(
+            column("atom_site_aniso_U_12"),
+            ["0.001", "-0.001", "0.001", "-0.001"]
+        );
+        assert_eq!(column("atom_site_aniso_U_13")[1], "0.002");
+        assert_eq!(column("atom_site_aniso_U_23")[1], "-0.0030(10)");
+        let bonds = expanded["geom_bond_distance"].as_multi_values().unwrap();
+        let column = |tag: &str| -> Vec<String> {
+            bonds
+                .find_loop_column_by_tag(tag)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+        assert_eq!(
+            column("geom_bond_atom_site_label_2"),
+            ["O1", "O1_2", "O1_2"]
+        );
+        assert_eq!(column("geom_bond_site_symmetry_2"), [".", ".", "1_454"]);
+        assert!(expanded["symmetry_equiv_pos_as_xyz"]
+            .as_multi_values()
+            .is_none());
+        let space_group = resolve_space_group(&expanded).unwrap();
+        assert_eq!(space_group.it_number().number(), 1);
+        assert!(space_group.conflicts().is_empty());
+    }
+}
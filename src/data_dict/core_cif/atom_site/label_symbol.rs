@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{convert::Infallible, fmt::Display, str::FromStr};
 
 use castep_periodic_table::element::ElementSymbol;
 
@@ -32,36 +32,83 @@ impl TypeSymbol {
             oxidation_state,
         }
     }
+
+    pub fn code(&self) -> &TypeSymbolCode {
+        &self.code
+    }
+
+    pub fn oxidation_state(&self) -> Option<i32> {
+        self.oxidation_state
+    }
+}
+
+impl FromStr for TypeSymbol {
+    type Err = Infallible;
+
+    /// Symbols matching an element, optionally followed by an oxidation
+    /// state like `Fe3+`, `O2-` or `Na+`, are stored as
+    /// `TypeSymbolCode::ElementSymbol`; everything else is kept verbatim as
+    /// `TypeSymbolCode::Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(s.len());
+        let (element, suffix) = s.split_at(split);
+        let symbol = ElementSymbol::from_str(element)
+            .ok()
+            .and_then(|element| Some((element, oxidation_state(suffix)?)));
+        Ok(match symbol {
+            Some((element, oxidation_state)) => {
+                TypeSymbol::new(TypeSymbolCode::ElementSymbol(element), oxidation_state)
+            }
+            None => TypeSymbol::new(TypeSymbolCode::Custom(s.to_string()), None),
+        })
+    }
+}
+
+/// Oxidation state of a suffix like `3+`, `2-` or `+`; `Some(None)` for an
+/// empty suffix and `None` when the suffix is not an oxidation state.
+fn oxidation_state(suffix: &str) -> Option<Option<i32>> {
+    if suffix.is_empty() {
+        return Some(None);
+    }
+    let (magnitude, sign) = suffix.split_at(suffix.len() - 1);
+    let sign = match sign {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let magnitude = match magnitude {
+        "" => 1,
+        digits => digits.parse::<i32>().ok()?,
+    };
+    Some(Some(sign * magnitude))
 }
 
 impl Display for TypeSymbol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let oxidation_state = if let Some(i) = self.oxidation_state {
-            if i > 0 {
-                format!("{i}+")
-            } else {
-                format!("{i}-")
-            }
-        } else {
-            String::new()
+        let oxidation_state = match self.oxidation_state {
+            Some(i) if i > 0 => format!("{i}+"),
+            Some(i) if i < 0 => format!("{}-", -i),
+            _ => String::new(),
         };
         write!(f, "{}{}", self.code, oxidation_state)
     }
 }
 
-impl From<TypeSymbol> for UnquotedString {
+impl From<TypeSymbol> for UnquotedString<'_> {
     fn from(value: TypeSymbol) -> Self {
         UnquotedString::new(value.to_string())
     }
 }
 
-impl From<TypeSymbol> for CharString {
+impl From<TypeSymbol> for CharString<'_> {
     fn from(value: TypeSymbol) -> Self {
         CharString::Unquoted(UnquotedString::from(value))
     }
 }
 
-impl From<TypeSymbol> for Value {
+impl From<TypeSymbol> for Value<'_> {
     fn from(value: TypeSymbol) -> Self {
         Value::CharString(CharString::from(value))
     }
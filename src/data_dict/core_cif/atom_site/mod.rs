@@ -1,38 +1,45 @@
-use crate::data_dict::{CifTerm, LoopValueTerm};
-use crate::grammar::{CharString, Float, Numeric, Tag, Value};
+use crate::data_dict::{reading::value_or_unknown, CifTerm, LoopValueTerm};
+use crate::grammar::{CharString, Numeric, Tag, UnsignedInteger, Value};
 
-use self::adp_type::AdpType;
-use self::label_symbol::TypeSymbol;
 use self::symmetry_multiplicity::SymMultiplicity;
 
+pub use self::adp_type::AdpType;
+pub use self::element::{resolve_element, resolve_site_elements, ElementSource, ResolvedElement};
+pub use self::expand::{expand_to_p1, DEFAULT_SITE_TOLERANCE};
+pub use self::label_symbol::{TypeSymbol, TypeSymbolCode};
+pub use self::site_symmetry::assign_site_symmetry;
+
 mod adp_type;
 #[cfg(feature = "chemrust-core")]
 pub mod chemrust_impl;
+mod element;
+mod expand;
 mod label_symbol;
+mod site_symmetry;
 mod symmetry_multiplicity;
 
 #[allow(clippy::upper_case_acronyms, non_camel_case_types)]
 #[derive(Debug, Clone)]
 pub enum AtomSiteLoopItem {
-    Adp_type(Vec<AdpType>),
-    Aniso_B_11,
-    Aniso_B_12,
-    Aniso_B_13,
-    Aniso_B_22,
-    Aniso_B_23,
-    Aniso_B_33,
-    Aniso_label,
+    Adp_type(Vec<Option<AdpType>>),
+    Aniso_B_11(Vec<Numeric>),
+    Aniso_B_12(Vec<Numeric>),
+    Aniso_B_13(Vec<Numeric>),
+    Aniso_B_22(Vec<Numeric>),
+    Aniso_B_23(Vec<Numeric>),
+    Aniso_B_33(Vec<Numeric>),
+    Aniso_label(Vec<CharString<'static>>),
     Aniso_ratio,
     Aniso_type_symbol(Vec<TypeSymbol>),
-    Aniso_U_11,
-    Aniso_U_12,
-    Aniso_U_13,
-    Aniso_U_22,
-    Aniso_U_23,
-    Aniso_U_33,
+    Aniso_U_11(Vec<Numeric>),
+    Aniso_U_12(Vec<Numeric>),
+    Aniso_U_13(Vec<Numeric>),
+    Aniso_U_22(Vec<Numeric>),
+    Aniso_U_23(Vec<Numeric>),
+    Aniso_U_33(Vec<Numeric>),
     Attached_hydrogens,
     B_equiv_geom_mean,
-    B_iso_or_equiv,
+    B_iso_or_equiv(Vec<Option<Numeric>>),
     Calc_attached_atom,
     Calc_flag,
     Cartn_x(Vec<Numeric>),
@@ -41,12 +48,12 @@ pub enum AtomSiteLoopItem {
     Chemical_conn_number,
     Constraints,
     Description,
-    Disorder_assembly,
-    Disorder_group,
+    Disorder_assembly(Vec<Option<String>>),
+    Disorder_group(Vec<Option<String>>),
     Fract_x(Vec<Numeric>),
     Fract_y(Vec<Numeric>),
     Fract_z(Vec<Numeric>),
-    Label(Vec<CharString>),
+    Label(Vec<CharString<'static>>),
     Label_component_0,
     Label_component_1,
     Label_component_2,
@@ -54,44 +61,44 @@ pub enum AtomSiteLoopItem {
     Label_component_4,
     Label_component_5,
     Label_component_6,
-    Occupancy(Vec<Float>),
+    Occupancy(Vec<Numeric>),
     Refinement_flags,
     Refinement_flags_adp,
     Refinement_flags_occupancy,
     Refinement_flags_posn,
     Restraints,
     Site_symmetry_multiplicity(Vec<SymMultiplicity>),
-    Site_symmetry_order,
+    Site_symmetry_order(Vec<UnsignedInteger>),
     Symmetry_multiplicity(Vec<SymMultiplicity>),
     Thermal_displace_type,
     Type_symbol(Vec<TypeSymbol>),
     U_equiv_geom_mean,
-    U_iso_or_equiv(Vec<Float>),
-    Wyckoff_symbol,
+    U_iso_or_equiv(Vec<Option<Numeric>>),
+    Wyckoff_symbol(Vec<Option<char>>),
 }
 
 impl CifTerm for AtomSiteLoopItem {
-    fn tag(&self) -> Tag {
+    fn tag(&self) -> Tag<'static> {
         let suffix = match self {
             AtomSiteLoopItem::Adp_type(_) => "adp_type",
-            AtomSiteLoopItem::Aniso_B_11 => "aniso_B_11",
-            AtomSiteLoopItem::Aniso_B_12 => "aniso_B_12",
-            AtomSiteLoopItem::Aniso_B_13 => "aniso_B_13",
-            AtomSiteLoopItem::Aniso_B_22 => "aniso_B_22",
-            AtomSiteLoopItem::Aniso_B_23 => "aniso_B_23",
-            AtomSiteLoopItem::Aniso_B_33 => "aniso_B_33",
-            AtomSiteLoopItem::Aniso_label => "aniso_label",
+            AtomSiteLoopItem::Aniso_B_11(_) => "aniso_B_11",
+            AtomSiteLoopItem::Aniso_B_12(_) => "aniso_B_12",
+            AtomSiteLoopItem::Aniso_B_13(_) => "aniso_B_13",
+            AtomSiteLoopItem::Aniso_B_22(_) => "aniso_B_22",
+            AtomSiteLoopItem::Aniso_B_23(_) => "aniso_B_23",
+            AtomSiteLoopItem::Aniso_B_33(_) => "aniso_B_33",
+            AtomSiteLoopItem::Aniso_label(_) => "aniso_label",
             AtomSiteLoopItem::Aniso_ratio => "aniso_ratio",
             AtomSiteLoopItem::Aniso_type_symbol(_) => "aniso_type_symbol",
-            AtomSiteLoopItem::Aniso_U_11 => "aniso_U_11",
-            AtomSiteLoopItem::Aniso_U_12 => "aniso_U_12",
-            AtomSiteLoopItem::Aniso_U_13 => "aniso_U_13",
-            AtomSiteLoopItem::Aniso_U_22 => "aniso_U_22",
-            AtomSiteLoopItem::Aniso_U_23 => "aniso_U_23",
-            AtomSiteLoopItem::Aniso_U_33 => "aniso_U_33",
+            AtomSiteLoopItem::Aniso_U_11(_) => "aniso_U_11",
+            AtomSiteLoopItem::Aniso_U_12(_) => "aniso_U_12",
+            AtomSiteLoopItem::Aniso_U_13(_) => "aniso_U_13",
+            AtomSiteLoopItem::Aniso_U_22(_) => "aniso_U_22",
+            AtomSiteLoopItem::Aniso_U_23(_) => "aniso_U_23",
+            AtomSiteLoopItem::Aniso_U_33(_) => "aniso_U_33",
             AtomSiteLoopItem::Attached_hydrogens => "attached_hydrogens",
             AtomSiteLoopItem::B_equiv_geom_mean => "b_equiv_geom_mean",
-            AtomSiteLoopItem::B_iso_or_equiv => "b_iso_or_equiv",
+            AtomSiteLoopItem::B_iso_or_equiv(_) => "B_iso_or_equiv",
             AtomSiteLoopItem::Calc_attached_atom => "calc_attached_atom",
             AtomSiteLoopItem::Calc_flag => "calc_flag",
             AtomSiteLoopItem::Cartn_x(_) => "cartn_x",
@@ -100,8 +107,8 @@ impl CifTerm for AtomSiteLoopItem {
             AtomSiteLoopItem::Chemical_conn_number => "chemical_conn_number",
             AtomSiteLoopItem::Constraints => "constraints",
             AtomSiteLoopItem::Description => "description",
-            AtomSiteLoopItem::Disorder_assembly => "disorder_assembly",
-            AtomSiteLoopItem::Disorder_group => "disorder_group",
+            AtomSiteLoopItem::Disorder_assembly(_) => "disorder_assembly",
+            AtomSiteLoopItem::Disorder_group(_) => "disorder_group",
             AtomSiteLoopItem::Fract_x(_) => "fract_x",
             AtomSiteLoopItem::Fract_y(_) => "fract_y",
             AtomSiteLoopItem::Fract_z(_) => "fract_z",
@@ -120,42 +127,41 @@ impl CifTerm for AtomSiteLoopItem {
             AtomSiteLoopItem::Refinement_flags_posn => "refinement_flags_posn",
             AtomSiteLoopItem::Restraints => "restraints",
             AtomSiteLoopItem::Site_symmetry_multiplicity(_) => "site_symmetry_multiplicity",
-            AtomSiteLoopItem::Site_symmetry_order => "site_symmetry_order",
+            AtomSiteLoopItem::Site_symmetry_order(_) => "site_symmetry_order",
             AtomSiteLoopItem::Symmetry_multiplicity(_) => "symmetry_multiplicity",
             AtomSiteLoopItem::Thermal_displace_type => "thermal_displace_type",
             AtomSiteLoopItem::Type_symbol(_) => "type_symbol",
             AtomSiteLoopItem::U_equiv_geom_mean => "u_equiv_geom_mean",
-            AtomSiteLoopItem::U_iso_or_equiv(_) => "u_iso_or_equiv",
-            AtomSiteLoopItem::Wyckoff_symbol => "wyckoff_symbol",
+            AtomSiteLoopItem::U_iso_or_equiv(_) => "U_iso_or_equiv",
+            AtomSiteLoopItem::Wyckoff_symbol(_) => "Wyckoff_symbol",
         };
         Tag::new(format!("atom_site_{suffix}"))
     }
 }
 
 impl LoopValueTerm for AtomSiteLoopItem {
-    fn values(&self) -> Vec<Value> {
+    fn values(&self) -> Vec<Value<'static>> {
         match self {
-            AtomSiteLoopItem::Adp_type(v) => v.iter().cloned().map(Value::from).collect(),
-            AtomSiteLoopItem::Aniso_B_11 => todo!(),
-            AtomSiteLoopItem::Aniso_B_12 => todo!(),
-            AtomSiteLoopItem::Aniso_B_13 => todo!(),
-            AtomSiteLoopItem::Aniso_B_22 => todo!(),
-            AtomSiteLoopItem::Aniso_B_23 => todo!(),
-            AtomSiteLoopItem::Aniso_B_33 => todo!(),
-            AtomSiteLoopItem::Aniso_label => todo!(),
+            AtomSiteLoopItem::Adp_type(v) => v.iter().copied().map(value_or_unknown).collect(),
+            AtomSiteLoopItem::Aniso_B_11(v)
+            | AtomSiteLoopItem::Aniso_B_12(v)
+            | AtomSiteLoopItem::Aniso_B_13(v)
+            | AtomSiteLoopItem::Aniso_B_22(v)
+            | AtomSiteLoopItem::Aniso_B_23(v)
+            | AtomSiteLoopItem::Aniso_B_33(v)
+            | AtomSiteLoopItem::Aniso_U_11(v)
+            | AtomSiteLoopItem::Aniso_U_12(v)
+            | AtomSiteLoopItem::Aniso_U_13(v)
+            | AtomSiteLoopItem::Aniso_U_22(v)
+            | AtomSiteLoopItem::Aniso_U_23(v)
+            | AtomSiteLoopItem::Aniso_U_33(v) => v.iter().copied().map(Value::from).collect(),
+            AtomSiteLoopItem::Aniso_label(s) => s.iter().cloned().map(Value::from).collect(),
             AtomSiteLoopItem::Aniso_ratio => todo!(),
             AtomSiteLoopItem::Type_symbol(symbol) | AtomSiteLoopItem::Aniso_type_symbol(symbol) => {
                 symbol.iter().cloned().map(Value::from).collect()
             }
-            AtomSiteLoopItem::Aniso_U_11 => todo!(),
-            AtomSiteLoopItem::Aniso_U_12 => todo!(),
-            AtomSiteLoopItem::Aniso_U_13 => todo!(),
-            AtomSiteLoopItem::Aniso_U_22 => todo!(),
-            AtomSiteLoopItem::Aniso_U_23 => todo!(),
-            AtomSiteLoopItem::Aniso_U_33 => todo!(),
             AtomSiteLoopItem::Attached_hydrogens => todo!(),
             AtomSiteLoopItem::B_equiv_geom_mean => todo!(),
-            AtomSiteLoopItem::B_iso_or_equiv => todo!(),
             AtomSiteLoopItem::Calc_attached_atom => todo!(),
             AtomSiteLoopItem::Calc_flag => todo!(),
             AtomSiteLoopItem::Cartn_x(v)
@@ -167,8 +173,11 @@ impl LoopValueTerm for AtomSiteLoopItem {
             AtomSiteLoopItem::Chemical_conn_number => todo!(),
             AtomSiteLoopItem::Constraints => todo!(),
             AtomSiteLoopItem::Description => todo!(),
-            AtomSiteLoopItem::Disorder_assembly => todo!(),
-            AtomSiteLoopItem::Disorder_group => todo!(),
+            AtomSiteLoopItem::Disorder_assembly(codes)
+            | AtomSiteLoopItem::Disorder_group(codes) => codes
+                .iter()
+                .map(|code| value_or_unknown(code.as_deref().map(CharString::from)))
+                .collect(),
             AtomSiteLoopItem::Label(s) => s.iter().cloned().map(Value::from).collect(),
             AtomSiteLoopItem::Label_component_0 => todo!(),
             AtomSiteLoopItem::Label_component_1 => todo!(),
@@ -177,9 +186,10 @@ impl LoopValueTerm for AtomSiteLoopItem {
             AtomSiteLoopItem::Label_component_4 => todo!(),
             AtomSiteLoopItem::Label_component_5 => todo!(),
             AtomSiteLoopItem::Label_component_6 => todo!(),
-            AtomSiteLoopItem::U_iso_or_equiv(f) | AtomSiteLoopItem::Occupancy(f) => {
-                f.iter().cloned().map(Value::from).collect()
+            AtomSiteLoopItem::B_iso_or_equiv(f) | AtomSiteLoopItem::U_iso_or_equiv(f) => {
+                f.iter().copied().map(value_or_unknown).collect()
             }
+            AtomSiteLoopItem::Occupancy(f) => f.iter().copied().map(Value::from).collect(),
             AtomSiteLoopItem::Refinement_flags => todo!(),
             AtomSiteLoopItem::Refinement_flags_adp => todo!(),
             AtomSiteLoopItem::Refinement_flags_occupancy => todo!(),
@@ -189,10 +199,19 @@ impl LoopValueTerm for AtomSiteLoopItem {
             | AtomSiteLoopItem::Symmetry_multiplicity(m) => {
                 m.iter().cloned().map(Value::from).collect()
             }
-            AtomSiteLoopItem::Site_symmetry_order => todo!(),
+            AtomSiteLoopItem::Site_symmetry_order(v) => {
+                v.iter().cloned().map(Value::from).collect()
+            }
             AtomSiteLoopItem::Thermal_displace_type => todo!(),
             AtomSiteLoopItem::U_equiv_geom_mean => todo!(),
-            AtomSiteLoopItem::Wyckoff_symbol => todo!(),
+            AtomSiteLoopItem::Wyckoff_symbol(letters) => letters
+                .iter()
+                .map(|letter| {
+                    letter.map_or(Value::Unknown, |letter| {
+                        Value::CharString(CharString::from(letter.to_string()))
+                    })
+                })
+                .collect(),
         }
     }
 }
@@ -0,0 +1,291 @@
+use crate::{
+    data_dict::{
+        core_cif::space_group::{SiteSymmetry, SiteSymmetryFinder},
+        reading::{find_column, required_column, required_numeric},
+        DataReadError, LoopValueTerm,
+    },
+    grammar::{
+        DataBlock, DataBlockHeading, DataBlockMember, DataItems, LoopColumn, LoopColumns,
+        UnsignedInteger,
+    },
+};
+
+use super::{symmetry_multiplicity::SymMultiplicity, AtomSiteLoopItem};
+
+/// Fill `_atom_site_symmetry_multiplicity`, `_atom_site_site_symmetry_order`
+/// and `_atom_site_Wyckoff_symbol` of every atom site from its fractional
+/// coordinates and the symmetry of the data block, as found by
+/// [`SiteSymmetryFinder::from_data_block`]. Existing values of these columns
+/// are replaced. The Wyckoff symbol is `?` for sites without a tabulated
+/// position, as in space groups or settings missing from the table.
+pub fn assign_site_symmetry<'a>(
+    data_block: &DataBlock<'a>,
+    tolerance: f64,
+) -> Result<DataBlock<'a>, DataReadError> {
+    let finder = SiteSymmetryFinder::from_data_block(data_block, tolerance)?;
+    let members = data_block
+        .members()
+        .iter()
+        .map(|member| match member {
+            DataBlockMember::DataItems(DataItems::MultiValues(columns))
+                if find_column(columns, "atom_site_label").is_some() =>
+            {
+                let sites = site_symmetries(&finder, columns)?;
+                Ok(DataBlockMember::DataItems(DataItems::MultiValues(
+                    with_site_symmetry_columns(columns, &sites),
+                )))
+            }
+            member => Ok(member.clone()),
+        })
+        .collect::<Result<Vec<DataBlockMember>, DataReadError>>()?;
+    Ok(DataBlock::from_heading_members((
+        DataBlockHeading::new(data_block.heading().to_string()),
+        members,
+    )))
+}
+
+/// The site symmetry of each row, with its Wyckoff letter when known.
+fn site_symmetries(
+    finder: &SiteSymmetryFinder,
+    columns: &LoopColumns,
+) -> Result<Vec<(SiteSymmetry, Option<char>)>, DataReadError> {
+    let fract = [
+        required_column(columns, "atom_site_fract_x")?,
+        required_column(columns, "atom_site_fract_y")?,
+        required_column(columns, "atom_site_fract_z")?,
+    ];
+    (0..fract[0].values().len())
+        .map(|row| {
+            let position = [
+                required_numeric(fract[0], row)?.as_f64(),
+                required_numeric(fract[1], row)?.as_f64(),
+                required_numeric(fract[2], row)?.as_f64(),
+            ];
+            let site = finder.site_symmetry(&position);
+            let letter = site.wyckoff_letter().ok();
+            Ok((site, letter))
+        })
+        .collect()
+}
+
+/// The loop with the site symmetry columns replaced in place, or appended
+/// when missing.
+fn with_site_symmetry_columns<'a>(
+    columns: &LoopColumns<'a>,
+    sites: &[(SiteSymmetry, Option<char>)],
+) -> LoopColumns<'a> {
+    let mut new_columns: Vec<LoopColumn<'a>> = [
+        AtomSiteLoopItem::Symmetry_multiplicity(
+            sites
+                .iter()
+                .map(|(site, _)| SymMultiplicity::new(site.multiplicity() as u8))
+                .collect(),
+        ),
+        AtomSiteLoopItem::Site_symmetry_order(
+            sites
+                .iter()
+                .map(|(site, _)| UnsignedInteger(site.order() as u32))
+                .collect(),
+        ),
+        AtomSiteLoopItem::Wyckoff_symbol(sites.iter().map(|(_, letter)| *letter).collect()),
+    ]
+    .into_iter()
+    .map(|item| item.to_loop_column())
+    .collect();
+    let mut loop_columns: Vec<LoopColumn> = columns
+        .columns()
+        .iter()
+        .map(|column| {
+            let tag = column.tag().as_ref();
+            match new_columns
+                .iter()
+                .position(|new| new.tag().as_ref().eq_ignore_ascii_case(tag))
+            {
+                Some(i) => new_columns.remove(i),
+                None => column.clone(),
+            }
+        })
+        .collect();
+    loop_columns.extend(new_columns);
+    LoopColumns::new(loop_columns)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data_dict::core_cif::{atom_site::DEFAULT_SITE_TOLERANCE, space_group::generate_group},
+        data_dict::DataReadError,
+        grammar::{DataBlock, SyntacticUnit},
+    };
+
+    use super::assign_site_symmetry;
+
+    /// `(multiplicity, order, Wyckoff symbol)` assigned to each site of a
+    /// block with the given cell, space group and generated operators.
+    fn assigned(
+        cell: [&str; 6],
+        space_group: &str,
+        generators: &[&str],
+        sites: &str,
+    ) -> Result<Vec<(String, String, String)>, DataReadError> {
+        let operators: Vec<String> = generate_group(generators)
+            .iter()
+            .map(|operator| format!("'{operator}'"))
+            .collect();
+        let input = format!(
+            "data_test
+_cell_length_a {}
+_cell_length_b {}
+_cell_length_c {}
+_cell_angle_alpha {}
+_cell_angle_beta {}
+_cell_angle_gamma {}
+_space_group_name_H-M_alt '{space_group}'
+loop_
+_space_group_symop_operation_xyz
+{}
+loop_
+_atom_site_label
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+{sites}",
+            cell[0],
+            cell[1],
+            cell[2],
+            cell[3],
+            cell[4],
+            cell[5],
+            operators.join("\n"),
+        );
+        let block = DataBlock::parse_str(&mut input.as_str()).unwrap();
+        let assigned = assign_site_symmetry(&block, DEFAULT_SITE_TOLERANCE)?;
+        let sites = assigned["atom_site_label"].as_multi_values().unwrap();
+        let column = |tag: &str| -> Vec<String> {
+            sites
+                .find_loop_column_by_tag(tag)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+        let [multiplicities, orders, letters] = [
+            "atom_site_symmetry_multiplicity",
+            "atom_site_site_symmetry_order",
+            "atom_site_Wyckoff_symbol",
+        ]
+        .map(column);
+        Ok(multiplicities
+            .into_iter()
+            .zip(orders)
+            .zip(letters)
+            .map(|((multiplicity, order), letter)| (multiplicity, order, letter))
+            .collect())
+    }
+
+    fn row(multiplicity: &str, order: &str, letter: &str) -> (String, String, String) {
+        (multiplicity.into(), order.into(), letter.into())
+    }
+
+    #[test]
+    fn assign_p21_c() {
+        let mut input = r#"data_test
+_cell_length_a 5.0
+_cell_length_b 6.0
+_cell_length_c 7.0
+_cell_angle_alpha 90
+_cell_angle_beta 100
+_cell_angle_gamma 90
+_space_group_name_H-M_alt 'P 1 21/c 1'
+loop_
+_space_group_symop_operation_xyz
+x,y,z
+-x,y+1/2,-z+1/2
+-x,-y,-z
+x,-y+1/2,z+1/2
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+_atom_site_wyckoff_symbol
+Fe1 Fe 0.5 0 0.5 ?
+O1 O 0.1234(5) 0.25 0.8 ?
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let assigned = assign_site_symmetry(&block, DEFAULT_SITE_TOLERANCE).unwrap();
+        let sites = assigned["atom_site_label"].as_multi_values().unwrap();
+        let column = |tag: &str| -> Vec<String> {
+            sites
+                .find_loop_column_by_tag(tag)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+        assert_eq!(column("atom_site_symmetry_multiplicity"), ["2", "4"]);
+        assert_eq!(column("atom_site_site_symmetry_order"), ["2", "1"]);
+        assert_eq!(column("atom_site_Wyckoff_symbol"), ["d", "e"]);
+        assert!(sites
+            .find_loop_column_by_tag("atom_site_wyckoff_symbol")
+            .is_none());
+        assert_eq!(sites.columns().len(), 8);
+    }
+
+    #[test]
+    fn assign_centred_and_cubic() {
+        let c2c = assigned(
+            ["10.0", "6.0", "8.0", "90", "110", "90"],
+            "C 1 2/c 1",
+            &["-x,y,-z+1/2", "-x,-y,-z", "x+1/2,y+1/2,z"],
+            "Fe1 0 0 0\nFe2 0 0.3 0.25\nO1 0.25 0.25 0.5\nC1 0.1 0.2 0.3\n",
+        )
+        .unwrap();
+        assert_eq!(
+            c2c,
+            [
+                row("4", "2", "a"),
+                row("4", "2", "e"),
+                row("4", "2", "d"),
+                row("8", "1", "f"),
+            ]
+        );
+
+        let nacl = assigned(
+            ["5.64", "5.64", "5.64", "90", "90", "90"],
+            "F m -3 m",
+            &[
+                "z,x,y",
+                "-x,-y,z",
+                "-x,y,-z",
+                "y,x,-z",
+                "-x,-y,-z",
+                "x,y+1/2,z+1/2",
+                "x+1/2,y,z+1/2",
+            ],
+            "Na1 0 0 0\nCl1 0.5 0.5 0.5\nO1 0.25 0.25 0.25\nO2 0.2 0 0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            nacl,
+            [
+                row("4", "48", "a"),
+                row("4", "48", "b"),
+                row("8", "24", "c"),
+                row("24", "8", "e"),
+            ]
+        );
+
+        let p2c = assigned(
+            ["5.0", "6.0", "7.0", "90", "100", "90"],
+            "P 1 2/c 1",
+            &["-x,y,-z+1/2", "-x,-y,-z"],
+            "Fe1 0 0 0\nO1 0.1 0.2 0.3\n",
+        )
+        .unwrap();
+        assert_eq!(p2c, [row("2", "2", "?"), row("4", "1", "?")]);
+    }
+}
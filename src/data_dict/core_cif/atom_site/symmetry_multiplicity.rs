@@ -45,7 +45,7 @@ impl From<SymMultiplicity> for Numeric {
     }
 }
 
-impl From<SymMultiplicity> for Value {
+impl From<SymMultiplicity> for Value<'_> {
     fn from(value: SymMultiplicity) -> Self {
         Value::Numeric(Numeric::from(value))
     }
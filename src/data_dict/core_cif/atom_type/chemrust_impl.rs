@@ -0,0 +1,35 @@
+use chemrust_core::data::atom::CoreAtomData;
+
+use crate::{
+    data_dict::{
+        core_cif::atom_site::{TypeSymbol, TypeSymbolCode},
+        LoopCategory,
+    },
+    grammar::{DataItems, Numeric},
+    DataBlockMember,
+};
+
+use super::AtomType;
+
+/// Atom types of the model, with `_atom_type_number_in_cell` counted from
+/// the atoms, which are expected to fill the whole unit cell.
+pub(crate) fn basic_atom_type_data<T: CoreAtomData>(atom_data: &T) -> DataItems<'static> {
+    let symbols: Vec<TypeSymbol> = atom_data
+        .symbols_repr()
+        .iter()
+        .map(|symbol| TypeSymbol::new(TypeSymbolCode::ElementSymbol(*symbol), None))
+        .collect();
+    let types: Vec<AtomType> = AtomType::from_type_symbols(&symbols)
+        .into_iter()
+        .map(|atom_type| {
+            let repr = atom_type.symbol().to_string();
+            let count = symbols.iter().filter(|s| s.to_string() == repr).count();
+            atom_type.with_number_in_cell(Numeric::from(count as i32))
+        })
+        .collect();
+    AtomType::to_data_items(&types)
+}
+
+pub fn from_atom_data(value: &impl CoreAtomData) -> Vec<DataBlockMember<'static>> {
+    vec![DataBlockMember::DataItems(basic_atom_type_data(value))]
+}
@@ -0,0 +1,433 @@
+use std::str::FromStr;
+
+use crate::data_dict::reading::{
+    optional_char_string, optional_numeric, required_column, value_or_unknown, DataReadError,
+};
+use crate::data_dict::{CifTerm, LoopCategory, LoopValueTerm};
+use crate::grammar::{CharString, DataBlock, DataItems, LoopColumns, Numeric, Tag, Value};
+
+use super::atom_site::TypeSymbol;
+
+#[cfg(feature = "chemrust-core")]
+pub mod chemrust_impl;
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum AtomTypeLoopItem {
+    Analytical_mass_percent(Vec<Option<Numeric>>),
+    Description(Vec<Option<CharString<'static>>>),
+    Number_in_cell(Vec<Option<Numeric>>),
+    Oxidation_number(Vec<Option<i32>>),
+    Radius_bond(Vec<Option<Numeric>>),
+    Radius_contact(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_a1(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_a2(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_a3(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_a4(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_b1(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_b2(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_b3(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_b4(Vec<Option<Numeric>>),
+    Scat_Cromer_Mann_c(Vec<Option<Numeric>>),
+    Scat_dispersion_imag(Vec<Option<Numeric>>),
+    Scat_dispersion_real(Vec<Option<Numeric>>),
+    Scat_length_neutron(Vec<Option<Numeric>>),
+    Scat_source(Vec<Option<CharString<'static>>>),
+    Symbol(Vec<TypeSymbol>),
+}
+
+impl CifTerm for AtomTypeLoopItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            AtomTypeLoopItem::Analytical_mass_percent(_) => "analytical_mass_%",
+            AtomTypeLoopItem::Description(_) => "description",
+            AtomTypeLoopItem::Number_in_cell(_) => "number_in_cell",
+            AtomTypeLoopItem::Oxidation_number(_) => "oxidation_number",
+            AtomTypeLoopItem::Radius_bond(_) => "radius_bond",
+            AtomTypeLoopItem::Radius_contact(_) => "radius_contact",
+            AtomTypeLoopItem::Scat_Cromer_Mann_a1(_) => "scat_Cromer_Mann_a1",
+            AtomTypeLoopItem::Scat_Cromer_Mann_a2(_) => "scat_Cromer_Mann_a2",
+            AtomTypeLoopItem::Scat_Cromer_Mann_a3(_) => "scat_Cromer_Mann_a3",
+            AtomTypeLoopItem::Scat_Cromer_Mann_a4(_) => "scat_Cromer_Mann_a4",
+            AtomTypeLoopItem::Scat_Cromer_Mann_b1(_) => "scat_Cromer_Mann_b1",
+            AtomTypeLoopItem::Scat_Cromer_Mann_b2(_) => "scat_Cromer_Mann_b2",
+            AtomTypeLoopItem::Scat_Cromer_Mann_b3(_) => "scat_Cromer_Mann_b3",
+            AtomTypeLoopItem::Scat_Cromer_Mann_b4(_) => "scat_Cromer_Mann_b4",
+            AtomTypeLoopItem::Scat_Cromer_Mann_c(_) => "scat_Cromer_Mann_c",
+            AtomTypeLoopItem::Scat_dispersion_imag(_) => "scat_dispersion_imag",
+            AtomTypeLoopItem::Scat_dispersion_real(_) => "scat_dispersion_real",
+            AtomTypeLoopItem::Scat_length_neutron(_) => "scat_length_neutron",
+            AtomTypeLoopItem::Scat_source(_) => "scat_source",
+            AtomTypeLoopItem::Symbol(_) => "symbol",
+        };
+        Tag::new(format!("atom_type_{suffix}"))
+    }
+}
+
+impl LoopValueTerm for AtomTypeLoopItem {
+    fn values(&self) -> Vec<Value<'static>> {
+        match self {
+            AtomTypeLoopItem::Analytical_mass_percent(v)
+            | AtomTypeLoopItem::Number_in_cell(v)
+            | AtomTypeLoopItem::Radius_bond(v)
+            | AtomTypeLoopItem::Radius_contact(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_a1(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_a2(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_a3(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_a4(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_b1(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_b2(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_b3(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_b4(v)
+            | AtomTypeLoopItem::Scat_Cromer_Mann_c(v)
+            | AtomTypeLoopItem::Scat_dispersion_imag(v)
+            | AtomTypeLoopItem::Scat_dispersion_real(v)
+            | AtomTypeLoopItem::Scat_length_neutron(v) => {
+                v.iter().copied().map(value_or_unknown).collect()
+            }
+            AtomTypeLoopItem::Description(s) | AtomTypeLoopItem::Scat_source(s) => {
+                s.iter().cloned().map(value_or_unknown).collect()
+            }
+            AtomTypeLoopItem::Oxidation_number(v) => v
+                .iter()
+                .map(|i| value_or_unknown(i.map(Numeric::from)))
+                .collect(),
+            AtomTypeLoopItem::Symbol(symbols) => symbols.iter().cloned().map(Value::from).collect(),
+        }
+    }
+}
+
+/// Cromer-Mann coefficients of the analytical approximation to the
+/// scattering factor, `f(s) = sum_i a_i exp(-b_i s^2) + c` with `s = sin(θ)/λ`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CromerMannCoefficients {
+    a: [Numeric; 4],
+    b: [Numeric; 4],
+    c: Numeric,
+}
+
+impl CromerMannCoefficients {
+    pub fn new(a: [Numeric; 4], b: [Numeric; 4], c: Numeric) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn a(&self) -> [Numeric; 4] {
+        self.a
+    }
+
+    pub fn b(&self) -> [Numeric; 4] {
+        self.b
+    }
+
+    pub fn c(&self) -> Numeric {
+        self.c
+    }
+
+    /// Scattering factor at `stol` (sin(θ)/λ, in reciprocal Ångström).
+    pub fn scattering_factor(&self, stol: f64) -> f64 {
+        let s2 = stol * stol;
+        self.a
+            .iter()
+            .zip(self.b.iter())
+            .map(|(a, b)| f64::from(a.number()) * (-f64::from(b.number()) * s2).exp())
+            .sum::<f64>()
+            + f64::from(self.c.number())
+    }
+}
+
+/// One row of the `_atom_type_` loop.
+#[derive(Debug, Clone)]
+pub struct AtomType {
+    symbol: TypeSymbol,
+    description: Option<CharString<'static>>,
+    oxidation_number: Option<i32>,
+    number_in_cell: Option<Numeric>,
+    cromer_mann: Option<CromerMannCoefficients>,
+    scat_dispersion_real: Option<Numeric>,
+    scat_dispersion_imag: Option<Numeric>,
+    scat_source: Option<CharString<'static>>,
+    radius_bond: Option<Numeric>,
+}
+
+impl AtomType {
+    pub fn new(symbol: TypeSymbol) -> Self {
+        Self {
+            oxidation_number: symbol.oxidation_state(),
+            symbol,
+            description: None,
+            number_in_cell: None,
+            cromer_mann: None,
+            scat_dispersion_real: None,
+            scat_dispersion_imag: None,
+            scat_source: None,
+            radius_bond: None,
+        }
+    }
+
+    /// One `AtomType` per distinct symbol, in order of first appearance.
+    pub fn from_type_symbols(symbols: &[TypeSymbol]) -> Vec<AtomType> {
+        let mut types: Vec<AtomType> = Vec::new();
+        symbols.iter().for_each(|symbol| {
+            let repr = symbol.to_string();
+            if !types.iter().any(|t| t.symbol.to_string() == repr) {
+                types.push(AtomType::new(symbol.clone()));
+            }
+        });
+        types
+    }
+
+    pub fn with_description(mut self, description: CharString<'static>) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_oxidation_number(mut self, oxidation_number: i32) -> Self {
+        self.oxidation_number = Some(oxidation_number);
+        self
+    }
+
+    pub fn with_number_in_cell(mut self, number_in_cell: Numeric) -> Self {
+        self.number_in_cell = Some(number_in_cell);
+        self
+    }
+
+    pub fn with_cromer_mann(mut self, coefficients: CromerMannCoefficients) -> Self {
+        self.cromer_mann = Some(coefficients);
+        self
+    }
+
+    pub fn with_scat_dispersion(mut self, real: Numeric, imag: Numeric) -> Self {
+        self.scat_dispersion_real = Some(real);
+        self.scat_dispersion_imag = Some(imag);
+        self
+    }
+
+    pub fn with_scat_source(mut self, source: CharString<'static>) -> Self {
+        self.scat_source = Some(source);
+        self
+    }
+
+    pub fn with_radius_bond(mut self, radius: Numeric) -> Self {
+        self.radius_bond = Some(radius);
+        self
+    }
+
+    pub fn symbol(&self) -> &TypeSymbol {
+        &self.symbol
+    }
+
+    pub fn description(&self) -> Option<&CharString<'static>> {
+        self.description.as_ref()
+    }
+
+    pub fn oxidation_number(&self) -> Option<i32> {
+        self.oxidation_number
+    }
+
+    pub fn number_in_cell(&self) -> Option<Numeric> {
+        self.number_in_cell
+    }
+
+    pub fn cromer_mann(&self) -> Option<&CromerMannCoefficients> {
+        self.cromer_mann.as_ref()
+    }
+
+    pub fn scat_dispersion_real(&self) -> Option<Numeric> {
+        self.scat_dispersion_real
+    }
+
+    pub fn scat_dispersion_imag(&self) -> Option<Numeric> {
+        self.scat_dispersion_imag
+    }
+
+    pub fn scat_source(&self) -> Option<&CharString<'static>> {
+        self.scat_source.as_ref()
+    }
+
+    pub fn radius_bond(&self) -> Option<Numeric> {
+        self.radius_bond
+    }
+
+    /// Read all rows of the `_atom_type_` loop.
+    pub fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<AtomType>, DataReadError> {
+        <AtomType as LoopCategory>::from_loop_columns(columns)
+    }
+
+    /// Read the `_atom_type_` loop of a data block.
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Vec<AtomType>, DataReadError> {
+        <AtomType as LoopCategory>::from_data_block(data_block)
+    }
+}
+
+/// Build the `_atom_type_` loop, as [`LoopCategory::to_data_items`].
+pub fn atom_type_data(types: &[AtomType]) -> DataItems<'static> {
+    AtomType::to_data_items(types)
+}
+
+type NumericColumn = fn(Vec<Option<Numeric>>) -> AtomTypeLoopItem;
+
+impl LoopCategory for AtomType {
+    const KEY_TAG: &'static str = "atom_type_symbol";
+
+    fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<AtomType>, DataReadError> {
+        let symbols = required_column(columns, "atom_type_symbol")?;
+        let column = |suffix: &str| columns.find_loop_column_by_tag(format!("atom_type_{suffix}"));
+        let cromer_mann_columns = ["a1", "a2", "a3", "a4", "b1", "b2", "b3", "b4", "c"]
+            .map(|suffix| column(&format!("scat_Cromer_Mann_{suffix}")));
+        symbols
+            .values()
+            .iter()
+            .enumerate()
+            .map(|(row, value)| {
+                let symbol = match value {
+                    Value::CharString(s) => TypeSymbol::from_str(s.as_ref()).unwrap(),
+                    v => return Err(DataReadError::invalid("atom_type_symbol", Some(row), v)),
+                };
+                let oxidation_number = optional_numeric(column("oxidation_number"), row)?
+                    .map(|n| {
+                        n.number().as_integer().map(|i| i.0).ok_or_else(|| {
+                            DataReadError::invalid(
+                                "atom_type_oxidation_number",
+                                Some(row),
+                                &Value::Numeric(n),
+                            )
+                        })
+                    })
+                    .transpose()?;
+                let cromer_mann = cromer_mann_columns
+                    .iter()
+                    .map(|c| optional_numeric(*c, row))
+                    .collect::<Result<Option<Vec<Numeric>>, DataReadError>>()?
+                    .map(|coeffs| {
+                        CromerMannCoefficients::new(
+                            [coeffs[0], coeffs[1], coeffs[2], coeffs[3]],
+                            [coeffs[4], coeffs[5], coeffs[6], coeffs[7]],
+                            coeffs[8],
+                        )
+                    });
+                Ok(AtomType {
+                    oxidation_number: oxidation_number.or(symbol.oxidation_state()),
+                    symbol,
+                    description: optional_char_string(column("description"), row)?,
+                    number_in_cell: optional_numeric(column("number_in_cell"), row)?,
+                    cromer_mann,
+                    scat_dispersion_real: optional_numeric(column("scat_dispersion_real"), row)?,
+                    scat_dispersion_imag: optional_numeric(column("scat_dispersion_imag"), row)?,
+                    scat_source: optional_char_string(column("scat_source"), row)?,
+                    radius_bond: optional_numeric(column("radius_bond"), row)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Optional columns are only written when at least one atom type has the
+    /// entry, missing entries are written as `?`.
+    fn to_loop_columns(records: &[Self]) -> LoopColumns<'static> {
+        let mut items = vec![AtomTypeLoopItem::Symbol(
+            records.iter().map(|t| t.symbol.clone()).collect(),
+        )];
+        let descriptions: Vec<Option<CharString>> =
+            records.iter().map(|t| t.description.clone()).collect();
+        if descriptions.iter().any(Option::is_some) {
+            items.push(AtomTypeLoopItem::Description(descriptions));
+        }
+        let oxidation_numbers: Vec<Option<i32>> =
+            records.iter().map(|t| t.oxidation_number).collect();
+        if oxidation_numbers.iter().any(Option::is_some) {
+            items.push(AtomTypeLoopItem::Oxidation_number(oxidation_numbers));
+        }
+        let numeric_columns = [
+            (
+                AtomTypeLoopItem::Number_in_cell as NumericColumn,
+                records.iter().map(|t| t.number_in_cell).collect(),
+            ),
+            (
+                AtomTypeLoopItem::Scat_dispersion_real,
+                records.iter().map(|t| t.scat_dispersion_real).collect(),
+            ),
+            (
+                AtomTypeLoopItem::Scat_dispersion_imag,
+                records.iter().map(|t| t.scat_dispersion_imag).collect(),
+            ),
+            (
+                AtomTypeLoopItem::Radius_bond,
+                records
+                    .iter()
+                    .map(|t| t.radius_bond)
+                    .collect::<Vec<Option<Numeric>>>(),
+            ),
+        ];
+        numeric_columns
+            .into_iter()
+            .filter(|(_, column)| column.iter().any(Option::is_some))
+            .for_each(|(variant, column)| items.push(variant(column)));
+        if records.iter().any(|t| t.cromer_mann.is_some()) {
+            let coefficient = |pick: fn(&CromerMannCoefficients) -> Numeric| {
+                records
+                    .iter()
+                    .map(|t| t.cromer_mann.as_ref().map(pick))
+                    .collect::<Vec<Option<Numeric>>>()
+            };
+            items.extend([
+                AtomTypeLoopItem::Scat_Cromer_Mann_a1(coefficient(|cm| cm.a[0])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_a2(coefficient(|cm| cm.a[1])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_a3(coefficient(|cm| cm.a[2])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_a4(coefficient(|cm| cm.a[3])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_b1(coefficient(|cm| cm.b[0])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_b2(coefficient(|cm| cm.b[1])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_b3(coefficient(|cm| cm.b[2])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_b4(coefficient(|cm| cm.b[3])),
+                AtomTypeLoopItem::Scat_Cromer_Mann_c(coefficient(|cm| cm.c)),
+            ]);
+        }
+        let sources: Vec<Option<CharString>> =
+            records.iter().map(|t| t.scat_source.clone()).collect();
+        if sources.iter().any(Option::is_some) {
+            items.push(AtomTypeLoopItem::Scat_source(sources));
+        }
+        LoopColumns::new(items.iter().map(|item| item.to_loop_column()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        grammar::{DataBlock, SyntacticUnit},
+        DataItems,
+    };
+
+    use super::{atom_type_data, AtomType};
+
+    #[test]
+    fn atom_type_round_trip() {
+        let mut input = r#"data_I
+loop_
+ _atom_type_symbol
+ _atom_type_description
+ _atom_type_scat_dispersion_real
+ _atom_type_scat_dispersion_imag
+ _atom_type_scat_source
+ 'C'  'C'   0.0033   0.0000
+ 'International Tables Vol C Tables 4.2.6.8 and 6.1.1.4'
+ 'O'  'O'   0.0106   0.0000
+ 'International Tables Vol C Tables 4.2.6.8 and 6.1.1.4'
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let types = AtomType::from_data_block(&block).unwrap();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[1].symbol().to_string(), "O");
+        assert_eq!(
+            types[1]
+                .scat_dispersion_real()
+                .map(|n| f64::from(n.number())),
+            Some(0.0106_f32 as f64)
+        );
+        let DataItems::MultiValues(columns) = atom_type_data(&types) else {
+            panic!("atom type data should be a loop")
+        };
+        let read_back = AtomType::from_loop_columns(&columns).unwrap();
+        assert_eq!(read_back.len(), 2);
+        assert!(read_back[0].scat_source().is_some());
+        assert!(read_back[0].cromer_mann().is_none());
+    }
+}
@@ -10,13 +10,13 @@ use crate::{
 pub enum AuditItem {
     Block_code,
     Block_doi,
-    Creation_date(CharString),
-    Creation_method(CharString),
+    Creation_date(CharString<'static>),
+    Creation_method(CharString<'static>),
     Update_record,
 }
 
 impl CifTerm for AuditItem {
-    fn tag(&self) -> Tag {
+    fn tag(&self) -> Tag<'static> {
         let suffix = match self {
             AuditItem::Block_code => "block_code",
             AuditItem::Block_doi => "block_doi",
@@ -29,7 +29,7 @@ impl CifTerm for AuditItem {
 }
 
 impl SingleValueTerm for AuditItem {
-    fn value(&self) -> Value {
+    fn value(&self) -> Value<'static> {
         match self {
             AuditItem::Block_code => todo!(),
             AuditItem::Block_doi => todo!(),
@@ -40,7 +40,7 @@ impl SingleValueTerm for AuditItem {
     }
 }
 
-pub(crate) fn default_audit_data() -> Vec<DataItems> {
+pub(crate) fn default_audit_data() -> Vec<DataItems<'static>> {
     [
         AuditItem::Creation_date(
             SingleQuotedString::new(format!("{}", Utc::now().format("%Y-%m-%d"))).into(),
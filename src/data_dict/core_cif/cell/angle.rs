@@ -1,20 +1,33 @@
 use crate::grammar::{Float, Number, Numeric, Value};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-pub struct CellAngle(Float);
+pub struct CellAngle(Numeric);
 
 impl CellAngle {
     pub fn new(float: Float) -> Self {
-        Self(Float(float.clamp(0.0, 180.0)))
+        Self(Numeric::new(
+            Number::Float(Float(float.clamp(0.0, 180.0))),
+            None,
+        ))
     }
+
+    /// An angle with its standard uncertainty, e.g. `98.234(3)`.
+    pub fn from_numeric(numeric: Numeric) -> Self {
+        Self(numeric)
+    }
+
     pub fn angle(&self) -> Float {
+        Float(self.0.as_f64() as f32)
+    }
+
+    pub fn numeric(&self) -> Numeric {
         self.0
     }
 }
 
-impl From<CellAngle> for Value {
+impl From<CellAngle> for Value<'_> {
     fn from(value: CellAngle) -> Self {
-        Value::Numeric(Numeric::new(Number::Float(value.angle()), None))
+        Value::Numeric(value.numeric())
     }
 }
 
@@ -4,7 +4,7 @@ use crate::{data_dict::SingleValueTerm, grammar::DataItems, DataBlockMember};
 
 use super::CellTerms;
 /// For simple creation
-pub(crate) fn basic_cell_data<T: UnitCellParameters>(value: &T) -> Vec<DataItems> {
+pub(crate) fn basic_cell_data<T: UnitCellParameters>(value: &T) -> Vec<DataItems<'static>> {
     let cell_terms = [
         CellTerms::Length_a(value.length_a().into()),
         CellTerms::Length_b(value.length_b().into()),
@@ -20,7 +20,7 @@ pub(crate) fn basic_cell_data<T: UnitCellParameters>(value: &T) -> Vec<DataItems
         .collect()
 }
 
-pub fn from_unit_cell_parameters(value: &impl UnitCellParameters) -> Vec<DataBlockMember> {
+pub fn from_unit_cell_parameters(value: &impl UnitCellParameters) -> Vec<DataBlockMember<'static>> {
     [
         CellTerms::Length_a(value.length_a().into()),
         CellTerms::Length_b(value.length_b().into()),
@@ -1,15 +1,19 @@
 mod angle;
 #[cfg(feature = "chemrust-core")]
 pub mod chemrust_impl;
+mod parameters;
 
 use std::fmt::Display;
 
 use crate::{
-    data_dict::{CifTerm, SingleValueTerm},
+    data_dict::{reading::text_value, CifTerm, LoopValueTerm, SingleValueTerm},
     grammar::{Numeric, Tag, UnsignedInteger, Value},
 };
 
 pub use self::angle::CellAngle;
+pub use self::parameters::CellParameters;
+
+pub(crate) use self::parameters::{measured, metric_dot, metric_tensor, reciprocal_parameters};
 
 #[derive(Debug, Clone)]
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
@@ -21,56 +25,90 @@ pub enum CellTerms {
     Length_a(Numeric),
     Length_b(Numeric),
     Length_c(Numeric),
-    Measurement_pressure,
-    Measurement_radiation,
-    Measurement_reflns_used,
-    Measurement_temperature,
-    Measurement_theta_max,
-    Measurement_theta_min,
-    Measurement_wavelength,
+    /// In kilopascals.
+    Measurement_pressure(Numeric),
+    Measurement_radiation(String),
+    Measurement_reflns_used(UnsignedInteger),
+    /// In kelvins.
+    Measurement_temperature(Numeric),
+    /// In degrees.
+    Measurement_theta_max(Numeric),
+    /// In degrees.
+    Measurement_theta_min(Numeric),
+    /// In Ångström.
+    Measurement_wavelength(Numeric),
     Reciprocal_angle_alpha(CellAngle),
     Reciprocal_angle_beta(CellAngle),
     Reciprocal_angle_gamma(CellAngle),
     Reciprocal_length_a(Numeric),
     Reciprocal_length_b(Numeric),
     Reciprocal_length_c(Numeric),
-    Special_details,
+    Special_details(String),
     Volume(Numeric),
 }
 
 #[derive(Debug, Clone)]
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum MeasurementReflnLoopItem {
-    Measurement_refln_index_h,
-    Measurement_refln_index_k,
-    Measurement_refln_index_l,
-    Measurement_refln_theta,
+    Measurement_refln_index_h(Vec<i32>),
+    Measurement_refln_index_k(Vec<i32>),
+    Measurement_refln_index_l(Vec<i32>),
+    /// In degrees.
+    Measurement_refln_theta(Vec<Numeric>),
+}
+
+impl CifTerm for MeasurementReflnLoopItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            MeasurementReflnLoopItem::Measurement_refln_index_h(_) => "index_h",
+            MeasurementReflnLoopItem::Measurement_refln_index_k(_) => "index_k",
+            MeasurementReflnLoopItem::Measurement_refln_index_l(_) => "index_l",
+            MeasurementReflnLoopItem::Measurement_refln_theta(_) => "theta",
+        };
+        Tag::new(format!("cell_measurement_refln_{suffix}"))
+    }
+}
+
+impl LoopValueTerm for MeasurementReflnLoopItem {
+    fn values(&self) -> Vec<Value<'static>> {
+        match self {
+            MeasurementReflnLoopItem::Measurement_refln_index_h(indices)
+            | MeasurementReflnLoopItem::Measurement_refln_index_k(indices)
+            | MeasurementReflnLoopItem::Measurement_refln_index_l(indices) => indices
+                .iter()
+                .map(|&i| Value::Numeric(Numeric::from(i)))
+                .collect(),
+            MeasurementReflnLoopItem::Measurement_refln_theta(theta) => {
+                theta.iter().copied().map(Value::from).collect()
+            }
+        }
+    }
 }
 
 impl CifTerm for CellTerms {
-    fn tag(&self) -> Tag {
+    fn tag(&self) -> Tag<'static> {
         let name = match self {
             CellTerms::Angle_alpha(_) => "angle_alpha",
             CellTerms::Angle_beta(_) => "angle_beta",
             CellTerms::Angle_gamma(_) => "angle_gamma",
-            CellTerms::Formula_units_Z(_) => "formula_units_z",
+            CellTerms::Formula_units_Z(_) => "formula_units_Z",
             CellTerms::Length_a(_) => "length_a",
             CellTerms::Length_b(_) => "length_b",
             CellTerms::Length_c(_) => "length_c",
-            CellTerms::Measurement_pressure => "measurement_pressure",
-            CellTerms::Measurement_radiation => "measurement_radiation",
-            CellTerms::Measurement_reflns_used => "measurement_reflns_used",
-            CellTerms::Measurement_temperature => "measurement_temperature",
-            CellTerms::Measurement_theta_max => "measurement_theta_max",
-            CellTerms::Measurement_theta_min => "measurement_theta_min",
-            CellTerms::Measurement_wavelength => "measurement_wavelength",
+            CellTerms::Measurement_pressure(_) => "measurement_pressure",
+            CellTerms::Measurement_radiation(_) => "measurement_radiation",
+            CellTerms::Measurement_reflns_used(_) => "measurement_reflns_used",
+            CellTerms::Measurement_temperature(_) => "measurement_temperature",
+            CellTerms::Measurement_theta_max(_) => "measurement_theta_max",
+            CellTerms::Measurement_theta_min(_) => "measurement_theta_min",
+            CellTerms::Measurement_wavelength(_) => "measurement_wavelength",
             CellTerms::Reciprocal_angle_alpha(_) => "reciprocal_angle_alpha",
             CellTerms::Reciprocal_angle_beta(_) => "reciprocal_angle_beta",
             CellTerms::Reciprocal_angle_gamma(_) => "reciprocal_angle_gamma",
             CellTerms::Reciprocal_length_a(_) => "reciprocal_length_a",
             CellTerms::Reciprocal_length_b(_) => "reciprocal_length_b",
             CellTerms::Reciprocal_length_c(_) => "reciprocal_length_c",
-            CellTerms::Special_details => "special_details",
+            CellTerms::Special_details(_) => "special_details",
             CellTerms::Volume(_) => "volume",
         };
         Tag::new(format!("cell_{name}"))
@@ -78,7 +116,7 @@ impl CifTerm for CellTerms {
 }
 
 impl SingleValueTerm for CellTerms {
-    fn value(&self) -> crate::grammar::Value {
+    fn value(&self) -> crate::grammar::Value<'static> {
         match self {
             CellTerms::Angle_alpha(a)
             | CellTerms::Angle_beta(a)
@@ -94,15 +132,16 @@ impl SingleValueTerm for CellTerms {
             | CellTerms::Reciprocal_length_a(numeric)
             | CellTerms::Reciprocal_length_b(numeric)
             | CellTerms::Reciprocal_length_c(numeric)
-            | CellTerms::Volume(numeric) => Value::Numeric(*numeric),
-            CellTerms::Measurement_pressure => todo!(),
-            CellTerms::Measurement_radiation => todo!(),
-            CellTerms::Measurement_reflns_used => todo!(),
-            CellTerms::Measurement_temperature => todo!(),
-            CellTerms::Measurement_theta_max => todo!(),
-            CellTerms::Measurement_theta_min => todo!(),
-            CellTerms::Measurement_wavelength => todo!(),
-            CellTerms::Special_details => todo!(),
+            | CellTerms::Volume(numeric)
+            | CellTerms::Measurement_pressure(numeric)
+            | CellTerms::Measurement_temperature(numeric)
+            | CellTerms::Measurement_theta_max(numeric)
+            | CellTerms::Measurement_theta_min(numeric)
+            | CellTerms::Measurement_wavelength(numeric) => Value::Numeric(*numeric),
+            CellTerms::Measurement_reflns_used(n) => (*n).into(),
+            CellTerms::Measurement_radiation(text) | CellTerms::Special_details(text) => {
+                text_value(text)
+            }
         }
     }
 }
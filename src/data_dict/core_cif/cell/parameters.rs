@@ -0,0 +1,222 @@
+use crate::{
+    data_dict::{reading::required_single_numeric, DataReadError},
+    grammar::{DataBlock, Numeric},
+};
+
+use super::{CellAngle, CellTerms};
+
+/// The six direct cell parameters with their standard uncertainties, as
+/// given by `_cell_length_*` (Ångström) and `_cell_angle_*` (degrees).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellParameters {
+    lengths: [Numeric; 3],
+    angles: [Numeric; 3],
+}
+
+impl CellParameters {
+    pub fn new(lengths: [Numeric; 3], angles: [Numeric; 3]) -> Self {
+        Self { lengths, angles }
+    }
+
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Self, DataReadError> {
+        let read = |tag: &str| required_single_numeric(data_block, tag);
+        Ok(Self {
+            lengths: [
+                read("cell_length_a")?,
+                read("cell_length_b")?,
+                read("cell_length_c")?,
+            ],
+            angles: [
+                read("cell_angle_alpha")?,
+                read("cell_angle_beta")?,
+                read("cell_angle_gamma")?,
+            ],
+        })
+    }
+
+    pub fn lengths(&self) -> [Numeric; 3] {
+        self.lengths
+    }
+
+    pub fn angles(&self) -> [Numeric; 3] {
+        self.angles
+    }
+
+    /// `[a, b, c, alpha, beta, gamma]`, angles in degrees.
+    pub fn values(&self) -> [f64; 6] {
+        let [a, b, c] = self.lengths.map(|l| l.as_f64());
+        let [alpha, beta, gamma] = self.angles.map(|l| l.as_f64());
+        [a, b, c, alpha, beta, gamma]
+    }
+
+    /// Standard uncertainties in the order of [`values`](Self::values),
+    /// zero when not given.
+    pub fn std_uncertainties(&self) -> [f64; 6] {
+        let [a, b, c] = self.lengths.map(|l| l.su_value().unwrap_or(0.0));
+        let [alpha, beta, gamma] = self.angles.map(|l| l.su_value().unwrap_or(0.0));
+        [a, b, c, alpha, beta, gamma]
+    }
+
+    /// Metric tensor `G`, so that the squared length of a fractional vector
+    /// `x` is `xᵀ G x`.
+    pub fn metric_tensor(&self) -> [[f64; 3]; 3] {
+        metric_tensor(&self.values())
+    }
+
+    /// Cell volume in Å³, with su propagated from the cell parameters.
+    pub fn volume(&self) -> Numeric {
+        let (volume, su) = self.with_su(volume);
+        measured(volume, su, 2)
+    }
+
+    /// `a*`, `b*` and `c*` in Å⁻¹, with su propagated from the cell
+    /// parameters.
+    pub fn reciprocal_lengths(&self) -> [Numeric; 3] {
+        std::array::from_fn(|i| {
+            let (length, su) = self.with_su(|p| reciprocal_parameters(p)[i]);
+            measured(length, su, 5)
+        })
+    }
+
+    /// `alpha*`, `beta*` and `gamma*` in degrees, with su propagated from
+    /// the cell parameters.
+    pub fn reciprocal_angles(&self) -> [CellAngle; 3] {
+        std::array::from_fn(|i| {
+            let (angle, su) = self.with_su(|p| reciprocal_parameters(p)[i + 3]);
+            CellAngle::from_numeric(measured(angle, su, 3))
+        })
+    }
+
+    /// `_cell_volume` and the `_cell_reciprocal_` terms derived from the
+    /// direct cell.
+    pub fn derived_terms(&self) -> Vec<CellTerms> {
+        let [a, b, c] = self.reciprocal_lengths();
+        let [alpha, beta, gamma] = self.reciprocal_angles();
+        vec![
+            CellTerms::Volume(self.volume()),
+            CellTerms::Reciprocal_length_a(a),
+            CellTerms::Reciprocal_length_b(b),
+            CellTerms::Reciprocal_length_c(c),
+            CellTerms::Reciprocal_angle_alpha(alpha),
+            CellTerms::Reciprocal_angle_beta(beta),
+            CellTerms::Reciprocal_angle_gamma(gamma),
+        ]
+    }
+
+    /// Value of `f` and its su from the su of the cell parameters, by
+    /// numerical differentiation.
+    fn with_su<F: Fn(&[f64; 6]) -> f64>(&self, f: F) -> (f64, f64) {
+        let parameters = self.values();
+        let variance: f64 = self
+            .std_uncertainties()
+            .iter()
+            .enumerate()
+            .filter(|(_, su)| **su > 0.0)
+            .map(|(i, su)| {
+                let h = 1e-6 * parameters[i].abs().max(1.0);
+                let (mut plus, mut minus) = (parameters, parameters);
+                plus[i] += h;
+                minus[i] -= h;
+                ((f(&plus) - f(&minus)) / (2.0 * h) * su).powi(2)
+            })
+            .sum();
+        (f(&parameters), variance.sqrt())
+    }
+}
+
+/// Volume of the cell `[a, b, c, alpha, beta, gamma]`, angles in degrees.
+pub(crate) fn volume(parameters: &[f64; 6]) -> f64 {
+    let [a, b, c, alpha, beta, gamma] = *parameters;
+    let [cos_alpha, cos_beta, cos_gamma] = [alpha, beta, gamma].map(|x| x.to_radians().cos());
+    a * b
+        * c
+        * (1.0 - cos_alpha.powi(2) - cos_beta.powi(2) - cos_gamma.powi(2)
+            + 2.0 * cos_alpha * cos_beta * cos_gamma)
+            .sqrt()
+}
+
+/// `[a*, b*, c*, alpha*, beta*, gamma*]` of `[a, b, c, alpha, beta, gamma]`,
+/// angles in degrees.
+pub(crate) fn reciprocal_parameters(parameters: &[f64; 6]) -> [f64; 6] {
+    let [a, b, c, alpha, beta, gamma] = *parameters;
+    let [alpha, beta, gamma] = [alpha, beta, gamma].map(f64::to_radians);
+    let v = volume(parameters);
+    let reciprocal_angle = |x: f64, y: f64, z: f64| {
+        ((y.cos() * z.cos() - x.cos()) / (y.sin() * z.sin()))
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees()
+    };
+    [
+        b * c * alpha.sin() / v,
+        a * c * beta.sin() / v,
+        a * b * gamma.sin() / v,
+        reciprocal_angle(alpha, beta, gamma),
+        reciprocal_angle(beta, gamma, alpha),
+        reciprocal_angle(gamma, alpha, beta),
+    ]
+}
+
+/// A derived value with its su, or with `decimals` places when the su is
+/// zero because the inputs have no uncertainties.
+pub(crate) fn measured(value: f64, su: f64, decimals: usize) -> Numeric {
+    if su > 0.0 {
+        Numeric::from_value_su(value, su)
+    } else {
+        Numeric::with_decimals(value, decimals)
+    }
+}
+
+/// Metric tensor of `[a, b, c, alpha, beta, gamma]`, angles in degrees.
+pub(crate) fn metric_tensor(parameters: &[f64; 6]) -> [[f64; 3]; 3] {
+    let [a, b, c, alpha, beta, gamma] = *parameters;
+    let (cos_alpha, cos_beta, cos_gamma) = (
+        alpha.to_radians().cos(),
+        beta.to_radians().cos(),
+        gamma.to_radians().cos(),
+    );
+    [
+        [a * a, a * b * cos_gamma, a * c * cos_beta],
+        [a * b * cos_gamma, b * b, b * c * cos_alpha],
+        [a * c * cos_beta, b * c * cos_alpha, c * c],
+    ]
+}
+
+/// `uᵀ G v` for fractional vectors `u` and `v`.
+pub(crate) fn metric_dot(g: &[[f64; 3]; 3], u: &[f64; 3], v: &[f64; 3]) -> f64 {
+    (0..3)
+        .map(|i| (0..3).map(|j| u[i] * g[i][j] * v[j]).sum::<f64>())
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, SyntacticUnit};
+
+    use super::CellParameters;
+
+    #[test]
+    fn derived_cell() {
+        let mut input = r#"data_I
+_cell_length_a 7.1280(3)
+_cell_length_b 10.6520(5)
+_cell_length_c 12.1600(6)
+_cell_angle_alpha 90
+_cell_angle_beta 98.234(2)
+_cell_angle_gamma 90
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let cell = CellParameters::from_data_block(&block).unwrap();
+        let volume = cell.volume();
+        assert!((volume.as_f64() - 913.8).abs() < 0.1);
+        assert_eq!(volume.to_string(), "913.76(7)");
+        let [a, b, _] = cell.reciprocal_lengths();
+        assert!((a.as_f64() - 1.0 / (7.128 * 98.234_f64.to_radians().sin())).abs() < 1e-5);
+        assert!((b.as_f64() - 1.0 / 10.652).abs() < 1e-5);
+        let [alpha, beta, gamma] = cell.reciprocal_angles();
+        assert_eq!(alpha.angle().0, 90.0);
+        assert!((beta.numeric().as_f64() - 81.766).abs() < 1e-3);
+        assert_eq!(gamma.angle().0, 90.0);
+        assert_eq!(cell.derived_terms().len(), 7);
+    }
+}
@@ -0,0 +1,209 @@
+use std::{fmt::Display, str::FromStr};
+
+use castep_periodic_table::{
+    data::ELEMENT_TABLE,
+    element::{ElementSymbol, LookupElement},
+};
+
+use crate::grammar::Numeric;
+
+/// Counts below this are taken as zero, and counts this close to an integer
+/// or to two decimals are written as such.
+const COUNT_TOLERANCE: f64 = 1e-3;
+
+/// A chemical formula as the number of atoms of each element, which may be
+/// fractional for partially occupied sites.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChemicalFormula {
+    counts: Vec<(ElementSymbol, f64)>,
+}
+
+impl ChemicalFormula {
+    /// Counts of the same element are added up; zero counts are left out.
+    pub fn new(counts: &[(ElementSymbol, f64)]) -> Self {
+        let mut formula = Self::default();
+        counts
+            .iter()
+            .for_each(|&(element, count)| formula.add(element, count));
+        formula
+    }
+
+    fn add(&mut self, element: ElementSymbol, count: f64) {
+        match self.counts.iter_mut().find(|(e, _)| *e == element) {
+            Some((_, total)) => *total += count,
+            None => self.counts.push((element, count)),
+        }
+        self.counts
+            .retain(|(_, count)| count.abs() > COUNT_TOLERANCE);
+    }
+
+    /// The elements with their counts in Hill order: C and H first when
+    /// there is carbon, then the others alphabetically.
+    pub fn counts(&self) -> Vec<(ElementSymbol, f64)> {
+        let mut counts = self.counts.clone();
+        let has_carbon = counts.iter().any(|(e, _)| *e == ElementSymbol::C);
+        let rank = |element: &ElementSymbol| match element {
+            ElementSymbol::C if has_carbon => 0,
+            ElementSymbol::H if has_carbon => 1,
+            _ => 2,
+        };
+        counts.sort_by(|(a, _), (b, _)| {
+            rank(a)
+                .cmp(&rank(b))
+                .then_with(|| a.to_string().cmp(&b.to_string()))
+        });
+        counts
+    }
+
+    /// Number of atoms of the element, zero if absent.
+    pub fn count(&self, element: ElementSymbol) -> f64 {
+        self.counts
+            .iter()
+            .find(|(e, _)| *e == element)
+            .map_or(0.0, |(_, count)| *count)
+    }
+
+    /// Molar mass in g/mol from the atomic masses of `castep-periodic-table`.
+    pub fn molar_mass(&self) -> f64 {
+        self.counts
+            .iter()
+            .map(|(element, count)| ELEMENT_TABLE.get_by_symbol(*element).mass() * count)
+            .sum()
+    }
+
+    /// `_chemical_formula_weight`, to two decimals.
+    pub fn formula_weight(&self) -> Numeric {
+        Numeric::with_decimals(self.molar_mass(), 2)
+    }
+
+    /// The formula divided by `z`.
+    pub fn divided_by(&self, z: u32) -> Self {
+        Self {
+            counts: self
+                .counts
+                .iter()
+                .map(|&(element, count)| (element, count / z as f64))
+                .collect(),
+        }
+    }
+
+    /// The largest number of formula units `Z`, at most `max_z`, in which
+    /// the formula divides without needing more decimals in its counts, with
+    /// the formula of one unit. `C20 H16` with `max_z` 4 gives `C5 H4` and
+    /// Z = 4, while `Fe0.7 Co0.3 O2` stays whole with Z = 1.
+    pub fn formula_units(&self, max_z: u32) -> (Self, u32) {
+        let decimals = self
+            .counts
+            .iter()
+            .map(|(_, count)| decimal_places(*count))
+            .max()
+            .unwrap_or(0);
+        let z = (1..=max_z.max(1))
+            .rev()
+            .find(|&z| {
+                self.counts
+                    .iter()
+                    .all(|(_, count)| decimal_places(count / z as f64) <= decimals)
+            })
+            .unwrap_or(1);
+        (self.divided_by(z), z)
+    }
+
+    /// Whether both formulas have the same elements with counts within
+    /// `tolerance`, to check a given `_chemical_formula_sum` against the
+    /// atom sites.
+    pub fn matches(&self, other: &Self, tolerance: f64) -> bool {
+        self.counts
+            .iter()
+            .chain(other.counts.iter())
+            .all(|(element, _)| (self.count(*element) - other.count(*element)).abs() <= tolerance)
+    }
+}
+
+fn is_integer(x: f64) -> bool {
+    (x - x.round()).abs() < COUNT_TOLERANCE
+}
+
+/// Decimals needed to write a count, up to two; three for anything finer.
+fn decimal_places(count: f64) -> u32 {
+    (0..=2)
+        .find(|&d| is_integer(count * 10f64.powi(d as i32)))
+        .unwrap_or(3)
+}
+
+/// Written as in `_chemical_formula_sum`, like `C22 H50 N4 O7`, with counts
+/// rounded to two decimals and counts of one left out.
+impl Display for ChemicalFormula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<String> = self
+            .counts()
+            .iter()
+            .map(|(element, count)| {
+                let count = (count * 100.0).round() / 100.0;
+                if count == 1.0 {
+                    format!("{element}")
+                } else if count.fract() == 0.0 {
+                    format!("{element}{count}")
+                } else {
+                    let count = format!("{count:.2}");
+                    format!("{element}{}", count.trim_end_matches('0'))
+                }
+            })
+            .collect();
+        write!(f, "{}", terms.join(" "))
+    }
+}
+
+/// A formula string which cannot be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormulaParseError(pub String);
+
+impl Display for FormulaParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a chemical formula", self.0)
+    }
+}
+
+impl std::error::Error for FormulaParseError {}
+
+impl FromStr for ChemicalFormula {
+    type Err = FormulaParseError;
+
+    /// Reads formulas like `C22 H50 N4 O7`, `C22H50N4O7` or
+    /// `Fe0.7 Co0.3 O`; an element without a count counts once.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || FormulaParseError(s.to_string());
+        let mut formula = Self::default();
+        let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if !chars[i].is_ascii_uppercase() {
+                return Err(error());
+            }
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_lowercase() {
+                i += 1;
+            }
+            let symbol: String = chars[start..i].iter().collect();
+            let element = ElementSymbol::from_str(&symbol).map_err(|_| error())?;
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let count = match i - start {
+                0 => 1.0,
+                _ => chars[start..i]
+                    .iter()
+                    .collect::<String>()
+                    .parse::<f64>()
+                    .map_err(|_| error())?,
+            };
+            formula.add(element, count);
+        }
+        if formula.counts.is_empty() {
+            return Err(error());
+        }
+        Ok(formula)
+    }
+}
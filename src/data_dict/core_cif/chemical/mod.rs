@@ -0,0 +1,58 @@
+//! The `_chemical_` category: the chemical formula and its weight.
+
+use crate::{
+    data_dict::{
+        reading::{optional_single_numeric, optional_single_text, text_value},
+        CifTerm, DataReadError, SingleValueTerm,
+    },
+    grammar::{DataBlock, Numeric, Tag, Value},
+};
+
+mod formula;
+
+pub use formula::{ChemicalFormula, FormulaParseError};
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum ChemicalItem {
+    Formula_moiety(String),
+    Formula_sum(ChemicalFormula),
+    Formula_weight(Numeric),
+}
+
+impl CifTerm for ChemicalItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            ChemicalItem::Formula_moiety(_) => "formula_moiety",
+            ChemicalItem::Formula_sum(_) => "formula_sum",
+            ChemicalItem::Formula_weight(_) => "formula_weight",
+        };
+        Tag::new(format!("chemical_{suffix}"))
+    }
+}
+
+impl SingleValueTerm for ChemicalItem {
+    fn value(&self) -> Value<'static> {
+        match self {
+            ChemicalItem::Formula_moiety(v) => text_value(v),
+            ChemicalItem::Formula_sum(v) => text_value(&v.to_string()),
+            ChemicalItem::Formula_weight(v) => Value::Numeric(*v),
+        }
+    }
+}
+
+/// `_chemical_formula_sum` of the data block, if given.
+pub fn read_formula_sum(data_block: &DataBlock) -> Result<Option<ChemicalFormula>, DataReadError> {
+    optional_single_text(data_block, "chemical_formula_sum")
+        .map(|text| {
+            text.parse::<ChemicalFormula>().map_err(|_| {
+                DataReadError::invalid("chemical_formula_sum", None, &text_value(&text))
+            })
+        })
+        .transpose()
+}
+
+/// `_chemical_formula_weight` of the data block, if given.
+pub fn read_formula_weight(data_block: &DataBlock) -> Result<Option<Numeric>, DataReadError> {
+    optional_single_numeric(data_block, "chemical_formula_weight")
+}
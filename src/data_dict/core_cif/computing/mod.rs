@@ -0,0 +1,219 @@
+//! The `_computing_` category: software used in each step of the structure determination.
+
+use crate::{
+    data_dict::{
+        reading::{optional_single_text, text_value},
+        CifTerm, DataReadError, SingleValueTerm,
+    },
+    grammar::{DataBlock, DataItems, Tag, Value},
+};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum ComputingItem {
+    Cell_refinement(String),
+    Data_collection(String),
+    Data_reduction(String),
+    Molecular_graphics(String),
+    Publication_material(String),
+    Structure_refinement(String),
+    Structure_solution(String),
+}
+
+impl CifTerm for ComputingItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            ComputingItem::Cell_refinement(_) => "cell_refinement",
+            ComputingItem::Data_collection(_) => "data_collection",
+            ComputingItem::Data_reduction(_) => "data_reduction",
+            ComputingItem::Molecular_graphics(_) => "molecular_graphics",
+            ComputingItem::Publication_material(_) => "publication_material",
+            ComputingItem::Structure_refinement(_) => "structure_refinement",
+            ComputingItem::Structure_solution(_) => "structure_solution",
+        };
+        Tag::new(format!("computing_{suffix}"))
+    }
+}
+
+impl SingleValueTerm for ComputingItem {
+    fn value(&self) -> Value<'static> {
+        match self {
+            ComputingItem::Cell_refinement(v)
+            | ComputingItem::Data_collection(v)
+            | ComputingItem::Data_reduction(v)
+            | ComputingItem::Molecular_graphics(v)
+            | ComputingItem::Publication_material(v)
+            | ComputingItem::Structure_refinement(v)
+            | ComputingItem::Structure_solution(v) => text_value(v),
+        }
+    }
+}
+
+/// Programs used, with references.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Computing {
+    data_collection: Option<String>,
+    cell_refinement: Option<String>,
+    data_reduction: Option<String>,
+    structure_solution: Option<String>,
+    structure_refinement: Option<String>,
+    molecular_graphics: Option<String>,
+    publication_material: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComputingBuilder {
+    computing: Computing,
+}
+
+impl ComputingBuilder {
+    pub fn with_data_collection(mut self, data_collection: impl Into<String>) -> Self {
+        self.computing.data_collection = Some(data_collection.into());
+        self
+    }
+
+    pub fn with_cell_refinement(mut self, cell_refinement: impl Into<String>) -> Self {
+        self.computing.cell_refinement = Some(cell_refinement.into());
+        self
+    }
+
+    pub fn with_data_reduction(mut self, data_reduction: impl Into<String>) -> Self {
+        self.computing.data_reduction = Some(data_reduction.into());
+        self
+    }
+
+    pub fn with_structure_solution(mut self, structure_solution: impl Into<String>) -> Self {
+        self.computing.structure_solution = Some(structure_solution.into());
+        self
+    }
+
+    /// e.g. `SHELXL-2018/3 (Sheldrick, 2015)`.
+    pub fn with_structure_refinement(mut self, structure_refinement: impl Into<String>) -> Self {
+        self.computing.structure_refinement = Some(structure_refinement.into());
+        self
+    }
+
+    pub fn with_molecular_graphics(mut self, molecular_graphics: impl Into<String>) -> Self {
+        self.computing.molecular_graphics = Some(molecular_graphics.into());
+        self
+    }
+
+    pub fn with_publication_material(mut self, publication_material: impl Into<String>) -> Self {
+        self.computing.publication_material = Some(publication_material.into());
+        self
+    }
+
+    pub fn build(self) -> Computing {
+        self.computing
+    }
+}
+
+impl Computing {
+    pub fn builder() -> ComputingBuilder {
+        ComputingBuilder::default()
+    }
+
+    pub fn data_collection(&self) -> Option<&str> {
+        self.data_collection.as_deref()
+    }
+
+    pub fn cell_refinement(&self) -> Option<&str> {
+        self.cell_refinement.as_deref()
+    }
+
+    pub fn data_reduction(&self) -> Option<&str> {
+        self.data_reduction.as_deref()
+    }
+
+    pub fn structure_solution(&self) -> Option<&str> {
+        self.structure_solution.as_deref()
+    }
+
+    pub fn structure_refinement(&self) -> Option<&str> {
+        self.structure_refinement.as_deref()
+    }
+
+    pub fn molecular_graphics(&self) -> Option<&str> {
+        self.molecular_graphics.as_deref()
+    }
+
+    pub fn publication_material(&self) -> Option<&str> {
+        self.publication_material.as_deref()
+    }
+
+    /// The items which have a value.
+    pub fn items(&self) -> Vec<ComputingItem> {
+        [
+            self.data_collection
+                .clone()
+                .map(ComputingItem::Data_collection),
+            self.cell_refinement
+                .clone()
+                .map(ComputingItem::Cell_refinement),
+            self.data_reduction
+                .clone()
+                .map(ComputingItem::Data_reduction),
+            self.structure_solution
+                .clone()
+                .map(ComputingItem::Structure_solution),
+            self.structure_refinement
+                .clone()
+                .map(ComputingItem::Structure_refinement),
+            self.molecular_graphics
+                .clone()
+                .map(ComputingItem::Molecular_graphics),
+            self.publication_material
+                .clone()
+                .map(ComputingItem::Publication_material),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn to_data_items(&self) -> Vec<DataItems<'static>> {
+        self.items()
+            .iter()
+            .map(|item| item.to_single_value_data())
+            .collect()
+    }
+
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Self, DataReadError> {
+        Ok(Self {
+            data_collection: optional_single_text(data_block, "computing_data_collection"),
+            cell_refinement: optional_single_text(data_block, "computing_cell_refinement"),
+            data_reduction: optional_single_text(data_block, "computing_data_reduction"),
+            structure_solution: optional_single_text(data_block, "computing_structure_solution"),
+            structure_refinement: optional_single_text(
+                data_block,
+                "computing_structure_refinement",
+            ),
+            molecular_graphics: optional_single_text(data_block, "computing_molecular_graphics"),
+            publication_material: optional_single_text(
+                data_block,
+                "computing_publication_material",
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Computing;
+
+    #[test]
+    fn computing_items() {
+        let computing = Computing::builder()
+            .with_structure_refinement("SHELXL-2018/3 (Sheldrick, 2015)")
+            .with_publication_material("crystal-cif-io")
+            .build();
+        let output: Vec<String> = computing
+            .to_data_items()
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+        assert!(output[0].contains("_computing_structure_refinement"));
+        assert!(output[0].contains("'SHELXL-2018/3 (Sheldrick, 2015)'"));
+        assert!(output[1].contains("crystal-cif-io"));
+    }
+}
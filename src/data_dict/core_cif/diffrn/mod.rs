@@ -0,0 +1,496 @@
+//! The `_diffrn_` category: conditions and results of the diffraction measurement.
+
+use crate::{
+    data_dict::{
+        reading::{
+            optional_single_numeric, optional_single_parsed, optional_single_text, text_value,
+        },
+        CifTerm, DataReadError, SingleValueTerm,
+    },
+    grammar::{DataBlock, DataItems, Numeric, Tag, UnsignedInteger, Value},
+};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum DiffrnItem {
+    Ambient_temperature(Numeric),
+    Detector_area_resol_mean(Numeric),
+    Measured_fraction_theta_full(Numeric),
+    Measured_fraction_theta_max(Numeric),
+    Measurement_device_type(String),
+    Measurement_method(String),
+    Radiation_monochromator(String),
+    Radiation_type(String),
+    Radiation_wavelength(Numeric),
+    Reflns_av_R_equivalents(Numeric),
+    Reflns_av_unetI_netI(Numeric),
+    Reflns_limit_h_max(i32),
+    Reflns_limit_h_min(i32),
+    Reflns_limit_k_max(i32),
+    Reflns_limit_k_min(i32),
+    Reflns_limit_l_max(i32),
+    Reflns_limit_l_min(i32),
+    Reflns_number(u32),
+    Reflns_theta_full(Numeric),
+    Reflns_theta_max(Numeric),
+    Reflns_theta_min(Numeric),
+    Source(String),
+}
+
+impl CifTerm for DiffrnItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            DiffrnItem::Ambient_temperature(_) => "ambient_temperature",
+            DiffrnItem::Detector_area_resol_mean(_) => "detector_area_resol_mean",
+            DiffrnItem::Measured_fraction_theta_full(_) => "measured_fraction_theta_full",
+            DiffrnItem::Measured_fraction_theta_max(_) => "measured_fraction_theta_max",
+            DiffrnItem::Measurement_device_type(_) => "measurement_device_type",
+            DiffrnItem::Measurement_method(_) => "measurement_method",
+            DiffrnItem::Radiation_monochromator(_) => "radiation_monochromator",
+            DiffrnItem::Radiation_type(_) => "radiation_type",
+            DiffrnItem::Radiation_wavelength(_) => "radiation_wavelength",
+            DiffrnItem::Reflns_av_R_equivalents(_) => "reflns_av_R_equivalents",
+            DiffrnItem::Reflns_av_unetI_netI(_) => "reflns_av_unetI/netI",
+            DiffrnItem::Reflns_limit_h_max(_) => "reflns_limit_h_max",
+            DiffrnItem::Reflns_limit_h_min(_) => "reflns_limit_h_min",
+            DiffrnItem::Reflns_limit_k_max(_) => "reflns_limit_k_max",
+            DiffrnItem::Reflns_limit_k_min(_) => "reflns_limit_k_min",
+            DiffrnItem::Reflns_limit_l_max(_) => "reflns_limit_l_max",
+            DiffrnItem::Reflns_limit_l_min(_) => "reflns_limit_l_min",
+            DiffrnItem::Reflns_number(_) => "reflns_number",
+            DiffrnItem::Reflns_theta_full(_) => "reflns_theta_full",
+            DiffrnItem::Reflns_theta_max(_) => "reflns_theta_max",
+            DiffrnItem::Reflns_theta_min(_) => "reflns_theta_min",
+            DiffrnItem::Source(_) => "source",
+        };
+        Tag::new(format!("diffrn_{suffix}"))
+    }
+}
+
+impl SingleValueTerm for DiffrnItem {
+    fn value(&self) -> Value<'static> {
+        match self {
+            DiffrnItem::Ambient_temperature(v)
+            | DiffrnItem::Detector_area_resol_mean(v)
+            | DiffrnItem::Measured_fraction_theta_full(v)
+            | DiffrnItem::Measured_fraction_theta_max(v)
+            | DiffrnItem::Radiation_wavelength(v)
+            | DiffrnItem::Reflns_av_R_equivalents(v)
+            | DiffrnItem::Reflns_av_unetI_netI(v)
+            | DiffrnItem::Reflns_theta_full(v)
+            | DiffrnItem::Reflns_theta_max(v)
+            | DiffrnItem::Reflns_theta_min(v) => Value::Numeric(*v),
+            DiffrnItem::Measurement_device_type(v)
+            | DiffrnItem::Measurement_method(v)
+            | DiffrnItem::Radiation_monochromator(v)
+            | DiffrnItem::Radiation_type(v)
+            | DiffrnItem::Source(v) => text_value(v),
+            DiffrnItem::Reflns_limit_h_max(v)
+            | DiffrnItem::Reflns_limit_h_min(v)
+            | DiffrnItem::Reflns_limit_k_max(v)
+            | DiffrnItem::Reflns_limit_k_min(v)
+            | DiffrnItem::Reflns_limit_l_max(v)
+            | DiffrnItem::Reflns_limit_l_min(v) => Numeric::from(*v).into(),
+            DiffrnItem::Reflns_number(v) => UnsignedInteger(*v).into(),
+        }
+    }
+}
+
+/// Diffraction experiment data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diffrn {
+    ambient_temperature: Option<Numeric>,
+    radiation_type: Option<String>,
+    radiation_wavelength: Option<Numeric>,
+    radiation_monochromator: Option<String>,
+    source: Option<String>,
+    measurement_device_type: Option<String>,
+    measurement_method: Option<String>,
+    detector_area_resol_mean: Option<Numeric>,
+    reflns_number: Option<u32>,
+    reflns_av_r_equivalents: Option<Numeric>,
+    reflns_av_uneti_neti: Option<Numeric>,
+    reflns_limit_h_min: Option<i32>,
+    reflns_limit_h_max: Option<i32>,
+    reflns_limit_k_min: Option<i32>,
+    reflns_limit_k_max: Option<i32>,
+    reflns_limit_l_min: Option<i32>,
+    reflns_limit_l_max: Option<i32>,
+    reflns_theta_min: Option<Numeric>,
+    reflns_theta_max: Option<Numeric>,
+    reflns_theta_full: Option<Numeric>,
+    measured_fraction_theta_max: Option<Numeric>,
+    measured_fraction_theta_full: Option<Numeric>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffrnBuilder {
+    diffrn: Diffrn,
+}
+
+impl DiffrnBuilder {
+    /// Mean temperature during the measurement, in kelvins.
+    pub fn with_ambient_temperature(mut self, ambient_temperature: Numeric) -> Self {
+        self.diffrn.ambient_temperature = Some(ambient_temperature);
+        self
+    }
+
+    /// e.g. `Mo K\a`.
+    pub fn with_radiation_type(mut self, radiation_type: impl Into<String>) -> Self {
+        self.diffrn.radiation_type = Some(radiation_type.into());
+        self
+    }
+
+    /// In Ångström.
+    pub fn with_radiation_wavelength(mut self, radiation_wavelength: Numeric) -> Self {
+        self.diffrn.radiation_wavelength = Some(radiation_wavelength);
+        self
+    }
+
+    pub fn with_radiation_monochromator(
+        mut self,
+        radiation_monochromator: impl Into<String>,
+    ) -> Self {
+        self.diffrn.radiation_monochromator = Some(radiation_monochromator.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.diffrn.source = Some(source.into());
+        self
+    }
+
+    pub fn with_measurement_device_type(
+        mut self,
+        measurement_device_type: impl Into<String>,
+    ) -> Self {
+        self.diffrn.measurement_device_type = Some(measurement_device_type.into());
+        self
+    }
+
+    pub fn with_measurement_method(mut self, measurement_method: impl Into<String>) -> Self {
+        self.diffrn.measurement_method = Some(measurement_method.into());
+        self
+    }
+
+    /// In pixels per millimetre.
+    pub fn with_detector_area_resol_mean(mut self, detector_area_resol_mean: Numeric) -> Self {
+        self.diffrn.detector_area_resol_mean = Some(detector_area_resol_mean);
+        self
+    }
+
+    /// Total number of measured reflections, including symmetry equivalents.
+    pub fn with_reflns_number(mut self, reflns_number: u32) -> Self {
+        self.diffrn.reflns_number = Some(reflns_number);
+        self
+    }
+
+    pub fn with_reflns_av_r_equivalents(mut self, reflns_av_r_equivalents: Numeric) -> Self {
+        self.diffrn.reflns_av_r_equivalents = Some(reflns_av_r_equivalents);
+        self
+    }
+
+    pub fn with_reflns_av_uneti_neti(mut self, reflns_av_uneti_neti: Numeric) -> Self {
+        self.diffrn.reflns_av_uneti_neti = Some(reflns_av_uneti_neti);
+        self
+    }
+
+    pub fn with_reflns_limit_h_min(mut self, reflns_limit_h_min: i32) -> Self {
+        self.diffrn.reflns_limit_h_min = Some(reflns_limit_h_min);
+        self
+    }
+
+    pub fn with_reflns_limit_h_max(mut self, reflns_limit_h_max: i32) -> Self {
+        self.diffrn.reflns_limit_h_max = Some(reflns_limit_h_max);
+        self
+    }
+
+    pub fn with_reflns_limit_k_min(mut self, reflns_limit_k_min: i32) -> Self {
+        self.diffrn.reflns_limit_k_min = Some(reflns_limit_k_min);
+        self
+    }
+
+    pub fn with_reflns_limit_k_max(mut self, reflns_limit_k_max: i32) -> Self {
+        self.diffrn.reflns_limit_k_max = Some(reflns_limit_k_max);
+        self
+    }
+
+    pub fn with_reflns_limit_l_min(mut self, reflns_limit_l_min: i32) -> Self {
+        self.diffrn.reflns_limit_l_min = Some(reflns_limit_l_min);
+        self
+    }
+
+    pub fn with_reflns_limit_l_max(mut self, reflns_limit_l_max: i32) -> Self {
+        self.diffrn.reflns_limit_l_max = Some(reflns_limit_l_max);
+        self
+    }
+
+    /// In degrees.
+    pub fn with_reflns_theta_min(mut self, reflns_theta_min: Numeric) -> Self {
+        self.diffrn.reflns_theta_min = Some(reflns_theta_min);
+        self
+    }
+
+    /// In degrees.
+    pub fn with_reflns_theta_max(mut self, reflns_theta_max: Numeric) -> Self {
+        self.diffrn.reflns_theta_max = Some(reflns_theta_max);
+        self
+    }
+
+    /// In degrees.
+    pub fn with_reflns_theta_full(mut self, reflns_theta_full: Numeric) -> Self {
+        self.diffrn.reflns_theta_full = Some(reflns_theta_full);
+        self
+    }
+
+    pub fn with_measured_fraction_theta_max(
+        mut self,
+        measured_fraction_theta_max: Numeric,
+    ) -> Self {
+        self.diffrn.measured_fraction_theta_max = Some(measured_fraction_theta_max);
+        self
+    }
+
+    pub fn with_measured_fraction_theta_full(
+        mut self,
+        measured_fraction_theta_full: Numeric,
+    ) -> Self {
+        self.diffrn.measured_fraction_theta_full = Some(measured_fraction_theta_full);
+        self
+    }
+
+    pub fn build(self) -> Diffrn {
+        self.diffrn
+    }
+}
+
+impl Diffrn {
+    pub fn builder() -> DiffrnBuilder {
+        DiffrnBuilder::default()
+    }
+
+    pub fn ambient_temperature(&self) -> Option<Numeric> {
+        self.ambient_temperature
+    }
+
+    pub fn radiation_type(&self) -> Option<&str> {
+        self.radiation_type.as_deref()
+    }
+
+    pub fn radiation_wavelength(&self) -> Option<Numeric> {
+        self.radiation_wavelength
+    }
+
+    pub fn radiation_monochromator(&self) -> Option<&str> {
+        self.radiation_monochromator.as_deref()
+    }
+
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    pub fn measurement_device_type(&self) -> Option<&str> {
+        self.measurement_device_type.as_deref()
+    }
+
+    pub fn measurement_method(&self) -> Option<&str> {
+        self.measurement_method.as_deref()
+    }
+
+    pub fn detector_area_resol_mean(&self) -> Option<Numeric> {
+        self.detector_area_resol_mean
+    }
+
+    pub fn reflns_number(&self) -> Option<u32> {
+        self.reflns_number
+    }
+
+    pub fn reflns_av_r_equivalents(&self) -> Option<Numeric> {
+        self.reflns_av_r_equivalents
+    }
+
+    pub fn reflns_av_uneti_neti(&self) -> Option<Numeric> {
+        self.reflns_av_uneti_neti
+    }
+
+    pub fn reflns_limit_h_min(&self) -> Option<i32> {
+        self.reflns_limit_h_min
+    }
+
+    pub fn reflns_limit_h_max(&self) -> Option<i32> {
+        self.reflns_limit_h_max
+    }
+
+    pub fn reflns_limit_k_min(&self) -> Option<i32> {
+        self.reflns_limit_k_min
+    }
+
+    pub fn reflns_limit_k_max(&self) -> Option<i32> {
+        self.reflns_limit_k_max
+    }
+
+    pub fn reflns_limit_l_min(&self) -> Option<i32> {
+        self.reflns_limit_l_min
+    }
+
+    pub fn reflns_limit_l_max(&self) -> Option<i32> {
+        self.reflns_limit_l_max
+    }
+
+    pub fn reflns_theta_min(&self) -> Option<Numeric> {
+        self.reflns_theta_min
+    }
+
+    pub fn reflns_theta_max(&self) -> Option<Numeric> {
+        self.reflns_theta_max
+    }
+
+    pub fn reflns_theta_full(&self) -> Option<Numeric> {
+        self.reflns_theta_full
+    }
+
+    pub fn measured_fraction_theta_max(&self) -> Option<Numeric> {
+        self.measured_fraction_theta_max
+    }
+
+    pub fn measured_fraction_theta_full(&self) -> Option<Numeric> {
+        self.measured_fraction_theta_full
+    }
+
+    /// The items which have a value.
+    pub fn items(&self) -> Vec<DiffrnItem> {
+        [
+            self.ambient_temperature
+                .map(DiffrnItem::Ambient_temperature),
+            self.radiation_type.clone().map(DiffrnItem::Radiation_type),
+            self.radiation_wavelength
+                .map(DiffrnItem::Radiation_wavelength),
+            self.radiation_monochromator
+                .clone()
+                .map(DiffrnItem::Radiation_monochromator),
+            self.source.clone().map(DiffrnItem::Source),
+            self.measurement_device_type
+                .clone()
+                .map(DiffrnItem::Measurement_device_type),
+            self.measurement_method
+                .clone()
+                .map(DiffrnItem::Measurement_method),
+            self.detector_area_resol_mean
+                .map(DiffrnItem::Detector_area_resol_mean),
+            self.reflns_number.map(DiffrnItem::Reflns_number),
+            self.reflns_av_r_equivalents
+                .map(DiffrnItem::Reflns_av_R_equivalents),
+            self.reflns_av_uneti_neti
+                .map(DiffrnItem::Reflns_av_unetI_netI),
+            self.reflns_limit_h_min.map(DiffrnItem::Reflns_limit_h_min),
+            self.reflns_limit_h_max.map(DiffrnItem::Reflns_limit_h_max),
+            self.reflns_limit_k_min.map(DiffrnItem::Reflns_limit_k_min),
+            self.reflns_limit_k_max.map(DiffrnItem::Reflns_limit_k_max),
+            self.reflns_limit_l_min.map(DiffrnItem::Reflns_limit_l_min),
+            self.reflns_limit_l_max.map(DiffrnItem::Reflns_limit_l_max),
+            self.reflns_theta_min.map(DiffrnItem::Reflns_theta_min),
+            self.reflns_theta_max.map(DiffrnItem::Reflns_theta_max),
+            self.reflns_theta_full.map(DiffrnItem::Reflns_theta_full),
+            self.measured_fraction_theta_max
+                .map(DiffrnItem::Measured_fraction_theta_max),
+            self.measured_fraction_theta_full
+                .map(DiffrnItem::Measured_fraction_theta_full),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn to_data_items(&self) -> Vec<DataItems<'static>> {
+        self.items()
+            .iter()
+            .map(|item| item.to_single_value_data())
+            .collect()
+    }
+
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Self, DataReadError> {
+        Ok(Self {
+            ambient_temperature: optional_single_numeric(data_block, "diffrn_ambient_temperature")?,
+            radiation_type: optional_single_text(data_block, "diffrn_radiation_type"),
+            radiation_wavelength: optional_single_numeric(
+                data_block,
+                "diffrn_radiation_wavelength",
+            )?,
+            radiation_monochromator: optional_single_text(
+                data_block,
+                "diffrn_radiation_monochromator",
+            ),
+            source: optional_single_text(data_block, "diffrn_source"),
+            measurement_device_type: optional_single_text(
+                data_block,
+                "diffrn_measurement_device_type",
+            ),
+            measurement_method: optional_single_text(data_block, "diffrn_measurement_method"),
+            detector_area_resol_mean: optional_single_numeric(
+                data_block,
+                "diffrn_detector_area_resol_mean",
+            )?,
+            reflns_number: optional_single_parsed(data_block, "diffrn_reflns_number")?,
+            reflns_av_r_equivalents: optional_single_numeric(
+                data_block,
+                "diffrn_reflns_av_R_equivalents",
+            )?,
+            reflns_av_uneti_neti: optional_single_numeric(
+                data_block,
+                "diffrn_reflns_av_unetI/netI",
+            )?,
+            reflns_limit_h_min: optional_single_parsed(data_block, "diffrn_reflns_limit_h_min")?,
+            reflns_limit_h_max: optional_single_parsed(data_block, "diffrn_reflns_limit_h_max")?,
+            reflns_limit_k_min: optional_single_parsed(data_block, "diffrn_reflns_limit_k_min")?,
+            reflns_limit_k_max: optional_single_parsed(data_block, "diffrn_reflns_limit_k_max")?,
+            reflns_limit_l_min: optional_single_parsed(data_block, "diffrn_reflns_limit_l_min")?,
+            reflns_limit_l_max: optional_single_parsed(data_block, "diffrn_reflns_limit_l_max")?,
+            reflns_theta_min: optional_single_numeric(data_block, "diffrn_reflns_theta_min")?,
+            reflns_theta_max: optional_single_numeric(data_block, "diffrn_reflns_theta_max")?,
+            reflns_theta_full: optional_single_numeric(data_block, "diffrn_reflns_theta_full")?,
+            measured_fraction_theta_max: optional_single_numeric(
+                data_block,
+                "diffrn_measured_fraction_theta_max",
+            )?,
+            measured_fraction_theta_full: optional_single_numeric(
+                data_block,
+                "diffrn_measured_fraction_theta_full",
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, SyntacticUnit};
+
+    use super::Diffrn;
+
+    #[test]
+    fn diffrn_items() {
+        let mut input = r#"data_I
+_diffrn_ambient_temperature       100(2)
+_diffrn_radiation_wavelength      0.71073
+_diffrn_radiation_type            MoK\a
+_diffrn_measurement_device_type   'Bruker APEX-II CCD'
+_diffrn_reflns_number             24719
+_diffrn_reflns_limit_h_min        -12
+_diffrn_reflns_av_unetI/netI      0.0311
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let diffrn = Diffrn::from_data_block(&block).unwrap();
+        assert_eq!(diffrn.reflns_limit_h_min(), Some(-12));
+        assert_eq!(diffrn.reflns_number(), Some(24719));
+        assert_eq!(diffrn.measurement_device_type(), Some("Bruker APEX-II CCD"));
+        assert!(diffrn.reflns_av_uneti_neti().is_some());
+        let output: Vec<String> = diffrn
+            .to_data_items()
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+        assert!(output
+            .iter()
+            .any(|line| line.contains("'Bruker APEX-II CCD'")));
+        assert!(output.iter().any(|line| line.contains("100(2)")));
+    }
+}
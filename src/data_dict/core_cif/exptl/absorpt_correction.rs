@@ -0,0 +1,68 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    data_dict::{reading::text_value, InvalidEnumeration},
+    grammar::Value,
+};
+
+/// `_exptl_absorpt_correction_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbsorptCorrectionType {
+    Analytical,
+    Cylinder,
+    Empirical,
+    Gaussian,
+    Integration,
+    MultiScan,
+    None,
+    Numerical,
+    PsiScan,
+    Refdelf,
+    Sphere,
+}
+
+impl Display for AbsorptCorrectionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let option = match self {
+            AbsorptCorrectionType::Analytical => "analytical",
+            AbsorptCorrectionType::Cylinder => "cylinder",
+            AbsorptCorrectionType::Empirical => "empirical",
+            AbsorptCorrectionType::Gaussian => "gaussian",
+            AbsorptCorrectionType::Integration => "integration",
+            AbsorptCorrectionType::MultiScan => "multi-scan",
+            AbsorptCorrectionType::None => "none",
+            AbsorptCorrectionType::Numerical => "numerical",
+            AbsorptCorrectionType::PsiScan => "psi-scan",
+            AbsorptCorrectionType::Refdelf => "refdelf",
+            AbsorptCorrectionType::Sphere => "sphere",
+        };
+        f.write_str(option)
+    }
+}
+
+impl FromStr for AbsorptCorrectionType {
+    type Err = InvalidEnumeration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "analytical" => Ok(Self::Analytical),
+            "cylinder" => Ok(Self::Cylinder),
+            "empirical" => Ok(Self::Empirical),
+            "gaussian" => Ok(Self::Gaussian),
+            "integration" => Ok(Self::Integration),
+            "multi-scan" => Ok(Self::MultiScan),
+            "none" => Ok(Self::None),
+            "numerical" => Ok(Self::Numerical),
+            "psi-scan" => Ok(Self::PsiScan),
+            "refdelf" => Ok(Self::Refdelf),
+            "sphere" => Ok(Self::Sphere),
+            _ => Err(InvalidEnumeration(s.to_string())),
+        }
+    }
+}
+
+impl From<AbsorptCorrectionType> for Value<'_> {
+    fn from(value: AbsorptCorrectionType) -> Self {
+        text_value(&value.to_string())
+    }
+}
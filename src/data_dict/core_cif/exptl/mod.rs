@@ -0,0 +1,367 @@
+//! The `_exptl_` category: the crystal used in the experiment and its absorption correction.
+
+use crate::{
+    data_dict::{
+        reading::{
+            optional_single_numeric, optional_single_parsed, optional_single_text, text_value,
+        },
+        CifTerm, DataReadError, SingleValueTerm,
+    },
+    grammar::{DataBlock, DataItems, Numeric, Tag, Value},
+};
+
+mod absorpt_correction;
+
+pub use absorpt_correction::AbsorptCorrectionType;
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum ExptlItem {
+    Absorpt_coefficient_mu(Numeric),
+    Absorpt_correction_T_max(Numeric),
+    Absorpt_correction_T_min(Numeric),
+    Absorpt_correction_type(AbsorptCorrectionType),
+    Absorpt_process_details(String),
+    Crystal_colour(String),
+    Crystal_density_diffrn(Numeric),
+    Crystal_density_method(String),
+    Crystal_description(String),
+    Crystal_F_000(Numeric),
+    Crystal_size_max(Numeric),
+    Crystal_size_mid(Numeric),
+    Crystal_size_min(Numeric),
+}
+
+impl CifTerm for ExptlItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            ExptlItem::Absorpt_coefficient_mu(_) => "absorpt_coefficient_mu",
+            ExptlItem::Absorpt_correction_T_max(_) => "absorpt_correction_T_max",
+            ExptlItem::Absorpt_correction_T_min(_) => "absorpt_correction_T_min",
+            ExptlItem::Absorpt_correction_type(_) => "absorpt_correction_type",
+            ExptlItem::Absorpt_process_details(_) => "absorpt_process_details",
+            ExptlItem::Crystal_colour(_) => "crystal_colour",
+            ExptlItem::Crystal_density_diffrn(_) => "crystal_density_diffrn",
+            ExptlItem::Crystal_density_method(_) => "crystal_density_method",
+            ExptlItem::Crystal_description(_) => "crystal_description",
+            ExptlItem::Crystal_F_000(_) => "crystal_F_000",
+            ExptlItem::Crystal_size_max(_) => "crystal_size_max",
+            ExptlItem::Crystal_size_mid(_) => "crystal_size_mid",
+            ExptlItem::Crystal_size_min(_) => "crystal_size_min",
+        };
+        Tag::new(format!("exptl_{suffix}"))
+    }
+}
+
+impl SingleValueTerm for ExptlItem {
+    fn value(&self) -> Value<'static> {
+        match self {
+            ExptlItem::Absorpt_coefficient_mu(v)
+            | ExptlItem::Absorpt_correction_T_max(v)
+            | ExptlItem::Absorpt_correction_T_min(v)
+            | ExptlItem::Crystal_density_diffrn(v)
+            | ExptlItem::Crystal_F_000(v)
+            | ExptlItem::Crystal_size_max(v)
+            | ExptlItem::Crystal_size_mid(v)
+            | ExptlItem::Crystal_size_min(v) => Value::Numeric(*v),
+            ExptlItem::Absorpt_correction_type(v) => (*v).into(),
+            ExptlItem::Absorpt_process_details(v)
+            | ExptlItem::Crystal_colour(v)
+            | ExptlItem::Crystal_density_method(v)
+            | ExptlItem::Crystal_description(v) => text_value(v),
+        }
+    }
+}
+
+/// Experimental crystal data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Exptl {
+    crystal_description: Option<String>,
+    crystal_colour: Option<String>,
+    crystal_size_max: Option<Numeric>,
+    crystal_size_mid: Option<Numeric>,
+    crystal_size_min: Option<Numeric>,
+    crystal_density_diffrn: Option<Numeric>,
+    crystal_density_method: Option<String>,
+    crystal_f_000: Option<Numeric>,
+    absorpt_coefficient_mu: Option<Numeric>,
+    absorpt_correction_type: Option<AbsorptCorrectionType>,
+    absorpt_correction_t_min: Option<Numeric>,
+    absorpt_correction_t_max: Option<Numeric>,
+    absorpt_process_details: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExptlBuilder {
+    exptl: Exptl,
+}
+
+impl ExptlBuilder {
+    /// Crystal habit, e.g. `block`.
+    pub fn with_crystal_description(mut self, crystal_description: impl Into<String>) -> Self {
+        self.exptl.crystal_description = Some(crystal_description.into());
+        self
+    }
+
+    pub fn with_crystal_colour(mut self, crystal_colour: impl Into<String>) -> Self {
+        self.exptl.crystal_colour = Some(crystal_colour.into());
+        self
+    }
+
+    /// In millimetres.
+    pub fn with_crystal_size_max(mut self, crystal_size_max: Numeric) -> Self {
+        self.exptl.crystal_size_max = Some(crystal_size_max);
+        self
+    }
+
+    /// In millimetres.
+    pub fn with_crystal_size_mid(mut self, crystal_size_mid: Numeric) -> Self {
+        self.exptl.crystal_size_mid = Some(crystal_size_mid);
+        self
+    }
+
+    /// In millimetres.
+    pub fn with_crystal_size_min(mut self, crystal_size_min: Numeric) -> Self {
+        self.exptl.crystal_size_min = Some(crystal_size_min);
+        self
+    }
+
+    /// Density calculated from the structure, in Mg m⁻³.
+    pub fn with_crystal_density_diffrn(mut self, crystal_density_diffrn: Numeric) -> Self {
+        self.exptl.crystal_density_diffrn = Some(crystal_density_diffrn);
+        self
+    }
+
+    pub fn with_crystal_density_method(
+        mut self,
+        crystal_density_method: impl Into<String>,
+    ) -> Self {
+        self.exptl.crystal_density_method = Some(crystal_density_method.into());
+        self
+    }
+
+    /// Number of electrons in the unit cell.
+    pub fn with_crystal_f_000(mut self, crystal_f_000: Numeric) -> Self {
+        self.exptl.crystal_f_000 = Some(crystal_f_000);
+        self
+    }
+
+    /// In mm⁻¹.
+    pub fn with_absorpt_coefficient_mu(mut self, absorpt_coefficient_mu: Numeric) -> Self {
+        self.exptl.absorpt_coefficient_mu = Some(absorpt_coefficient_mu);
+        self
+    }
+
+    pub fn with_absorpt_correction_type(
+        mut self,
+        absorpt_correction_type: AbsorptCorrectionType,
+    ) -> Self {
+        self.exptl.absorpt_correction_type = Some(absorpt_correction_type);
+        self
+    }
+
+    pub fn with_absorpt_correction_t_min(mut self, absorpt_correction_t_min: Numeric) -> Self {
+        self.exptl.absorpt_correction_t_min = Some(absorpt_correction_t_min);
+        self
+    }
+
+    pub fn with_absorpt_correction_t_max(mut self, absorpt_correction_t_max: Numeric) -> Self {
+        self.exptl.absorpt_correction_t_max = Some(absorpt_correction_t_max);
+        self
+    }
+
+    pub fn with_absorpt_process_details(
+        mut self,
+        absorpt_process_details: impl Into<String>,
+    ) -> Self {
+        self.exptl.absorpt_process_details = Some(absorpt_process_details.into());
+        self
+    }
+
+    pub fn build(self) -> Exptl {
+        self.exptl
+    }
+}
+
+impl Exptl {
+    pub fn builder() -> ExptlBuilder {
+        ExptlBuilder::default()
+    }
+
+    pub fn crystal_description(&self) -> Option<&str> {
+        self.crystal_description.as_deref()
+    }
+
+    pub fn crystal_colour(&self) -> Option<&str> {
+        self.crystal_colour.as_deref()
+    }
+
+    pub fn crystal_size_max(&self) -> Option<Numeric> {
+        self.crystal_size_max
+    }
+
+    pub fn crystal_size_mid(&self) -> Option<Numeric> {
+        self.crystal_size_mid
+    }
+
+    pub fn crystal_size_min(&self) -> Option<Numeric> {
+        self.crystal_size_min
+    }
+
+    pub fn crystal_density_diffrn(&self) -> Option<Numeric> {
+        self.crystal_density_diffrn
+    }
+
+    pub fn crystal_density_method(&self) -> Option<&str> {
+        self.crystal_density_method.as_deref()
+    }
+
+    pub fn crystal_f_000(&self) -> Option<Numeric> {
+        self.crystal_f_000
+    }
+
+    pub fn absorpt_coefficient_mu(&self) -> Option<Numeric> {
+        self.absorpt_coefficient_mu
+    }
+
+    pub fn absorpt_correction_type(&self) -> Option<AbsorptCorrectionType> {
+        self.absorpt_correction_type
+    }
+
+    pub fn absorpt_correction_t_min(&self) -> Option<Numeric> {
+        self.absorpt_correction_t_min
+    }
+
+    pub fn absorpt_correction_t_max(&self) -> Option<Numeric> {
+        self.absorpt_correction_t_max
+    }
+
+    pub fn absorpt_process_details(&self) -> Option<&str> {
+        self.absorpt_process_details.as_deref()
+    }
+
+    /// The items which have a value.
+    pub fn items(&self) -> Vec<ExptlItem> {
+        [
+            self.crystal_description
+                .clone()
+                .map(ExptlItem::Crystal_description),
+            self.crystal_colour.clone().map(ExptlItem::Crystal_colour),
+            self.crystal_size_max.map(ExptlItem::Crystal_size_max),
+            self.crystal_size_mid.map(ExptlItem::Crystal_size_mid),
+            self.crystal_size_min.map(ExptlItem::Crystal_size_min),
+            self.crystal_density_diffrn
+                .map(ExptlItem::Crystal_density_diffrn),
+            self.crystal_density_method
+                .clone()
+                .map(ExptlItem::Crystal_density_method),
+            self.crystal_f_000.map(ExptlItem::Crystal_F_000),
+            self.absorpt_coefficient_mu
+                .map(ExptlItem::Absorpt_coefficient_mu),
+            self.absorpt_correction_type
+                .map(ExptlItem::Absorpt_correction_type),
+            self.absorpt_correction_t_min
+                .map(ExptlItem::Absorpt_correction_T_min),
+            self.absorpt_correction_t_max
+                .map(ExptlItem::Absorpt_correction_T_max),
+            self.absorpt_process_details
+                .clone()
+                .map(ExptlItem::Absorpt_process_details),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn to_data_items(&self) -> Vec<DataItems<'static>> {
+        self.items()
+            .iter()
+            .map(|item| item.to_single_value_data())
+            .collect()
+    }
+
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Self, DataReadError> {
+        Ok(Self {
+            crystal_description: optional_single_text(data_block, "exptl_crystal_description"),
+            crystal_colour: optional_single_text(data_block, "exptl_crystal_colour"),
+            crystal_size_max: optional_single_numeric(data_block, "exptl_crystal_size_max")?,
+            crystal_size_mid: optional_single_numeric(data_block, "exptl_crystal_size_mid")?,
+            crystal_size_min: optional_single_numeric(data_block, "exptl_crystal_size_min")?,
+            crystal_density_diffrn: optional_single_numeric(
+                data_block,
+                "exptl_crystal_density_diffrn",
+            )?,
+            crystal_density_method: optional_single_text(
+                data_block,
+                "exptl_crystal_density_method",
+            ),
+            crystal_f_000: optional_single_numeric(data_block, "exptl_crystal_F_000")?,
+            absorpt_coefficient_mu: optional_single_numeric(
+                data_block,
+                "exptl_absorpt_coefficient_mu",
+            )?,
+            absorpt_correction_type: optional_single_parsed(
+                data_block,
+                "exptl_absorpt_correction_type",
+            )?,
+            absorpt_correction_t_min: optional_single_numeric(
+                data_block,
+                "exptl_absorpt_correction_T_min",
+            )?,
+            absorpt_correction_t_max: optional_single_numeric(
+                data_block,
+                "exptl_absorpt_correction_T_max",
+            )?,
+            absorpt_process_details: optional_single_text(
+                data_block,
+                "exptl_absorpt_process_details",
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, Numeric, SyntacticUnit};
+
+    use super::{AbsorptCorrectionType, Exptl};
+
+    #[test]
+    fn exptl_items() {
+        let mut input = r#"data_I
+_exptl_crystal_description        block
+_exptl_crystal_size_max           0.32
+_exptl_absorpt_correction_type    multi-scan
+_exptl_absorpt_process_details
+;
+ SADABS (Bruker, 2016)
+;
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let exptl = Exptl::from_data_block(&block).unwrap();
+        assert_eq!(
+            exptl.absorpt_correction_type(),
+            Some(AbsorptCorrectionType::MultiScan)
+        );
+        assert_eq!(
+            exptl.absorpt_process_details(),
+            Some("SADABS (Bruker, 2016)")
+        );
+        assert_eq!(
+            exptl.crystal_size_max().map(|s| s.as_f64() as f32),
+            Some(0.32)
+        );
+
+        let exptl = Exptl::builder()
+            .with_crystal_colour("pale yellow")
+            .with_crystal_size_min(Numeric::with_decimals(0.1, 2))
+            .build();
+        let output: Vec<String> = exptl
+            .to_data_items()
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+        assert!(output[0].contains("'pale yellow'"));
+        assert!(output[1].contains("0.10"));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    data_dict::{
+        reading::{
+            find_column, optional_parsed, required_column, required_numeric, required_text,
+            value_or_unknown, DataReadError,
+        },
+        CifTerm, LoopCategory, LoopValueTerm,
+    },
+    grammar::{LoopColumns, Numeric, Tag, Value},
+};
+
+use super::{label_values, site_symmetry_values, PublFlag, SiteSymmetryCode};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum GeomAngleLoopItem {
+    Angle(Vec<Numeric>),
+    Atom_site_label_1(Vec<String>),
+    Atom_site_label_2(Vec<String>),
+    Atom_site_label_3(Vec<String>),
+    Publ_flag(Vec<Option<PublFlag>>),
+    Site_symmetry_1(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_2(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_3(Vec<Option<SiteSymmetryCode>>),
+}
+
+impl CifTerm for GeomAngleLoopItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            GeomAngleLoopItem::Angle(_) => "",
+            GeomAngleLoopItem::Atom_site_label_1(_) => "_atom_site_label_1",
+            GeomAngleLoopItem::Atom_site_label_2(_) => "_atom_site_label_2",
+            GeomAngleLoopItem::Atom_site_label_3(_) => "_atom_site_label_3",
+            GeomAngleLoopItem::Publ_flag(_) => "_publ_flag",
+            GeomAngleLoopItem::Site_symmetry_1(_) => "_site_symmetry_1",
+            GeomAngleLoopItem::Site_symmetry_2(_) => "_site_symmetry_2",
+            GeomAngleLoopItem::Site_symmetry_3(_) => "_site_symmetry_3",
+        };
+        Tag::new(format!("geom_angle{suffix}"))
+    }
+}
+
+impl LoopValueTerm for GeomAngleLoopItem {
+    fn values(&self) -> Vec<Value<'static>> {
+        match self {
+            GeomAngleLoopItem::Angle(a) => a.iter().copied().map(Value::from).collect(),
+            GeomAngleLoopItem::Atom_site_label_1(labels)
+            | GeomAngleLoopItem::Atom_site_label_2(labels)
+            | GeomAngleLoopItem::Atom_site_label_3(labels) => label_values(labels),
+            GeomAngleLoopItem::Publ_flag(flags) => {
+                flags.iter().copied().map(value_or_unknown).collect()
+            }
+            GeomAngleLoopItem::Site_symmetry_1(codes)
+            | GeomAngleLoopItem::Site_symmetry_2(codes)
+            | GeomAngleLoopItem::Site_symmetry_3(codes) => site_symmetry_values(codes),
+        }
+    }
+}
+
+/// One row of the `_geom_angle_` loop. The angle is at the second atom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeomAngle {
+    atom_site_labels: [String; 3],
+    angle: Numeric,
+    site_symmetry: [Option<SiteSymmetryCode>; 3],
+    publ_flag: Option<PublFlag>,
+}
+
+impl GeomAngle {
+    pub fn new(atom_site_labels: [String; 3], angle: Numeric) -> Self {
+        Self {
+            atom_site_labels,
+            angle,
+            site_symmetry: [None; 3],
+            publ_flag: None,
+        }
+    }
+
+    pub fn with_site_symmetry(mut self, site_symmetry: [Option<SiteSymmetryCode>; 3]) -> Self {
+        self.site_symmetry = site_symmetry;
+        self
+    }
+
+    pub fn with_publ_flag(mut self, publ_flag: PublFlag) -> Self {
+        self.publ_flag = Some(publ_flag);
+        self
+    }
+
+    pub fn atom_site_labels(&self) -> &[String; 3] {
+        &self.atom_site_labels
+    }
+
+    /// Angle in degrees.
+    pub fn angle(&self) -> Numeric {
+        self.angle
+    }
+
+    pub fn site_symmetry(&self) -> [Option<SiteSymmetryCode>; 3] {
+        self.site_symmetry
+    }
+
+    pub fn publ_flag(&self) -> Option<PublFlag> {
+        self.publ_flag
+    }
+}
+
+impl LoopCategory for GeomAngle {
+    const KEY_TAG: &'static str = "geom_angle_atom_site_label_1";
+
+    fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<Self>, DataReadError> {
+        let labels = [
+            required_column(columns, "geom_angle_atom_site_label_1")?,
+            required_column(columns, "geom_angle_atom_site_label_2")?,
+            required_column(columns, "geom_angle_atom_site_label_3")?,
+        ];
+        let angle = required_column(columns, "geom_angle")?;
+        let site_symmetry = [
+            find_column(columns, "geom_angle_site_symmetry_1"),
+            find_column(columns, "geom_angle_site_symmetry_2"),
+            find_column(columns, "geom_angle_site_symmetry_3"),
+        ];
+        let publ_flag = find_column(columns, "geom_angle_publ_flag");
+        (0..labels[0].values().len())
+            .map(|row| {
+                Ok(GeomAngle {
+                    atom_site_labels: [
+                        required_text(labels[0], row)?,
+                        required_text(labels[1], row)?,
+                        required_text(labels[2], row)?,
+                    ],
+                    angle: required_numeric(angle, row)?,
+                    site_symmetry: [
+                        optional_parsed(site_symmetry[0], row)?,
+                        optional_parsed(site_symmetry[1], row)?,
+                        optional_parsed(site_symmetry[2], row)?,
+                    ],
+                    publ_flag: optional_parsed(publ_flag, row)?,
+                })
+            })
+            .collect()
+    }
+
+    /// `_geom_angle_site_symmetry_2` is only written when any angle has it.
+    fn to_loop_columns(records: &[Self]) -> LoopColumns<'static> {
+        let label = |i: usize| -> Vec<String> {
+            records
+                .iter()
+                .map(|a| a.atom_site_labels[i].clone())
+                .collect()
+        };
+        let symmetry = |i: usize| -> Vec<Option<SiteSymmetryCode>> {
+            records.iter().map(|a| a.site_symmetry[i]).collect()
+        };
+        let site_symmetry_2 = symmetry(1);
+        let write_site_symmetry_2 = site_symmetry_2.iter().any(Option::is_some);
+        let items = [
+            Some(GeomAngleLoopItem::Atom_site_label_1(label(0))),
+            Some(GeomAngleLoopItem::Atom_site_label_2(label(1))),
+            Some(GeomAngleLoopItem::Atom_site_label_3(label(2))),
+            Some(GeomAngleLoopItem::Angle(
+                records.iter().map(|a| a.angle).collect(),
+            )),
+            Some(GeomAngleLoopItem::Site_symmetry_1(symmetry(0))),
+            write_site_symmetry_2.then_some(GeomAngleLoopItem::Site_symmetry_2(site_symmetry_2)),
+            Some(GeomAngleLoopItem::Site_symmetry_3(symmetry(2))),
+            Some(GeomAngleLoopItem::Publ_flag(
+                records.iter().map(|a| a.publ_flag).collect(),
+            )),
+        ];
+        LoopColumns::new(
+            items
+                .iter()
+                .flatten()
+                .map(|item| item.to_loop_column())
+                .collect(),
+        )
+    }
+}
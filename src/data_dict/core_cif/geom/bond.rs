@@ -0,0 +1,173 @@
+use crate::{
+    data_dict::{
+        reading::{
+            find_column, optional_parsed, required_column, required_numeric, required_text,
+            value_or_unknown, DataReadError,
+        },
+        CifTerm, LoopCategory, LoopValueTerm,
+    },
+    grammar::{LoopColumns, Numeric, Tag, Value},
+};
+
+use super::{label_values, site_symmetry_values, PublFlag, SiteSymmetryCode};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum GeomBondLoopItem {
+    Atom_site_label_1(Vec<String>),
+    Atom_site_label_2(Vec<String>),
+    Distance(Vec<Numeric>),
+    Publ_flag(Vec<Option<PublFlag>>),
+    Site_symmetry_1(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_2(Vec<Option<SiteSymmetryCode>>),
+}
+
+impl CifTerm for GeomBondLoopItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            GeomBondLoopItem::Atom_site_label_1(_) => "atom_site_label_1",
+            GeomBondLoopItem::Atom_site_label_2(_) => "atom_site_label_2",
+            GeomBondLoopItem::Distance(_) => "distance",
+            GeomBondLoopItem::Publ_flag(_) => "publ_flag",
+            GeomBondLoopItem::Site_symmetry_1(_) => "site_symmetry_1",
+            GeomBondLoopItem::Site_symmetry_2(_) => "site_symmetry_2",
+        };
+        Tag::new(format!("geom_bond_{suffix}"))
+    }
+}
+
+impl LoopValueTerm for GeomBondLoopItem {
+    fn values(&self) -> Vec<Value<'static>> {
+        match self {
+            GeomBondLoopItem::Atom_site_label_1(labels)
+            | GeomBondLoopItem::Atom_site_label_2(labels) => label_values(labels),
+            GeomBondLoopItem::Distance(d) => d.iter().copied().map(Value::from).collect(),
+            GeomBondLoopItem::Publ_flag(flags) => {
+                flags.iter().copied().map(value_or_unknown).collect()
+            }
+            GeomBondLoopItem::Site_symmetry_1(codes) | GeomBondLoopItem::Site_symmetry_2(codes) => {
+                site_symmetry_values(codes)
+            }
+        }
+    }
+}
+
+/// One row of the `_geom_bond_` loop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeomBond {
+    atom_site_labels: [String; 2],
+    distance: Numeric,
+    site_symmetry_1: Option<SiteSymmetryCode>,
+    site_symmetry_2: Option<SiteSymmetryCode>,
+    publ_flag: Option<PublFlag>,
+}
+
+impl GeomBond {
+    pub fn new(atom_site_labels: [String; 2], distance: Numeric) -> Self {
+        Self {
+            atom_site_labels,
+            distance,
+            site_symmetry_1: None,
+            site_symmetry_2: None,
+            publ_flag: None,
+        }
+    }
+
+    pub fn with_site_symmetry(
+        mut self,
+        site_symmetry_1: Option<SiteSymmetryCode>,
+        site_symmetry_2: Option<SiteSymmetryCode>,
+    ) -> Self {
+        self.site_symmetry_1 = site_symmetry_1;
+        self.site_symmetry_2 = site_symmetry_2;
+        self
+    }
+
+    pub fn with_publ_flag(mut self, publ_flag: PublFlag) -> Self {
+        self.publ_flag = Some(publ_flag);
+        self
+    }
+
+    pub fn atom_site_labels(&self) -> &[String; 2] {
+        &self.atom_site_labels
+    }
+
+    /// Bond length in Ångström.
+    pub fn distance(&self) -> Numeric {
+        self.distance
+    }
+
+    pub fn site_symmetry_1(&self) -> Option<SiteSymmetryCode> {
+        self.site_symmetry_1
+    }
+
+    pub fn site_symmetry_2(&self) -> Option<SiteSymmetryCode> {
+        self.site_symmetry_2
+    }
+
+    pub fn publ_flag(&self) -> Option<PublFlag> {
+        self.publ_flag
+    }
+}
+
+impl LoopCategory for GeomBond {
+    const KEY_TAG: &'static str = "geom_bond_atom_site_label_1";
+
+    fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<Self>, DataReadError> {
+        let label_1 = required_column(columns, "geom_bond_atom_site_label_1")?;
+        let label_2 = required_column(columns, "geom_bond_atom_site_label_2")?;
+        let distance = required_column(columns, "geom_bond_distance")?;
+        let site_symmetry_1 = find_column(columns, "geom_bond_site_symmetry_1");
+        let site_symmetry_2 = find_column(columns, "geom_bond_site_symmetry_2");
+        let publ_flag = find_column(columns, "geom_bond_publ_flag");
+        (0..label_1.values().len())
+            .map(|row| {
+                Ok(GeomBond {
+                    atom_site_labels: [required_text(label_1, row)?, required_text(label_2, row)?],
+                    distance: required_numeric(distance, row)?,
+                    site_symmetry_1: optional_parsed(site_symmetry_1, row)?,
+                    site_symmetry_2: optional_parsed(site_symmetry_2, row)?,
+                    publ_flag: optional_parsed(publ_flag, row)?,
+                })
+            })
+            .collect()
+    }
+
+    /// `_geom_bond_site_symmetry_1` is only written when any bond has it.
+    fn to_loop_columns(records: &[Self]) -> LoopColumns<'static> {
+        let site_symmetry_1: Vec<Option<SiteSymmetryCode>> =
+            records.iter().map(|b| b.site_symmetry_1).collect();
+        let write_site_symmetry_1 = site_symmetry_1.iter().any(Option::is_some);
+        let items = [
+            Some(GeomBondLoopItem::Atom_site_label_1(
+                records
+                    .iter()
+                    .map(|b| b.atom_site_labels[0].clone())
+                    .collect(),
+            )),
+            Some(GeomBondLoopItem::Atom_site_label_2(
+                records
+                    .iter()
+                    .map(|b| b.atom_site_labels[1].clone())
+                    .collect(),
+            )),
+            Some(GeomBondLoopItem::Distance(
+                records.iter().map(|b| b.distance).collect(),
+            )),
+            write_site_symmetry_1.then_some(GeomBondLoopItem::Site_symmetry_1(site_symmetry_1)),
+            Some(GeomBondLoopItem::Site_symmetry_2(
+                records.iter().map(|b| b.site_symmetry_2).collect(),
+            )),
+            Some(GeomBondLoopItem::Publ_flag(
+                records.iter().map(|b| b.publ_flag).collect(),
+            )),
+        ];
+        LoopColumns::new(
+            items
+                .iter()
+                .flatten()
+                .map(|item| item.to_loop_column())
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,401 @@
+use std::num::NonZeroUsize;
+
+use crate::{
+    data_dict::{
+        core_cif::{
+            atom_site::resolve_element,
+            cell::{measured, metric_dot, metric_tensor, reciprocal_parameters, CellParameters},
+            space_group::{space_group_operators, SymmetryOperator},
+        },
+        reading::{
+            find_column, optional_char_string, required_column, required_numeric, required_text,
+        },
+        DataReadError, LoopCategory,
+    },
+    grammar::{DataBlock, DataItems},
+};
+
+use super::{GeomAngle, GeomBond, SiteSymmetryCode};
+
+/// Two atoms are bonded when their distance is within the sum of their
+/// covalent radii plus this tolerance, in Ångström.
+pub const DEFAULT_BOND_TOLERANCE: f64 = 0.4;
+
+/// Image atoms closer than this to the central atom are the atom itself or
+/// a symmetry copy on a special position.
+const MIN_DISTANCE: f64 = 0.1;
+
+#[derive(Debug, Clone)]
+struct Site {
+    label: String,
+    radius: Option<f64>,
+    coord: [f64; 3],
+    su: [f64; 3],
+}
+
+/// A symmetry image of a site near a central atom.
+#[derive(Debug, Clone, Copy)]
+struct Neighbour {
+    site: usize,
+    operator: usize,
+    shift: [i32; 3],
+    coord: [f64; 3],
+    distance: f64,
+}
+
+/// Bond and angle tables computed from the cell, the symmetry operators and
+/// the atom sites of a data block, in the way SHELXL and PLATON list them:
+/// every bond between covalently bonded atoms and every angle between two
+/// bonds of the same atom, with site symmetry codes referring to the
+/// operators of [`space_group_operators`]: the
+/// `_space_group_symop_operation_xyz` list, or the general positions of the
+/// space group when no list is given.
+///
+/// Standard uncertainties are propagated from the su of the coordinates and
+/// the cell parameters by numerical differentiation, neglecting their
+/// correlations as there is no variance-covariance matrix in the CIF.
+#[derive(Debug, Clone, Default)]
+pub struct GeometryTable {
+    bonds: Vec<GeomBond>,
+    angles: Vec<GeomAngle>,
+}
+
+impl GeometryTable {
+    /// Compute the tables with bonds accepted up to the sum of the covalent
+    /// radii of `castep_periodic_table` plus `tolerance`. The element of
+    /// each site is found by [`resolve_element`]; sites whose element is not
+    /// found are left out.
+    pub fn from_data_block(data_block: &DataBlock, tolerance: f64) -> Result<Self, DataReadError> {
+        let cell = CellParameters::from_data_block(data_block)?;
+        let operators = space_group_operators(data_block)?;
+        let sites = read_sites(data_block)?;
+        let calculator = Calculator {
+            cell,
+            operators,
+            sites,
+            tolerance,
+        };
+        Ok(calculator.tables())
+    }
+
+    pub fn bonds(&self) -> &[GeomBond] {
+        &self.bonds
+    }
+
+    pub fn angles(&self) -> &[GeomAngle] {
+        &self.angles
+    }
+
+    /// `_geom_bond_` and `_geom_angle_` loops, leaving out empty ones.
+    pub fn to_data_items(&self) -> Vec<DataItems<'static>> {
+        let mut items = Vec::new();
+        if !self.bonds.is_empty() {
+            items.push(GeomBond::to_data_items(&self.bonds));
+        }
+        if !self.angles.is_empty() {
+            items.push(GeomAngle::to_data_items(&self.angles));
+        }
+        items
+    }
+}
+
+fn read_sites(data_block: &DataBlock) -> Result<Vec<Site>, DataReadError> {
+    let columns = data_block["atom_site_label"]
+        .as_multi_values()
+        .ok_or_else(|| DataReadError::MissingTag("atom_site_label".to_string()))?;
+    let label = required_column(columns, "atom_site_label")?;
+    let fract = [
+        required_column(columns, "atom_site_fract_x")?,
+        required_column(columns, "atom_site_fract_y")?,
+        required_column(columns, "atom_site_fract_z")?,
+    ];
+    let type_symbol = find_column(columns, "atom_site_type_symbol");
+    (0..label.values().len())
+        .map(|row| {
+            let label = required_text(label, row)?;
+            let symbol = optional_char_string(type_symbol, row)?;
+            let element = resolve_element(symbol.as_ref().map(|s| s.as_ref()), &label);
+            let mut coord = [0.0; 3];
+            let mut su = [0.0; 3];
+            for (i, column) in fract.iter().enumerate() {
+                let value = required_numeric(column, row)?;
+                coord[i] = value.as_f64();
+                su[i] = value.su_value().unwrap_or(0.0);
+            }
+            Ok(Site {
+                radius: element.map(|element| element.covalent_radius()),
+                label,
+                coord,
+                su,
+            })
+        })
+        .collect()
+}
+
+struct Calculator {
+    cell: CellParameters,
+    operators: Vec<SymmetryOperator>,
+    sites: Vec<Site>,
+    tolerance: f64,
+}
+
+impl Calculator {
+    fn tables(&self) -> GeometryTable {
+        let mut bonds = Vec::new();
+        let mut angles = Vec::new();
+        for center in 0..self.sites.len() {
+            let neighbours = self.neighbours(center);
+            let mut self_bond_lengths: Vec<f64> = Vec::new();
+            for n in neighbours.iter() {
+                // Each bond is listed once, from the atom earlier in the list.
+                // Bonds of an atom to its own images come in inverse pairs.
+                if n.site < center
+                    || (n.site == center
+                        && self_bond_lengths
+                            .iter()
+                            .any(|d| (d - n.distance).abs() < 1e-6))
+                {
+                    continue;
+                }
+                if n.site == center {
+                    self_bond_lengths.push(n.distance);
+                }
+                bonds.push(self.bond(center, n));
+            }
+            for (i, a) in neighbours.iter().enumerate() {
+                for b in neighbours.iter().skip(i + 1) {
+                    angles.push(self.angle(center, a, b));
+                }
+            }
+        }
+        GeometryTable { bonds, angles }
+    }
+
+    /// Images of all sites bonded to the site `center`, sorted by distance.
+    fn neighbours(&self, center: usize) -> Vec<Neighbour> {
+        let Some(center_radius) = self.sites[center].radius else {
+            return Vec::new();
+        };
+        let parameters = self.cell.values();
+        let g = metric_tensor(&parameters);
+        let reciprocal = reciprocal_parameters(&parameters);
+        let x0 = self.sites[center].coord;
+        let mut neighbours: Vec<Neighbour> = Vec::new();
+        for (j, site) in self.sites.iter().enumerate() {
+            let Some(radius) = site.radius else {
+                continue;
+            };
+            let cutoff = center_radius + radius + self.tolerance;
+            let extent: [i32; 3] = std::array::from_fn(|i| (cutoff * reciprocal[i]).ceil() as i32);
+            for (k, op) in self.operators.iter().enumerate() {
+                let image = op.apply(&site.coord);
+                let base: [i32; 3] = std::array::from_fn(|i| (x0[i] - image[i]).round() as i32);
+                for da in -extent[0]..=extent[0] {
+                    for db in -extent[1]..=extent[1] {
+                        for dc in -extent[2]..=extent[2] {
+                            let shift = [base[0] + da, base[1] + db, base[2] + dc];
+                            let coord: [f64; 3] =
+                                std::array::from_fn(|i| image[i] + shift[i] as f64);
+                            let dv: [f64; 3] = std::array::from_fn(|i| coord[i] - x0[i]);
+                            let distance = metric_dot(&g, &dv, &dv).sqrt();
+                            if distance < MIN_DISTANCE || distance > cutoff {
+                                continue;
+                            }
+                            // Symmetry operators of a special position give
+                            // the same image more than once.
+                            let duplicate = neighbours.iter().any(|n| {
+                                n.site == j && (0..3).all(|i| (n.coord[i] - coord[i]).abs() < 1e-4)
+                            });
+                            if !duplicate {
+                                neighbours.push(Neighbour {
+                                    site: j,
+                                    operator: k,
+                                    shift,
+                                    coord,
+                                    distance,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        neighbours.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        neighbours
+    }
+
+    /// Site symmetry code of a neighbour, `None` for the identity.
+    fn site_symmetry(&self, n: &Neighbour) -> Option<SiteSymmetryCode> {
+        let identity =
+            n.shift == [0, 0, 0] && self.operators[n.operator] == SymmetryOperator::identity();
+        (!identity)
+            .then(|| SiteSymmetryCode::new(NonZeroUsize::MIN.saturating_add(n.operator), n.shift))
+    }
+
+    /// Image of the coordinate of a neighbour under its operator and shift.
+    fn image(&self, n: &Neighbour, coord: &[f64; 3]) -> [f64; 3] {
+        let image = self.operators[n.operator].apply(coord);
+        std::array::from_fn(|i| image[i] + n.shift[i] as f64)
+    }
+
+    fn bond(&self, center: usize, n: &Neighbour) -> GeomBond {
+        let (distance, su) = self.with_su(&[center, n.site], |parameters, coords| {
+            let g = metric_tensor(parameters);
+            let image = self.image(n, &coords[1]);
+            let dv: [f64; 3] = std::array::from_fn(|i| image[i] - coords[0][i]);
+            metric_dot(&g, &dv, &dv).sqrt()
+        });
+        GeomBond::new(
+            [
+                self.sites[center].label.clone(),
+                self.sites[n.site].label.clone(),
+            ],
+            measured(distance, su, 4),
+        )
+        .with_site_symmetry(None, self.site_symmetry(n))
+    }
+
+    fn angle(&self, center: usize, a: &Neighbour, b: &Neighbour) -> GeomAngle {
+        let (angle, su) = self.with_su(&[a.site, center, b.site], |parameters, coords| {
+            let g = metric_tensor(parameters);
+            let image_a = self.image(a, &coords[0]);
+            let image_b = self.image(b, &coords[2]);
+            let u: [f64; 3] = std::array::from_fn(|i| image_a[i] - coords[1][i]);
+            let v: [f64; 3] = std::array::from_fn(|i| image_b[i] - coords[1][i]);
+            let cos =
+                metric_dot(&g, &u, &v) / (metric_dot(&g, &u, &u) * metric_dot(&g, &v, &v)).sqrt();
+            cos.clamp(-1.0, 1.0).acos().to_degrees()
+        });
+        GeomAngle::new(
+            [
+                self.sites[a.site].label.clone(),
+                self.sites[center].label.clone(),
+                self.sites[b.site].label.clone(),
+            ],
+            measured(angle, su, 1),
+        )
+        .with_site_symmetry([self.site_symmetry(a), None, self.site_symmetry(b)])
+    }
+
+    /// Evaluate `f` on the cell parameters and the coordinates of `sites`,
+    /// and its su from the su of the cell parameters and the coordinates of
+    /// each distinct site. A site appearing twice, as with bonds to its own
+    /// images, moves in both places.
+    fn with_su<F>(&self, sites: &[usize], f: F) -> (f64, f64)
+    where
+        F: Fn(&[f64; 6], &[[f64; 3]]) -> f64,
+    {
+        let parameters = self.cell.values();
+        let coords: Vec<[f64; 3]> = sites.iter().map(|&s| self.sites[s].coord).collect();
+        let value = f(&parameters, &coords);
+        let mut variance = 0.0;
+        for (p, su) in self.cell.std_uncertainties().iter().enumerate() {
+            if *su > 0.0 {
+                let h = 1e-6 * parameters[p].abs().max(1.0);
+                let (mut plus, mut minus) = (parameters, parameters);
+                plus[p] += h;
+                minus[p] -= h;
+                let derivative = (f(&plus, &coords) - f(&minus, &coords)) / (2.0 * h);
+                variance += (derivative * su).powi(2);
+            }
+        }
+        let mut distinct = sites.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+        for site in distinct {
+            for axis in 0..3 {
+                let su = self.sites[site].su[axis];
+                if su <= 0.0 {
+                    continue;
+                }
+                let h = 1e-6;
+                let shifted = |delta: f64| -> Vec<[f64; 3]> {
+                    coords
+                        .iter()
+                        .zip(sites.iter())
+                        .map(|(coord, &s)| {
+                            let mut coord = *coord;
+                            if s == site {
+                                coord[axis] += delta;
+                            }
+                            coord
+                        })
+                        .collect()
+                };
+                let derivative =
+                    (f(&parameters, &shifted(h)) - f(&parameters, &shifted(-h))) / (2.0 * h);
+                variance += (derivative * su).powi(2);
+            }
+        }
+        (value, variance.sqrt())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, SyntacticUnit};
+
+    use super::{GeometryTable, DEFAULT_BOND_TOLERANCE};
+
+    #[test]
+    fn water_geometry() {
+        let mut input = r#"data_water
+_cell_length_a 10.000(1)
+_cell_length_b 10.000(1)
+_cell_length_c 10.000(1)
+_cell_angle_alpha 90
+_cell_angle_beta 90
+_cell_angle_gamma 90
+loop_
+_space_group_symop_operation_xyz
+'x, y, z'
+'-x, y, -z'
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+O1 O 0.0000 0.1000(2) 0.0000
+H1 H 0.0757 0.1586 0.0000
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let table = GeometryTable::from_data_block(&block, DEFAULT_BOND_TOLERANCE).unwrap();
+        let bonds = table.bonds();
+        assert_eq!(bonds.len(), 2);
+        assert_eq!(
+            bonds[0].atom_site_labels(),
+            &["O1".to_string(), "H1".to_string()]
+        );
+        assert_eq!(bonds[0].site_symmetry_2(), None);
+        assert!((bonds[0].distance().as_f64() - 0.957).abs() < 1e-3);
+        assert!(bonds[0].distance().std_uncertainty().is_some());
+        assert_eq!(bonds[1].site_symmetry_2().unwrap().to_string(), "2_555");
+        let angles = table.angles();
+        assert_eq!(angles.len(), 1);
+        assert!((angles[0].angle().as_f64() - 104.5).abs() < 0.1);
+        assert_eq!(angles[0].atom_site_labels()[1], "O1");
+
+        // The operators of the space group when none are listed
+        let mut input = r#"data_water
+_cell_length_a 10.000(1)
+_cell_length_b 10.000(1)
+_cell_length_c 10.000(1)
+_cell_angle_alpha 90
+_cell_angle_beta 90
+_cell_angle_gamma 90
+_space_group_name_H-M_alt 'P 1 2 1'
+loop_
+_atom_site_label
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+O1 0.0000 0.1000(2) 0.0000
+H1 0.0757 0.1586 0.0000
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let table = GeometryTable::from_data_block(&block, DEFAULT_BOND_TOLERANCE).unwrap();
+        assert_eq!(table.bonds().len(), 2);
+        assert_eq!(table.angles().len(), 1);
+    }
+}
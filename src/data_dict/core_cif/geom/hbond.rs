@@ -0,0 +1,274 @@
+use crate::{
+    data_dict::{
+        reading::{
+            find_column, optional_numeric, optional_parsed, required_column, required_text,
+            value_or_unknown, DataReadError,
+        },
+        CifTerm, LoopCategory, LoopValueTerm,
+    },
+    grammar::{LoopColumns, Numeric, Tag, Value},
+};
+
+use super::{label_values, site_symmetry_values, PublFlag, SiteSymmetryCode};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum GeomHbondLoopItem {
+    Angle_DHA(Vec<Option<Numeric>>),
+    Atom_site_label_A(Vec<String>),
+    Atom_site_label_D(Vec<String>),
+    Atom_site_label_H(Vec<String>),
+    Distance_DA(Vec<Option<Numeric>>),
+    Distance_DH(Vec<Option<Numeric>>),
+    Distance_HA(Vec<Option<Numeric>>),
+    Publ_flag(Vec<Option<PublFlag>>),
+    Site_symmetry_A(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_D(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_H(Vec<Option<SiteSymmetryCode>>),
+}
+
+impl CifTerm for GeomHbondLoopItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            GeomHbondLoopItem::Angle_DHA(_) => "angle_DHA",
+            GeomHbondLoopItem::Atom_site_label_A(_) => "atom_site_label_A",
+            GeomHbondLoopItem::Atom_site_label_D(_) => "atom_site_label_D",
+            GeomHbondLoopItem::Atom_site_label_H(_) => "atom_site_label_H",
+            GeomHbondLoopItem::Distance_DA(_) => "distance_DA",
+            GeomHbondLoopItem::Distance_DH(_) => "distance_DH",
+            GeomHbondLoopItem::Distance_HA(_) => "distance_HA",
+            GeomHbondLoopItem::Publ_flag(_) => "publ_flag",
+            GeomHbondLoopItem::Site_symmetry_A(_) => "site_symmetry_A",
+            GeomHbondLoopItem::Site_symmetry_D(_) => "site_symmetry_D",
+            GeomHbondLoopItem::Site_symmetry_H(_) => "site_symmetry_H",
+        };
+        Tag::new(format!("geom_hbond_{suffix}"))
+    }
+}
+
+impl LoopValueTerm for GeomHbondLoopItem {
+    fn values(&self) -> Vec<Value<'static>> {
+        match self {
+            GeomHbondLoopItem::Angle_DHA(v)
+            | GeomHbondLoopItem::Distance_DA(v)
+            | GeomHbondLoopItem::Distance_DH(v)
+            | GeomHbondLoopItem::Distance_HA(v) => {
+                v.iter().copied().map(value_or_unknown).collect()
+            }
+            GeomHbondLoopItem::Atom_site_label_A(labels)
+            | GeomHbondLoopItem::Atom_site_label_D(labels)
+            | GeomHbondLoopItem::Atom_site_label_H(labels) => label_values(labels),
+            GeomHbondLoopItem::Publ_flag(flags) => {
+                flags.iter().copied().map(value_or_unknown).collect()
+            }
+            GeomHbondLoopItem::Site_symmetry_A(codes)
+            | GeomHbondLoopItem::Site_symmetry_D(codes)
+            | GeomHbondLoopItem::Site_symmetry_H(codes) => site_symmetry_values(codes),
+        }
+    }
+}
+
+/// One row of the `_geom_hbond_` loop: donor D, hydrogen H and acceptor A.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeomHbond {
+    atom_site_label_d: String,
+    atom_site_label_h: String,
+    atom_site_label_a: String,
+    distance_dh: Option<Numeric>,
+    distance_ha: Option<Numeric>,
+    distance_da: Option<Numeric>,
+    angle_dha: Option<Numeric>,
+    site_symmetry_d: Option<SiteSymmetryCode>,
+    site_symmetry_h: Option<SiteSymmetryCode>,
+    site_symmetry_a: Option<SiteSymmetryCode>,
+    publ_flag: Option<PublFlag>,
+}
+
+impl GeomHbond {
+    pub fn new(
+        atom_site_label_d: String,
+        atom_site_label_h: String,
+        atom_site_label_a: String,
+    ) -> Self {
+        Self {
+            atom_site_label_d,
+            atom_site_label_h,
+            atom_site_label_a,
+            distance_dh: None,
+            distance_ha: None,
+            distance_da: None,
+            angle_dha: None,
+            site_symmetry_d: None,
+            site_symmetry_h: None,
+            site_symmetry_a: None,
+            publ_flag: None,
+        }
+    }
+
+    pub fn with_distances(
+        mut self,
+        distance_dh: Option<Numeric>,
+        distance_ha: Option<Numeric>,
+        distance_da: Option<Numeric>,
+    ) -> Self {
+        self.distance_dh = distance_dh;
+        self.distance_ha = distance_ha;
+        self.distance_da = distance_da;
+        self
+    }
+
+    pub fn with_angle_dha(mut self, angle_dha: Numeric) -> Self {
+        self.angle_dha = Some(angle_dha);
+        self
+    }
+
+    pub fn with_site_symmetry_a(mut self, site_symmetry_a: SiteSymmetryCode) -> Self {
+        self.site_symmetry_a = Some(site_symmetry_a);
+        self
+    }
+
+    pub fn with_site_symmetry_d(mut self, site_symmetry_d: SiteSymmetryCode) -> Self {
+        self.site_symmetry_d = Some(site_symmetry_d);
+        self
+    }
+
+    pub fn with_site_symmetry_h(mut self, site_symmetry_h: SiteSymmetryCode) -> Self {
+        self.site_symmetry_h = Some(site_symmetry_h);
+        self
+    }
+
+    pub fn with_publ_flag(mut self, publ_flag: PublFlag) -> Self {
+        self.publ_flag = Some(publ_flag);
+        self
+    }
+
+    pub fn atom_site_label_d(&self) -> &str {
+        &self.atom_site_label_d
+    }
+
+    pub fn atom_site_label_h(&self) -> &str {
+        &self.atom_site_label_h
+    }
+
+    pub fn atom_site_label_a(&self) -> &str {
+        &self.atom_site_label_a
+    }
+
+    pub fn distance_dh(&self) -> Option<Numeric> {
+        self.distance_dh
+    }
+
+    pub fn distance_ha(&self) -> Option<Numeric> {
+        self.distance_ha
+    }
+
+    pub fn distance_da(&self) -> Option<Numeric> {
+        self.distance_da
+    }
+
+    pub fn angle_dha(&self) -> Option<Numeric> {
+        self.angle_dha
+    }
+
+    pub fn site_symmetry_d(&self) -> Option<SiteSymmetryCode> {
+        self.site_symmetry_d
+    }
+
+    pub fn site_symmetry_h(&self) -> Option<SiteSymmetryCode> {
+        self.site_symmetry_h
+    }
+
+    pub fn site_symmetry_a(&self) -> Option<SiteSymmetryCode> {
+        self.site_symmetry_a
+    }
+
+    pub fn publ_flag(&self) -> Option<PublFlag> {
+        self.publ_flag
+    }
+}
+
+impl LoopCategory for GeomHbond {
+    const KEY_TAG: &'static str = "geom_hbond_atom_site_label_D";
+
+    fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<Self>, DataReadError> {
+        let label_d = required_column(columns, "geom_hbond_atom_site_label_D")?;
+        let label_h = required_column(columns, "geom_hbond_atom_site_label_H")?;
+        let label_a = required_column(columns, "geom_hbond_atom_site_label_A")?;
+        let column = |tag: &str| find_column(columns, tag);
+        (0..label_d.values().len())
+            .map(|row| {
+                Ok(GeomHbond {
+                    atom_site_label_d: required_text(label_d, row)?,
+                    atom_site_label_h: required_text(label_h, row)?,
+                    atom_site_label_a: required_text(label_a, row)?,
+                    distance_dh: optional_numeric(column("geom_hbond_distance_DH"), row)?,
+                    distance_ha: optional_numeric(column("geom_hbond_distance_HA"), row)?,
+                    distance_da: optional_numeric(column("geom_hbond_distance_DA"), row)?,
+                    angle_dha: optional_numeric(column("geom_hbond_angle_DHA"), row)?,
+                    site_symmetry_d: optional_parsed(column("geom_hbond_site_symmetry_D"), row)?,
+                    site_symmetry_h: optional_parsed(column("geom_hbond_site_symmetry_H"), row)?,
+                    site_symmetry_a: optional_parsed(column("geom_hbond_site_symmetry_A"), row)?,
+                    publ_flag: optional_parsed(column("geom_hbond_publ_flag"), row)?,
+                })
+            })
+            .collect()
+    }
+
+    /// The donor and hydrogen site symmetry columns are only written when
+    /// any hydrogen bond has them.
+    fn to_loop_columns(records: &[Self]) -> LoopColumns<'static> {
+        let site_symmetry_d: Vec<Option<SiteSymmetryCode>> =
+            records.iter().map(|h| h.site_symmetry_d).collect();
+        let site_symmetry_h: Vec<Option<SiteSymmetryCode>> =
+            records.iter().map(|h| h.site_symmetry_h).collect();
+        let write_site_symmetry_d = site_symmetry_d.iter().any(Option::is_some);
+        let write_site_symmetry_h = site_symmetry_h.iter().any(Option::is_some);
+        let items = [
+            Some(GeomHbondLoopItem::Atom_site_label_D(
+                records
+                    .iter()
+                    .map(|h| h.atom_site_label_d.clone())
+                    .collect(),
+            )),
+            Some(GeomHbondLoopItem::Atom_site_label_H(
+                records
+                    .iter()
+                    .map(|h| h.atom_site_label_h.clone())
+                    .collect(),
+            )),
+            Some(GeomHbondLoopItem::Atom_site_label_A(
+                records
+                    .iter()
+                    .map(|h| h.atom_site_label_a.clone())
+                    .collect(),
+            )),
+            Some(GeomHbondLoopItem::Distance_DH(
+                records.iter().map(|h| h.distance_dh).collect(),
+            )),
+            Some(GeomHbondLoopItem::Distance_HA(
+                records.iter().map(|h| h.distance_ha).collect(),
+            )),
+            Some(GeomHbondLoopItem::Distance_DA(
+                records.iter().map(|h| h.distance_da).collect(),
+            )),
+            Some(GeomHbondLoopItem::Angle_DHA(
+                records.iter().map(|h| h.angle_dha).collect(),
+            )),
+            write_site_symmetry_d.then_some(GeomHbondLoopItem::Site_symmetry_D(site_symmetry_d)),
+            write_site_symmetry_h.then_some(GeomHbondLoopItem::Site_symmetry_H(site_symmetry_h)),
+            Some(GeomHbondLoopItem::Site_symmetry_A(
+                records.iter().map(|h| h.site_symmetry_a).collect(),
+            )),
+            Some(GeomHbondLoopItem::Publ_flag(
+                records.iter().map(|h| h.publ_flag).collect(),
+            )),
+        ];
+        LoopColumns::new(
+            items
+                .iter()
+                .flatten()
+                .map(|item| item.to_loop_column())
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,170 @@
+//! The `_geom_` category: bond lengths, bond angles, torsion angles and
+//! hydrogen bonds, with the site symmetry of each atom given as a
+//! [`SiteSymmetryCode`]. A missing code (`.`) means the atom is at the site
+//! listed in `_atom_site_`.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::grammar::{CharString, UnquotedString, Value};
+
+mod angle;
+mod bond;
+mod calc;
+mod hbond;
+mod symmetry_code;
+mod torsion;
+
+pub use angle::{GeomAngle, GeomAngleLoopItem};
+pub use bond::{GeomBond, GeomBondLoopItem};
+pub use calc::{GeometryTable, DEFAULT_BOND_TOLERANCE};
+pub use hbond::{GeomHbond, GeomHbondLoopItem};
+pub use symmetry_code::{SiteSymmetryCode, SiteSymmetryCodeError};
+pub use torsion::{GeomTorsion, GeomTorsionLoopItem};
+
+/// `_geom_*_publ_flag`: whether the entry should be listed in a publication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublFlag {
+    Yes,
+    No,
+}
+
+impl Display for PublFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublFlag::Yes => f.write_str("yes"),
+            PublFlag::No => f.write_str("no"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PublFlagError;
+
+impl Display for PublFlagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Not a valid publication flag, expected yes, y, no or n")
+    }
+}
+
+impl std::error::Error for PublFlagError {}
+
+impl FromStr for PublFlag {
+    type Err = PublFlagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yes" | "y" => Ok(Self::Yes),
+            "no" | "n" => Ok(Self::No),
+            _ => Err(PublFlagError),
+        }
+    }
+}
+
+impl From<PublFlag> for Value<'_> {
+    fn from(value: PublFlag) -> Self {
+        Value::CharString(CharString::Unquoted(UnquotedString::new(value.to_string())))
+    }
+}
+
+/// Site symmetry column values, `.` for atoms at the listed site.
+fn site_symmetry_values(codes: &[Option<SiteSymmetryCode>]) -> Vec<Value<'static>> {
+    codes
+        .iter()
+        .map(|code| code.map(Value::from).unwrap_or(Value::Inapplicable))
+        .collect()
+}
+
+/// Label column values.
+fn label_values(labels: &[String]) -> Vec<Value<'static>> {
+    labels
+        .iter()
+        .map(|label| Value::CharString(CharString::from(label.as_str())))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroUsize;
+
+    use crate::{
+        data_dict::LoopCategory,
+        grammar::{DataBlock, SyntacticUnit},
+    };
+
+    use super::{GeomAngle, GeomBond, GeomHbond, GeomTorsion, PublFlag, SiteSymmetryCode};
+
+    #[test]
+    fn geom_loops() {
+        let mut input = r#"data_I
+loop_
+ _geom_bond_atom_site_label_1
+ _geom_bond_atom_site_label_2
+ _geom_bond_distance
+ _Geom_Bond_Site_Symmetry_2
+ _geom_bond_publ_flag
+N1 C2 1.466(7) . no
+N1 C14 1.502(8) 2_665 yes
+loop_
+ _geom_angle_atom_site_label_1
+ _geom_angle_atom_site_label_2
+ _geom_angle_atom_site_label_3
+ _geom_angle
+ _geom_angle_site_symmetry_1
+ _geom_angle_site_symmetry_3
+ _geom_angle_publ_flag
+C2 N1 C14 115.2(5) . . no
+loop_
+ _geom_torsion_atom_site_label_1
+ _geom_torsion_atom_site_label_2
+ _geom_torsion_atom_site_label_3
+ _geom_torsion_atom_site_label_4
+ _geom_torsion
+ _geom_torsion_site_symmetry_1
+ _geom_torsion_site_symmetry_2
+ _geom_torsion_site_symmetry_3
+ _geom_torsion_site_symmetry_4
+ _geom_torsion_publ_flag
+N1 C2 C3 N4 -65.0(9) . . . . y
+loop_
+_geom_hbond_atom_site_label_D
+_geom_hbond_atom_site_label_H
+_geom_hbond_atom_site_label_A
+_geom_hbond_distance_DH
+_geom_hbond_distance_HA
+_geom_hbond_distance_DA
+_geom_hbond_angle_DHA
+_geom_hbond_site_symmetry_a
+_geom_hbond_publ_flag
+  N1  H1B O23  0.92 1.85   2.760(11)  172  1_445 y
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let bonds = GeomBond::from_data_block(&block).unwrap();
+        assert_eq!(bonds.len(), 2);
+        assert_eq!(bonds[0].site_symmetry_2(), None);
+        assert_eq!(
+            bonds[1].site_symmetry_2(),
+            Some(SiteSymmetryCode::new(
+                NonZeroUsize::new(2).unwrap(),
+                [1, 1, 0]
+            ))
+        );
+        assert_eq!(bonds[1].publ_flag(), Some(PublFlag::Yes));
+        let angles = GeomAngle::from_data_block(&block).unwrap();
+        assert_eq!(angles[0].atom_site_labels()[2], "C14");
+        let torsions = GeomTorsion::from_data_block(&block).unwrap();
+        assert_eq!(torsions[0].publ_flag(), Some(PublFlag::Yes));
+        let hbonds = GeomHbond::from_data_block(&block).unwrap();
+        assert_eq!(
+            hbonds[0].site_symmetry_a(),
+            Some(SiteSymmetryCode::new(NonZeroUsize::MIN, [-1, -1, 0]))
+        );
+
+        let columns = GeomBond::to_loop_columns(&bonds);
+        let read_back = GeomBond::from_loop_columns(&columns).unwrap();
+        assert_eq!(read_back[1].site_symmetry_2(), bonds[1].site_symmetry_2());
+        assert_eq!(read_back[0].distance(), bonds[0].distance());
+        let columns = GeomHbond::to_loop_columns(&hbonds);
+        let read_back = GeomHbond::from_loop_columns(&columns).unwrap();
+        assert_eq!(read_back[0].site_symmetry_a(), hbonds[0].site_symmetry_a());
+    }
+}
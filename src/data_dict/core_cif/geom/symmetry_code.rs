@@ -0,0 +1,127 @@
+use std::{fmt::Display, num::NonZeroUsize, str::FromStr};
+
+use crate::grammar::{CharString, UnquotedString, Value};
+
+/// Site symmetry code in the `n_klm` form, e.g. `2_665`: the `n`-th symmetry
+/// operator of the `_space_group_symop` list (1-based), followed by a lattice
+/// translation whose components are written offset by 5, so `555` is no
+/// translation. Translations beyond the single digit range are written as
+/// `n_k_l_m` with signed components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SiteSymmetryCode {
+    operator: NonZeroUsize,
+    translation: [i32; 3],
+}
+
+impl SiteSymmetryCode {
+    pub fn new(operator: NonZeroUsize, translation: [i32; 3]) -> Self {
+        Self {
+            operator,
+            translation,
+        }
+    }
+
+    /// `1_555`
+    pub fn identity() -> Self {
+        Self::new(NonZeroUsize::MIN, [0, 0, 0])
+    }
+
+    /// 1-based index of the symmetry operator.
+    pub fn operator(&self) -> usize {
+        self.operator.get()
+    }
+
+    pub fn translation(&self) -> [i32; 3] {
+        self.translation
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.operator == NonZeroUsize::MIN && self.translation == [0, 0, 0]
+    }
+}
+
+impl Default for SiteSymmetryCode {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Display for SiteSymmetryCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [k, l, m] = self.translation;
+        if self.translation.iter().all(|t| (-5..=4).contains(t)) {
+            write!(f, "{}_{}{}{}", self.operator, k + 5, l + 5, m + 5)
+        } else {
+            write!(f, "{}_{}_{}_{}", self.operator, k, l, m)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteSymmetryCodeError(String);
+
+impl Display for SiteSymmetryCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a valid site symmetry code", self.0)
+    }
+}
+
+impl std::error::Error for SiteSymmetryCodeError {}
+
+impl FromStr for SiteSymmetryCode {
+    type Err = SiteSymmetryCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || SiteSymmetryCodeError(s.to_string());
+        let mut parts = s.trim().split('_');
+        let operator = parts
+            .next()
+            .and_then(|n| n.parse::<NonZeroUsize>().ok())
+            .ok_or_else(error)?;
+        let rest: Vec<&str> = parts.collect();
+        let translation = match rest.as_slice() {
+            [] => [0, 0, 0],
+            [klm] if klm.len() == 3 && klm.chars().all(|c| c.is_ascii_digit()) => {
+                let mut digits = klm.chars().map(|c| c.to_digit(10).unwrap() as i32 - 5);
+                [
+                    digits.next().unwrap(),
+                    digits.next().unwrap(),
+                    digits.next().unwrap(),
+                ]
+            }
+            [k, l, m] => {
+                let parse = |t: &str| t.parse::<i32>().map_err(|_| error());
+                [parse(k)?, parse(l)?, parse(m)?]
+            }
+            _ => return Err(error()),
+        };
+        Ok(Self::new(operator, translation))
+    }
+}
+
+impl From<SiteSymmetryCode> for Value<'_> {
+    fn from(value: SiteSymmetryCode) -> Self {
+        Value::CharString(CharString::Unquoted(UnquotedString::new(value.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroUsize;
+
+    use super::SiteSymmetryCode;
+
+    #[test]
+    fn site_symmetry_code() {
+        let code: SiteSymmetryCode = "2_665".parse().unwrap();
+        assert_eq!(code.operator(), 2);
+        assert_eq!(code.translation(), [1, 1, 0]);
+        assert_eq!(code.to_string(), "2_665");
+        let far = SiteSymmetryCode::new(NonZeroUsize::new(3).unwrap(), [6, 0, -1]);
+        assert_eq!(far.to_string(), "3_6_0_-1");
+        assert_eq!(far.to_string().parse::<SiteSymmetryCode>().unwrap(), far);
+        assert!("1".parse::<SiteSymmetryCode>().unwrap().is_identity());
+        assert!("0_555".parse::<SiteSymmetryCode>().is_err());
+        assert!("1_55".parse::<SiteSymmetryCode>().is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    data_dict::{
+        reading::{
+            find_column, optional_parsed, required_column, required_numeric, required_text,
+            value_or_unknown, DataReadError,
+        },
+        CifTerm, LoopCategory, LoopValueTerm,
+    },
+    grammar::{LoopColumns, Numeric, Tag, Value},
+};
+
+use super::{label_values, site_symmetry_values, PublFlag, SiteSymmetryCode};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum GeomTorsionLoopItem {
+    Atom_site_label_1(Vec<String>),
+    Atom_site_label_2(Vec<String>),
+    Atom_site_label_3(Vec<String>),
+    Atom_site_label_4(Vec<String>),
+    Publ_flag(Vec<Option<PublFlag>>),
+    Site_symmetry_1(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_2(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_3(Vec<Option<SiteSymmetryCode>>),
+    Site_symmetry_4(Vec<Option<SiteSymmetryCode>>),
+    Torsion(Vec<Numeric>),
+}
+
+impl CifTerm for GeomTorsionLoopItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            GeomTorsionLoopItem::Atom_site_label_1(_) => "_atom_site_label_1",
+            GeomTorsionLoopItem::Atom_site_label_2(_) => "_atom_site_label_2",
+            GeomTorsionLoopItem::Atom_site_label_3(_) => "_atom_site_label_3",
+            GeomTorsionLoopItem::Atom_site_label_4(_) => "_atom_site_label_4",
+            GeomTorsionLoopItem::Publ_flag(_) => "_publ_flag",
+            GeomTorsionLoopItem::Site_symmetry_1(_) => "_site_symmetry_1",
+            GeomTorsionLoopItem::Site_symmetry_2(_) => "_site_symmetry_2",
+            GeomTorsionLoopItem::Site_symmetry_3(_) => "_site_symmetry_3",
+            GeomTorsionLoopItem::Site_symmetry_4(_) => "_site_symmetry_4",
+            GeomTorsionLoopItem::Torsion(_) => "",
+        };
+        Tag::new(format!("geom_torsion{suffix}"))
+    }
+}
+
+impl LoopValueTerm for GeomTorsionLoopItem {
+    fn values(&self) -> Vec<Value<'static>> {
+        match self {
+            GeomTorsionLoopItem::Atom_site_label_1(labels)
+            | GeomTorsionLoopItem::Atom_site_label_2(labels)
+            | GeomTorsionLoopItem::Atom_site_label_3(labels)
+            | GeomTorsionLoopItem::Atom_site_label_4(labels) => label_values(labels),
+            GeomTorsionLoopItem::Publ_flag(flags) => {
+                flags.iter().copied().map(value_or_unknown).collect()
+            }
+            GeomTorsionLoopItem::Site_symmetry_1(codes)
+            | GeomTorsionLoopItem::Site_symmetry_2(codes)
+            | GeomTorsionLoopItem::Site_symmetry_3(codes)
+            | GeomTorsionLoopItem::Site_symmetry_4(codes) => site_symmetry_values(codes),
+            GeomTorsionLoopItem::Torsion(t) => t.iter().copied().map(Value::from).collect(),
+        }
+    }
+}
+
+/// One row of the `_geom_torsion_` loop: the dihedral angle between the
+/// planes of atoms 1, 2, 3 and atoms 2, 3, 4.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeomTorsion {
+    atom_site_labels: [String; 4],
+    torsion: Numeric,
+    site_symmetry: [Option<SiteSymmetryCode>; 4],
+    publ_flag: Option<PublFlag>,
+}
+
+impl GeomTorsion {
+    pub fn new(atom_site_labels: [String; 4], torsion: Numeric) -> Self {
+        Self {
+            atom_site_labels,
+            torsion,
+            site_symmetry: [None; 4],
+            publ_flag: None,
+        }
+    }
+
+    pub fn with_site_symmetry(mut self, site_symmetry: [Option<SiteSymmetryCode>; 4]) -> Self {
+        self.site_symmetry = site_symmetry;
+        self
+    }
+
+    pub fn with_publ_flag(mut self, publ_flag: PublFlag) -> Self {
+        self.publ_flag = Some(publ_flag);
+        self
+    }
+
+    pub fn atom_site_labels(&self) -> &[String; 4] {
+        &self.atom_site_labels
+    }
+
+    /// Torsion angle in degrees.
+    pub fn torsion(&self) -> Numeric {
+        self.torsion
+    }
+
+    pub fn site_symmetry(&self) -> [Option<SiteSymmetryCode>; 4] {
+        self.site_symmetry
+    }
+
+    pub fn publ_flag(&self) -> Option<PublFlag> {
+        self.publ_flag
+    }
+}
+
+impl LoopCategory for GeomTorsion {
+    const KEY_TAG: &'static str = "geom_torsion_atom_site_label_1";
+
+    fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<Self>, DataReadError> {
+        let labels = [
+            required_column(columns, "geom_torsion_atom_site_label_1")?,
+            required_column(columns, "geom_torsion_atom_site_label_2")?,
+            required_column(columns, "geom_torsion_atom_site_label_3")?,
+            required_column(columns, "geom_torsion_atom_site_label_4")?,
+        ];
+        let torsion = required_column(columns, "geom_torsion")?;
+        let site_symmetry = [
+            find_column(columns, "geom_torsion_site_symmetry_1"),
+            find_column(columns, "geom_torsion_site_symmetry_2"),
+            find_column(columns, "geom_torsion_site_symmetry_3"),
+            find_column(columns, "geom_torsion_site_symmetry_4"),
+        ];
+        let publ_flag = find_column(columns, "geom_torsion_publ_flag");
+        (0..labels[0].values().len())
+            .map(|row| {
+                Ok(GeomTorsion {
+                    atom_site_labels: [
+                        required_text(labels[0], row)?,
+                        required_text(labels[1], row)?,
+                        required_text(labels[2], row)?,
+                        required_text(labels[3], row)?,
+                    ],
+                    torsion: required_numeric(torsion, row)?,
+                    site_symmetry: [
+                        optional_parsed(site_symmetry[0], row)?,
+                        optional_parsed(site_symmetry[1], row)?,
+                        optional_parsed(site_symmetry[2], row)?,
+                        optional_parsed(site_symmetry[3], row)?,
+                    ],
+                    publ_flag: optional_parsed(publ_flag, row)?,
+                })
+            })
+            .collect()
+    }
+
+    /// All four site symmetry columns are written, as is conventional.
+    fn to_loop_columns(records: &[Self]) -> LoopColumns<'static> {
+        let label = |i: usize| -> Vec<String> {
+            records
+                .iter()
+                .map(|t| t.atom_site_labels[i].clone())
+                .collect()
+        };
+        let symmetry = |i: usize| -> Vec<Option<SiteSymmetryCode>> {
+            records.iter().map(|t| t.site_symmetry[i]).collect()
+        };
+        let items = [
+            GeomTorsionLoopItem::Atom_site_label_1(label(0)),
+            GeomTorsionLoopItem::Atom_site_label_2(label(1)),
+            GeomTorsionLoopItem::Atom_site_label_3(label(2)),
+            GeomTorsionLoopItem::Atom_site_label_4(label(3)),
+            GeomTorsionLoopItem::Torsion(records.iter().map(|t| t.torsion).collect()),
+            GeomTorsionLoopItem::Site_symmetry_1(symmetry(0)),
+            GeomTorsionLoopItem::Site_symmetry_2(symmetry(1)),
+            GeomTorsionLoopItem::Site_symmetry_3(symmetry(2)),
+            GeomTorsionLoopItem::Site_symmetry_4(symmetry(3)),
+            GeomTorsionLoopItem::Publ_flag(records.iter().map(|t| t.publ_flag).collect()),
+        ];
+        LoopColumns::new(items.iter().map(|item| item.to_loop_column()).collect())
+    }
+}
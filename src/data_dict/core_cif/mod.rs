@@ -1,4 +1,12 @@
 pub mod atom_site;
+pub mod atom_type;
 pub mod audit;
 pub mod cell;
+pub mod chemical;
+pub mod computing;
+pub mod diffrn;
+pub mod exptl;
+pub mod geom;
+pub mod refine;
+pub mod reflns;
 pub mod space_group;
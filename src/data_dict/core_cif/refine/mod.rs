@@ -0,0 +1,526 @@
+//! The `_refine_` category: results of the least-squares structure refinement.
+
+use crate::{
+    data_dict::{
+        reading::{
+            optional_single_numeric, optional_single_parsed, optional_single_text, text_value,
+        },
+        CifTerm, DataReadError, SingleValueTerm,
+    },
+    grammar::{DataBlock, DataItems, Numeric, Tag, UnsignedInteger, Value},
+};
+
+mod options;
+
+pub use options::{HydrogenTreatment, MatrixType, StructureFactorCoef, WeightingScheme};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum RefineItem {
+    Diff_density_max(Numeric),
+    Diff_density_min(Numeric),
+    Diff_density_rms(Numeric),
+    Ls_abs_structure_details(String),
+    Ls_abs_structure_Flack(Numeric),
+    Ls_extinction_coef(Numeric),
+    Ls_extinction_method(String),
+    Ls_goodness_of_fit_ref(Numeric),
+    Ls_hydrogen_treatment(HydrogenTreatment),
+    Ls_matrix_type(MatrixType),
+    Ls_number_parameters(u32),
+    Ls_number_reflns(u32),
+    Ls_number_restraints(u32),
+    Ls_R_factor_all(Numeric),
+    Ls_R_factor_gt(Numeric),
+    Ls_restrained_S_all(Numeric),
+    Ls_shift_su_max(Numeric),
+    Ls_shift_su_mean(Numeric),
+    Ls_structure_factor_coef(StructureFactorCoef),
+    Ls_weighting_details(String),
+    Ls_weighting_scheme(WeightingScheme),
+    Ls_wR_factor_gt(Numeric),
+    Ls_wR_factor_ref(Numeric),
+}
+
+impl CifTerm for RefineItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            RefineItem::Diff_density_max(_) => "diff_density_max",
+            RefineItem::Diff_density_min(_) => "diff_density_min",
+            RefineItem::Diff_density_rms(_) => "diff_density_rms",
+            RefineItem::Ls_abs_structure_details(_) => "ls_abs_structure_details",
+            RefineItem::Ls_abs_structure_Flack(_) => "ls_abs_structure_Flack",
+            RefineItem::Ls_extinction_coef(_) => "ls_extinction_coef",
+            RefineItem::Ls_extinction_method(_) => "ls_extinction_method",
+            RefineItem::Ls_goodness_of_fit_ref(_) => "ls_goodness_of_fit_ref",
+            RefineItem::Ls_hydrogen_treatment(_) => "ls_hydrogen_treatment",
+            RefineItem::Ls_matrix_type(_) => "ls_matrix_type",
+            RefineItem::Ls_number_parameters(_) => "ls_number_parameters",
+            RefineItem::Ls_number_reflns(_) => "ls_number_reflns",
+            RefineItem::Ls_number_restraints(_) => "ls_number_restraints",
+            RefineItem::Ls_R_factor_all(_) => "ls_R_factor_all",
+            RefineItem::Ls_R_factor_gt(_) => "ls_R_factor_gt",
+            RefineItem::Ls_restrained_S_all(_) => "ls_restrained_S_all",
+            RefineItem::Ls_shift_su_max(_) => "ls_shift/su_max",
+            RefineItem::Ls_shift_su_mean(_) => "ls_shift/su_mean",
+            RefineItem::Ls_structure_factor_coef(_) => "ls_structure_factor_coef",
+            RefineItem::Ls_weighting_details(_) => "ls_weighting_details",
+            RefineItem::Ls_weighting_scheme(_) => "ls_weighting_scheme",
+            RefineItem::Ls_wR_factor_gt(_) => "ls_wR_factor_gt",
+            RefineItem::Ls_wR_factor_ref(_) => "ls_wR_factor_ref",
+        };
+        Tag::new(format!("refine_{suffix}"))
+    }
+}
+
+impl SingleValueTerm for RefineItem {
+    fn value(&self) -> Value<'static> {
+        match self {
+            RefineItem::Diff_density_max(v)
+            | RefineItem::Diff_density_min(v)
+            | RefineItem::Diff_density_rms(v)
+            | RefineItem::Ls_abs_structure_Flack(v)
+            | RefineItem::Ls_extinction_coef(v)
+            | RefineItem::Ls_goodness_of_fit_ref(v)
+            | RefineItem::Ls_R_factor_all(v)
+            | RefineItem::Ls_R_factor_gt(v)
+            | RefineItem::Ls_restrained_S_all(v)
+            | RefineItem::Ls_shift_su_max(v)
+            | RefineItem::Ls_shift_su_mean(v)
+            | RefineItem::Ls_wR_factor_gt(v)
+            | RefineItem::Ls_wR_factor_ref(v) => Value::Numeric(*v),
+            RefineItem::Ls_abs_structure_details(v)
+            | RefineItem::Ls_extinction_method(v)
+            | RefineItem::Ls_weighting_details(v) => text_value(v),
+            RefineItem::Ls_hydrogen_treatment(v) => (*v).into(),
+            RefineItem::Ls_matrix_type(v) => (*v).into(),
+            RefineItem::Ls_structure_factor_coef(v) => (*v).into(),
+            RefineItem::Ls_weighting_scheme(v) => (*v).into(),
+            RefineItem::Ls_number_parameters(v)
+            | RefineItem::Ls_number_reflns(v)
+            | RefineItem::Ls_number_restraints(v) => UnsignedInteger(*v).into(),
+        }
+    }
+}
+
+/// Refinement details and figures of merit, as reported by SHELXL and similar programs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Refine {
+    ls_structure_factor_coef: Option<StructureFactorCoef>,
+    ls_matrix_type: Option<MatrixType>,
+    ls_weighting_scheme: Option<WeightingScheme>,
+    ls_weighting_details: Option<String>,
+    ls_hydrogen_treatment: Option<HydrogenTreatment>,
+    ls_extinction_method: Option<String>,
+    ls_extinction_coef: Option<Numeric>,
+    ls_number_reflns: Option<u32>,
+    ls_number_parameters: Option<u32>,
+    ls_number_restraints: Option<u32>,
+    ls_r_factor_all: Option<Numeric>,
+    ls_r_factor_gt: Option<Numeric>,
+    ls_wr_factor_ref: Option<Numeric>,
+    ls_wr_factor_gt: Option<Numeric>,
+    ls_goodness_of_fit_ref: Option<Numeric>,
+    ls_restrained_s_all: Option<Numeric>,
+    ls_shift_su_max: Option<Numeric>,
+    ls_shift_su_mean: Option<Numeric>,
+    ls_abs_structure_flack: Option<Numeric>,
+    ls_abs_structure_details: Option<String>,
+    diff_density_max: Option<Numeric>,
+    diff_density_min: Option<Numeric>,
+    diff_density_rms: Option<Numeric>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RefineBuilder {
+    refine: Refine,
+}
+
+impl RefineBuilder {
+    /// Quantity minimized in the refinement.
+    pub fn with_ls_structure_factor_coef(
+        mut self,
+        ls_structure_factor_coef: StructureFactorCoef,
+    ) -> Self {
+        self.refine.ls_structure_factor_coef = Some(ls_structure_factor_coef);
+        self
+    }
+
+    pub fn with_ls_matrix_type(mut self, ls_matrix_type: MatrixType) -> Self {
+        self.refine.ls_matrix_type = Some(ls_matrix_type);
+        self
+    }
+
+    pub fn with_ls_weighting_scheme(mut self, ls_weighting_scheme: WeightingScheme) -> Self {
+        self.refine.ls_weighting_scheme = Some(ls_weighting_scheme);
+        self
+    }
+
+    /// Weighting expression, e.g. `w=1/[\s^2^(Fo^2^)+(0.0481P)^2^]`.
+    pub fn with_ls_weighting_details(mut self, ls_weighting_details: impl Into<String>) -> Self {
+        self.refine.ls_weighting_details = Some(ls_weighting_details.into());
+        self
+    }
+
+    pub fn with_ls_hydrogen_treatment(mut self, ls_hydrogen_treatment: HydrogenTreatment) -> Self {
+        self.refine.ls_hydrogen_treatment = Some(ls_hydrogen_treatment);
+        self
+    }
+
+    pub fn with_ls_extinction_method(mut self, ls_extinction_method: impl Into<String>) -> Self {
+        self.refine.ls_extinction_method = Some(ls_extinction_method.into());
+        self
+    }
+
+    pub fn with_ls_extinction_coef(mut self, ls_extinction_coef: Numeric) -> Self {
+        self.refine.ls_extinction_coef = Some(ls_extinction_coef);
+        self
+    }
+
+    /// Number of unique reflections used in the refinement.
+    pub fn with_ls_number_reflns(mut self, ls_number_reflns: u32) -> Self {
+        self.refine.ls_number_reflns = Some(ls_number_reflns);
+        self
+    }
+
+    pub fn with_ls_number_parameters(mut self, ls_number_parameters: u32) -> Self {
+        self.refine.ls_number_parameters = Some(ls_number_parameters);
+        self
+    }
+
+    pub fn with_ls_number_restraints(mut self, ls_number_restraints: u32) -> Self {
+        self.refine.ls_number_restraints = Some(ls_number_restraints);
+        self
+    }
+
+    /// Residual factor for all reflections.
+    pub fn with_ls_r_factor_all(mut self, ls_r_factor_all: Numeric) -> Self {
+        self.refine.ls_r_factor_all = Some(ls_r_factor_all);
+        self
+    }
+
+    /// Residual factor for reflections above the `_reflns_threshold_expression`.
+    pub fn with_ls_r_factor_gt(mut self, ls_r_factor_gt: Numeric) -> Self {
+        self.refine.ls_r_factor_gt = Some(ls_r_factor_gt);
+        self
+    }
+
+    /// Weighted residual factor for the reflections used in the refinement.
+    pub fn with_ls_wr_factor_ref(mut self, ls_wr_factor_ref: Numeric) -> Self {
+        self.refine.ls_wr_factor_ref = Some(ls_wr_factor_ref);
+        self
+    }
+
+    pub fn with_ls_wr_factor_gt(mut self, ls_wr_factor_gt: Numeric) -> Self {
+        self.refine.ls_wr_factor_gt = Some(ls_wr_factor_gt);
+        self
+    }
+
+    pub fn with_ls_goodness_of_fit_ref(mut self, ls_goodness_of_fit_ref: Numeric) -> Self {
+        self.refine.ls_goodness_of_fit_ref = Some(ls_goodness_of_fit_ref);
+        self
+    }
+
+    pub fn with_ls_restrained_s_all(mut self, ls_restrained_s_all: Numeric) -> Self {
+        self.refine.ls_restrained_s_all = Some(ls_restrained_s_all);
+        self
+    }
+
+    /// Largest ratio of the final shift to the su of any parameter.
+    pub fn with_ls_shift_su_max(mut self, ls_shift_su_max: Numeric) -> Self {
+        self.refine.ls_shift_su_max = Some(ls_shift_su_max);
+        self
+    }
+
+    pub fn with_ls_shift_su_mean(mut self, ls_shift_su_mean: Numeric) -> Self {
+        self.refine.ls_shift_su_mean = Some(ls_shift_su_mean);
+        self
+    }
+
+    pub fn with_ls_abs_structure_flack(mut self, ls_abs_structure_flack: Numeric) -> Self {
+        self.refine.ls_abs_structure_flack = Some(ls_abs_structure_flack);
+        self
+    }
+
+    pub fn with_ls_abs_structure_details(
+        mut self,
+        ls_abs_structure_details: impl Into<String>,
+    ) -> Self {
+        self.refine.ls_abs_structure_details = Some(ls_abs_structure_details.into());
+        self
+    }
+
+    /// Largest peak of the final difference map, in e Å⁻³.
+    pub fn with_diff_density_max(mut self, diff_density_max: Numeric) -> Self {
+        self.refine.diff_density_max = Some(diff_density_max);
+        self
+    }
+
+    /// Deepest hole of the final difference map, in e Å⁻³.
+    pub fn with_diff_density_min(mut self, diff_density_min: Numeric) -> Self {
+        self.refine.diff_density_min = Some(diff_density_min);
+        self
+    }
+
+    /// In e Å⁻³.
+    pub fn with_diff_density_rms(mut self, diff_density_rms: Numeric) -> Self {
+        self.refine.diff_density_rms = Some(diff_density_rms);
+        self
+    }
+
+    pub fn build(self) -> Refine {
+        self.refine
+    }
+}
+
+impl Refine {
+    pub fn builder() -> RefineBuilder {
+        RefineBuilder::default()
+    }
+
+    pub fn ls_structure_factor_coef(&self) -> Option<StructureFactorCoef> {
+        self.ls_structure_factor_coef
+    }
+
+    pub fn ls_matrix_type(&self) -> Option<MatrixType> {
+        self.ls_matrix_type
+    }
+
+    pub fn ls_weighting_scheme(&self) -> Option<WeightingScheme> {
+        self.ls_weighting_scheme
+    }
+
+    pub fn ls_weighting_details(&self) -> Option<&str> {
+        self.ls_weighting_details.as_deref()
+    }
+
+    pub fn ls_hydrogen_treatment(&self) -> Option<HydrogenTreatment> {
+        self.ls_hydrogen_treatment
+    }
+
+    pub fn ls_extinction_method(&self) -> Option<&str> {
+        self.ls_extinction_method.as_deref()
+    }
+
+    pub fn ls_extinction_coef(&self) -> Option<Numeric> {
+        self.ls_extinction_coef
+    }
+
+    pub fn ls_number_reflns(&self) -> Option<u32> {
+        self.ls_number_reflns
+    }
+
+    pub fn ls_number_parameters(&self) -> Option<u32> {
+        self.ls_number_parameters
+    }
+
+    pub fn ls_number_restraints(&self) -> Option<u32> {
+        self.ls_number_restraints
+    }
+
+    pub fn ls_r_factor_all(&self) -> Option<Numeric> {
+        self.ls_r_factor_all
+    }
+
+    pub fn ls_r_factor_gt(&self) -> Option<Numeric> {
+        self.ls_r_factor_gt
+    }
+
+    pub fn ls_wr_factor_ref(&self) -> Option<Numeric> {
+        self.ls_wr_factor_ref
+    }
+
+    pub fn ls_wr_factor_gt(&self) -> Option<Numeric> {
+        self.ls_wr_factor_gt
+    }
+
+    pub fn ls_goodness_of_fit_ref(&self) -> Option<Numeric> {
+        self.ls_goodness_of_fit_ref
+    }
+
+    pub fn ls_restrained_s_all(&self) -> Option<Numeric> {
+        self.ls_restrained_s_all
+    }
+
+    pub fn ls_shift_su_max(&self) -> Option<Numeric> {
+        self.ls_shift_su_max
+    }
+
+    pub fn ls_shift_su_mean(&self) -> Option<Numeric> {
+        self.ls_shift_su_mean
+    }
+
+    pub fn ls_abs_structure_flack(&self) -> Option<Numeric> {
+        self.ls_abs_structure_flack
+    }
+
+    pub fn ls_abs_structure_details(&self) -> Option<&str> {
+        self.ls_abs_structure_details.as_deref()
+    }
+
+    pub fn diff_density_max(&self) -> Option<Numeric> {
+        self.diff_density_max
+    }
+
+    pub fn diff_density_min(&self) -> Option<Numeric> {
+        self.diff_density_min
+    }
+
+    pub fn diff_density_rms(&self) -> Option<Numeric> {
+        self.diff_density_rms
+    }
+
+    /// The items which have a value.
+    pub fn items(&self) -> Vec<RefineItem> {
+        [
+            self.ls_structure_factor_coef
+                .map(RefineItem::Ls_structure_factor_coef),
+            self.ls_matrix_type.map(RefineItem::Ls_matrix_type),
+            self.ls_weighting_scheme
+                .map(RefineItem::Ls_weighting_scheme),
+            self.ls_weighting_details
+                .clone()
+                .map(RefineItem::Ls_weighting_details),
+            self.ls_hydrogen_treatment
+                .map(RefineItem::Ls_hydrogen_treatment),
+            self.ls_extinction_method
+                .clone()
+                .map(RefineItem::Ls_extinction_method),
+            self.ls_extinction_coef.map(RefineItem::Ls_extinction_coef),
+            self.ls_number_reflns.map(RefineItem::Ls_number_reflns),
+            self.ls_number_parameters
+                .map(RefineItem::Ls_number_parameters),
+            self.ls_number_restraints
+                .map(RefineItem::Ls_number_restraints),
+            self.ls_r_factor_all.map(RefineItem::Ls_R_factor_all),
+            self.ls_r_factor_gt.map(RefineItem::Ls_R_factor_gt),
+            self.ls_wr_factor_ref.map(RefineItem::Ls_wR_factor_ref),
+            self.ls_wr_factor_gt.map(RefineItem::Ls_wR_factor_gt),
+            self.ls_goodness_of_fit_ref
+                .map(RefineItem::Ls_goodness_of_fit_ref),
+            self.ls_restrained_s_all
+                .map(RefineItem::Ls_restrained_S_all),
+            self.ls_shift_su_max.map(RefineItem::Ls_shift_su_max),
+            self.ls_shift_su_mean.map(RefineItem::Ls_shift_su_mean),
+            self.ls_abs_structure_flack
+                .map(RefineItem::Ls_abs_structure_Flack),
+            self.ls_abs_structure_details
+                .clone()
+                .map(RefineItem::Ls_abs_structure_details),
+            self.diff_density_max.map(RefineItem::Diff_density_max),
+            self.diff_density_min.map(RefineItem::Diff_density_min),
+            self.diff_density_rms.map(RefineItem::Diff_density_rms),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn to_data_items(&self) -> Vec<DataItems<'static>> {
+        self.items()
+            .iter()
+            .map(|item| item.to_single_value_data())
+            .collect()
+    }
+
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Self, DataReadError> {
+        Ok(Self {
+            ls_structure_factor_coef: optional_single_parsed(
+                data_block,
+                "refine_ls_structure_factor_coef",
+            )?,
+            ls_matrix_type: optional_single_parsed(data_block, "refine_ls_matrix_type")?,
+            ls_weighting_scheme: optional_single_parsed(data_block, "refine_ls_weighting_scheme")?,
+            ls_weighting_details: optional_single_text(data_block, "refine_ls_weighting_details"),
+            ls_hydrogen_treatment: optional_single_parsed(
+                data_block,
+                "refine_ls_hydrogen_treatment",
+            )?,
+            ls_extinction_method: optional_single_text(data_block, "refine_ls_extinction_method"),
+            ls_extinction_coef: optional_single_numeric(data_block, "refine_ls_extinction_coef")?,
+            ls_number_reflns: optional_single_parsed(data_block, "refine_ls_number_reflns")?,
+            ls_number_parameters: optional_single_parsed(
+                data_block,
+                "refine_ls_number_parameters",
+            )?,
+            ls_number_restraints: optional_single_parsed(
+                data_block,
+                "refine_ls_number_restraints",
+            )?,
+            ls_r_factor_all: optional_single_numeric(data_block, "refine_ls_R_factor_all")?,
+            ls_r_factor_gt: optional_single_numeric(data_block, "refine_ls_R_factor_gt")?,
+            ls_wr_factor_ref: optional_single_numeric(data_block, "refine_ls_wR_factor_ref")?,
+            ls_wr_factor_gt: optional_single_numeric(data_block, "refine_ls_wR_factor_gt")?,
+            ls_goodness_of_fit_ref: optional_single_numeric(
+                data_block,
+                "refine_ls_goodness_of_fit_ref",
+            )?,
+            ls_restrained_s_all: optional_single_numeric(data_block, "refine_ls_restrained_S_all")?,
+            ls_shift_su_max: optional_single_numeric(data_block, "refine_ls_shift/su_max")?,
+            ls_shift_su_mean: optional_single_numeric(data_block, "refine_ls_shift/su_mean")?,
+            ls_abs_structure_flack: optional_single_numeric(
+                data_block,
+                "refine_ls_abs_structure_Flack",
+            )?,
+            ls_abs_structure_details: optional_single_text(
+                data_block,
+                "refine_ls_abs_structure_details",
+            ),
+            diff_density_max: optional_single_numeric(data_block, "refine_diff_density_max")?,
+            diff_density_min: optional_single_numeric(data_block, "refine_diff_density_min")?,
+            diff_density_rms: optional_single_numeric(data_block, "refine_diff_density_rms")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, Numeric, SyntacticUnit};
+
+    use super::{HydrogenTreatment, Refine, StructureFactorCoef};
+
+    #[test]
+    fn refine_items() {
+        let mut input = r#"data_I
+_refine_ls_structure_factor_coef  Fsqd
+_refine_ls_matrix_type            full
+_refine_ls_weighting_scheme       calc
+_refine_ls_hydrogen_treatment     constr
+_refine_ls_number_reflns          3307
+_refine_ls_R_factor_gt            0.0412
+_refine_ls_wR_factor_ref          0.1043
+_refine_ls_shift/su_max           0.001
+_refine_diff_density_max          0.27
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let refine = Refine::from_data_block(&block).unwrap();
+        assert_eq!(
+            refine.ls_structure_factor_coef(),
+            Some(StructureFactorCoef::Fsqd)
+        );
+        assert_eq!(
+            refine.ls_hydrogen_treatment(),
+            Some(HydrogenTreatment::Constr)
+        );
+        assert_eq!(refine.ls_number_reflns(), Some(3307));
+        assert_eq!(
+            refine.ls_r_factor_gt().map(|r| r.to_string()),
+            Some("0.0412".to_string())
+        );
+        assert!(refine.ls_shift_su_max().is_some());
+        assert_eq!(refine.items().len(), 9);
+
+        let built = Refine::builder()
+            .with_ls_structure_factor_coef(StructureFactorCoef::Fsqd)
+            .with_ls_r_factor_gt(Numeric::with_decimals(0.0412, 4))
+            .build();
+        let output: Vec<String> = built
+            .to_data_items()
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+        assert!(output[0].contains("_refine_ls_structure_factor_coef"));
+        assert!(output[0].contains("Fsqd"));
+        assert!(output[1].contains("0.0412"));
+    }
+}
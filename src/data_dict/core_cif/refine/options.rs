@@ -0,0 +1,193 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    data_dict::{reading::text_value, InvalidEnumeration},
+    grammar::Value,
+};
+
+/// `_refine_ls_structure_factor_coef`: the quantity minimized in the
+/// least-squares refinement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureFactorCoef {
+    /// Structure factor amplitude |F|.
+    F,
+    /// Squared structure factor |F|².
+    Fsqd,
+    /// Net intensity.
+    Inet,
+}
+
+impl Display for StructureFactorCoef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let option = match self {
+            StructureFactorCoef::F => "F",
+            StructureFactorCoef::Fsqd => "Fsqd",
+            StructureFactorCoef::Inet => "Inet",
+        };
+        f.write_str(option)
+    }
+}
+
+impl FromStr for StructureFactorCoef {
+    type Err = InvalidEnumeration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "f" => Ok(Self::F),
+            "fsqd" => Ok(Self::Fsqd),
+            "inet" => Ok(Self::Inet),
+            _ => Err(InvalidEnumeration(s.to_string())),
+        }
+    }
+}
+
+/// `_refine_ls_matrix_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixType {
+    Full,
+    Fullcycle,
+    Atomblock,
+    Userblock,
+    Diagonal,
+    Sparse,
+}
+
+impl Display for MatrixType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let option = match self {
+            MatrixType::Full => "full",
+            MatrixType::Fullcycle => "fullcycle",
+            MatrixType::Atomblock => "atomblock",
+            MatrixType::Userblock => "userblock",
+            MatrixType::Diagonal => "diagonal",
+            MatrixType::Sparse => "sparse",
+        };
+        f.write_str(option)
+    }
+}
+
+impl FromStr for MatrixType {
+    type Err = InvalidEnumeration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "fullcycle" => Ok(Self::Fullcycle),
+            "atomblock" => Ok(Self::Atomblock),
+            "userblock" => Ok(Self::Userblock),
+            "diagonal" => Ok(Self::Diagonal),
+            "sparse" => Ok(Self::Sparse),
+            _ => Err(InvalidEnumeration(s.to_string())),
+        }
+    }
+}
+
+/// `_refine_ls_weighting_scheme`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightingScheme {
+    /// Based on measured su's.
+    Sigma,
+    /// Unit or no weights applied.
+    Unit,
+    /// Calculated weights applied, see `_refine_ls_weighting_details`.
+    Calc,
+}
+
+impl Display for WeightingScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let option = match self {
+            WeightingScheme::Sigma => "sigma",
+            WeightingScheme::Unit => "unit",
+            WeightingScheme::Calc => "calc",
+        };
+        f.write_str(option)
+    }
+}
+
+impl FromStr for WeightingScheme {
+    type Err = InvalidEnumeration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sigma" => Ok(Self::Sigma),
+            "unit" => Ok(Self::Unit),
+            "calc" => Ok(Self::Calc),
+            _ => Err(InvalidEnumeration(s.to_string())),
+        }
+    }
+}
+
+/// `_refine_ls_hydrogen_treatment`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HydrogenTreatment {
+    /// All H-atom parameters refined.
+    Refall,
+    /// Only H-atom coordinates refined.
+    Refxyz,
+    /// Only H-atom displacement parameters refined.
+    RefU,
+    /// No H-atom parameters refined.
+    Noref,
+    /// H-atom parameters constrained.
+    Constr,
+    /// Some constrained, some independent.
+    Mixed,
+    /// H-atom parameters not defined.
+    Undef,
+}
+
+impl Display for HydrogenTreatment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let option = match self {
+            HydrogenTreatment::Refall => "refall",
+            HydrogenTreatment::Refxyz => "refxyz",
+            HydrogenTreatment::RefU => "refU",
+            HydrogenTreatment::Noref => "noref",
+            HydrogenTreatment::Constr => "constr",
+            HydrogenTreatment::Mixed => "mixed",
+            HydrogenTreatment::Undef => "undef",
+        };
+        f.write_str(option)
+    }
+}
+
+impl FromStr for HydrogenTreatment {
+    type Err = InvalidEnumeration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "refall" => Ok(Self::Refall),
+            "refxyz" => Ok(Self::Refxyz),
+            "refu" => Ok(Self::RefU),
+            "noref" => Ok(Self::Noref),
+            "constr" => Ok(Self::Constr),
+            "mixed" => Ok(Self::Mixed),
+            "undef" => Ok(Self::Undef),
+            _ => Err(InvalidEnumeration(s.to_string())),
+        }
+    }
+}
+
+impl From<StructureFactorCoef> for Value<'_> {
+    fn from(value: StructureFactorCoef) -> Self {
+        text_value(&value.to_string())
+    }
+}
+
+impl From<MatrixType> for Value<'_> {
+    fn from(value: MatrixType) -> Self {
+        text_value(&value.to_string())
+    }
+}
+
+impl From<WeightingScheme> for Value<'_> {
+    fn from(value: WeightingScheme) -> Self {
+        text_value(&value.to_string())
+    }
+}
+
+impl From<HydrogenTreatment> for Value<'_> {
+    fn from(value: HydrogenTreatment) -> Self {
+        text_value(&value.to_string())
+    }
+}
@@ -0,0 +1,199 @@
+//! The `_reflns_` category: the set of unique reflections after merging.
+
+use crate::{
+    data_dict::{
+        reading::{
+            optional_single_numeric, optional_single_parsed, optional_single_text, text_value,
+        },
+        CifTerm, DataReadError, SingleValueTerm,
+    },
+    grammar::{DataBlock, DataItems, Numeric, Tag, UnsignedInteger, Value},
+};
+
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
+#[derive(Debug, Clone)]
+pub enum ReflnsItem {
+    Friedel_coverage(Numeric),
+    Friedel_fraction_full(Numeric),
+    Friedel_fraction_max(Numeric),
+    Number_gt(u32),
+    Number_total(u32),
+    Threshold_expression(String),
+}
+
+impl CifTerm for ReflnsItem {
+    fn tag(&self) -> Tag<'static> {
+        let suffix = match self {
+            ReflnsItem::Friedel_coverage(_) => "Friedel_coverage",
+            ReflnsItem::Friedel_fraction_full(_) => "Friedel_fraction_full",
+            ReflnsItem::Friedel_fraction_max(_) => "Friedel_fraction_max",
+            ReflnsItem::Number_gt(_) => "number_gt",
+            ReflnsItem::Number_total(_) => "number_total",
+            ReflnsItem::Threshold_expression(_) => "threshold_expression",
+        };
+        Tag::new(format!("reflns_{suffix}"))
+    }
+}
+
+impl SingleValueTerm for ReflnsItem {
+    fn value(&self) -> Value<'static> {
+        match self {
+            ReflnsItem::Friedel_coverage(v)
+            | ReflnsItem::Friedel_fraction_full(v)
+            | ReflnsItem::Friedel_fraction_max(v) => Value::Numeric(*v),
+            ReflnsItem::Number_gt(v) | ReflnsItem::Number_total(v) => UnsignedInteger(*v).into(),
+            ReflnsItem::Threshold_expression(v) => text_value(v),
+        }
+    }
+}
+
+/// Reflection statistics after merging symmetry equivalents.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reflns {
+    number_total: Option<u32>,
+    number_gt: Option<u32>,
+    threshold_expression: Option<String>,
+    friedel_coverage: Option<Numeric>,
+    friedel_fraction_full: Option<Numeric>,
+    friedel_fraction_max: Option<Numeric>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReflnsBuilder {
+    reflns: Reflns,
+}
+
+impl ReflnsBuilder {
+    /// Number of unique reflections.
+    pub fn with_number_total(mut self, number_total: u32) -> Self {
+        self.reflns.number_total = Some(number_total);
+        self
+    }
+
+    /// Number of unique reflections above the `_reflns_threshold_expression`.
+    pub fn with_number_gt(mut self, number_gt: u32) -> Self {
+        self.reflns.number_gt = Some(number_gt);
+        self
+    }
+
+    /// e.g. `I>2\s(I)`.
+    pub fn with_threshold_expression(mut self, threshold_expression: impl Into<String>) -> Self {
+        self.reflns.threshold_expression = Some(threshold_expression.into());
+        self
+    }
+
+    pub fn with_friedel_coverage(mut self, friedel_coverage: Numeric) -> Self {
+        self.reflns.friedel_coverage = Some(friedel_coverage);
+        self
+    }
+
+    pub fn with_friedel_fraction_full(mut self, friedel_fraction_full: Numeric) -> Self {
+        self.reflns.friedel_fraction_full = Some(friedel_fraction_full);
+        self
+    }
+
+    pub fn with_friedel_fraction_max(mut self, friedel_fraction_max: Numeric) -> Self {
+        self.reflns.friedel_fraction_max = Some(friedel_fraction_max);
+        self
+    }
+
+    pub fn build(self) -> Reflns {
+        self.reflns
+    }
+}
+
+impl Reflns {
+    pub fn builder() -> ReflnsBuilder {
+        ReflnsBuilder::default()
+    }
+
+    pub fn number_total(&self) -> Option<u32> {
+        self.number_total
+    }
+
+    pub fn number_gt(&self) -> Option<u32> {
+        self.number_gt
+    }
+
+    pub fn threshold_expression(&self) -> Option<&str> {
+        self.threshold_expression.as_deref()
+    }
+
+    pub fn friedel_coverage(&self) -> Option<Numeric> {
+        self.friedel_coverage
+    }
+
+    pub fn friedel_fraction_full(&self) -> Option<Numeric> {
+        self.friedel_fraction_full
+    }
+
+    pub fn friedel_fraction_max(&self) -> Option<Numeric> {
+        self.friedel_fraction_max
+    }
+
+    /// The items which have a value.
+    pub fn items(&self) -> Vec<ReflnsItem> {
+        [
+            self.number_total.map(ReflnsItem::Number_total),
+            self.number_gt.map(ReflnsItem::Number_gt),
+            self.threshold_expression
+                .clone()
+                .map(ReflnsItem::Threshold_expression),
+            self.friedel_coverage.map(ReflnsItem::Friedel_coverage),
+            self.friedel_fraction_full
+                .map(ReflnsItem::Friedel_fraction_full),
+            self.friedel_fraction_max
+                .map(ReflnsItem::Friedel_fraction_max),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn to_data_items(&self) -> Vec<DataItems<'static>> {
+        self.items()
+            .iter()
+            .map(|item| item.to_single_value_data())
+            .collect()
+    }
+
+    pub fn from_data_block(data_block: &DataBlock) -> Result<Self, DataReadError> {
+        Ok(Self {
+            number_total: optional_single_parsed(data_block, "reflns_number_total")?,
+            number_gt: optional_single_parsed(data_block, "reflns_number_gt")?,
+            threshold_expression: optional_single_text(data_block, "reflns_threshold_expression"),
+            friedel_coverage: optional_single_numeric(data_block, "reflns_Friedel_coverage")?,
+            friedel_fraction_full: optional_single_numeric(
+                data_block,
+                "reflns_Friedel_fraction_full",
+            )?,
+            friedel_fraction_max: optional_single_numeric(
+                data_block,
+                "reflns_Friedel_fraction_max",
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grammar::{DataBlock, SyntacticUnit};
+
+    use super::Reflns;
+
+    #[test]
+    fn reflns_items() {
+        let mut input = r#"data_I
+_reflns_number_total              3307
+_reflns_number_gt                 2840
+_reflns_threshold_expression      'I > 2\s(I)'
+_reflns_Friedel_coverage          0.000
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let reflns = Reflns::from_data_block(&block).unwrap();
+        assert_eq!(reflns.number_total(), Some(3307));
+        assert_eq!(reflns.threshold_expression(), Some("I > 2\\s(I)"));
+        assert!(reflns.friedel_coverage().is_some());
+        assert_eq!(reflns.to_data_items().len(), 4);
+    }
+}
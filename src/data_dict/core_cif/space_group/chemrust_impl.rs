@@ -5,8 +5,80 @@ use crystallographic_group::hall_symbols::HallSymbolNotation;
 use crate::data_dict::{LoopValueTerm, SingleValueTerm};
 use crate::grammar::{CharString, DataItems, LoopUnit, UnquotedString};
 
-use super::{CrystalSystemCif, ITNumber, SpaceGroupItem, SpaceGroupLoopItem};
-pub(crate) fn basic_space_group_data<T: SymmetryInfo>(model: &T) -> Vec<DataItems> {
+use super::settings::OTHER_SETTINGS;
+use super::symop::same_operators;
+use super::{
+    CrystalSystemCif, ITNumber, SpaceGroupItem, SpaceGroupLoopItem, SpaceGroupSetting,
+    SymmetryOperator,
+};
+
+/// General positions of a Hall symbol, including the centring translations.
+pub(crate) fn hall_general_positions(symbol: &str) -> Option<Vec<SymmetryOperator>> {
+    let notation = HallSymbolNotation::try_from_str(symbol).ok()?;
+    notation
+        .general_positions()
+        .derive_full_sets()
+        .iter()
+        .flat_map(|v| {
+            v.iter()
+                .map(|mat| mat.jones_faithful_repr())
+                .collect::<Vec<String>>()
+        })
+        .map(|repr| repr.parse::<SymmetryOperator>().ok())
+        .collect()
+}
+
+/// IT number of a Hall symbol written differently from the tabulated
+/// settings, found by comparing its general positions with those of the
+/// groups in the database.
+pub(crate) fn it_number_of_hall_symbol(symbol: &str) -> Option<u8> {
+    let positions = hall_general_positions(symbol)?;
+    (1..=230_u8).find(|&it_number| {
+        DEFAULT_SPACE_GROUP_SYMBOLS
+            .get_hm_full_notation((it_number - 1) as usize)
+            .and_then(hall_general_positions)
+            .is_some_and(|group| same_operators(&group, &positions))
+    })
+}
+
+/// The tabulated origin or axes choice of the group, like `F d -3 m :2`,
+/// which is the setting of the group in the database, `None` when that is
+/// the tabulated default or the group has no such choice.
+pub(crate) fn database_setting(it_number: u8) -> Option<&'static (u8, &'static str, &'static str)> {
+    let mut choices = OTHER_SETTINGS
+        .iter()
+        .filter(|(number, symbol, _)| *number == it_number && symbol.contains(':'))
+        .peekable();
+    choices.peek()?;
+    let positions = DEFAULT_SPACE_GROUP_SYMBOLS
+        .get_hm_full_notation((it_number - 1) as usize)
+        .and_then(hall_general_positions)?;
+    choices.find(|(_, _, hall)| {
+        hall_general_positions(hall).is_some_and(|group| same_operators(&group, &positions))
+    })
+}
+
+/// Operators of the space group of the model, with its setting when they
+/// match the tabulated default setting of the group.
+pub(crate) fn model_space_group<T: SymmetryInfo>(
+    model: &T,
+) -> (Vec<SymmetryOperator>, Option<SpaceGroupSetting>) {
+    if !model.make_symmetry() {
+        let setting = SpaceGroupSetting::default_of(ITNumber::new(1));
+        return (vec![SymmetryOperator::identity()], Some(setting));
+    }
+    let it_number = model.get_space_group_it_num();
+    let operators = DEFAULT_SPACE_GROUP_SYMBOLS
+        .get_hm_full_notation((it_number - 1) as usize)
+        .and_then(hall_general_positions)
+        .unwrap_or_else(|| vec![SymmetryOperator::identity()]);
+    let setting = SpaceGroupSetting::default_of(ITNumber::new(it_number));
+    let is_default = hall_general_positions(setting.hall())
+        .is_some_and(|default| same_operators(&default, &operators));
+    (operators, is_default.then_some(setting))
+}
+
+pub(crate) fn basic_space_group_data<T: SymmetryInfo>(model: &T) -> Vec<DataItems<'static>> {
     let space_group = DEFAULT_SPACE_GROUP_SYMBOLS
         .get_hm_full_notation((model.get_space_group_it_num() - 1) as usize)
         .expect("Invalid space group number");
@@ -45,3 +117,77 @@ pub(crate) fn basic_space_group_data<T: SymmetryInfo>(model: &T) -> Vec<DataItem
     };
     items.to_vec()
 }
+
+#[cfg(test)]
+mod test {
+    use crystallographic_group::database::{LookUpSpaceGroup, DEFAULT_SPACE_GROUP_SYMBOLS};
+
+    use super::{
+        super::{settings::DEFAULT_SETTINGS, wyckoff::wyckoff_positions},
+        hall_general_positions, same_operators,
+    };
+    use crate::data_dict::core_cif::space_group::{
+        ITNumber, SiteSymmetryFinder, SpaceGroupSetting, SymmetryOperator,
+    };
+
+    /// The operators moved to an origin shifted by `shift`.
+    fn shifted(operators: &[SymmetryOperator], shift: [f64; 3]) -> Vec<SymmetryOperator> {
+        operators
+            .iter()
+            .map(|operator| {
+                // t + (1 - R) p, from the image R p + t of the origin shift
+                let image = operator.apply(&shift);
+                let translation =
+                    std::array::from_fn(|i| 2.0 * operator.translation()[i] + shift[i] - image[i]);
+                SymmetryOperator::new(operator.rotation(), translation)
+            })
+            .collect()
+    }
+
+    /// Every tabulated default setting is the group of the database with the
+    /// same IT number, up to the choice of origin.
+    #[test]
+    fn default_settings_match_database() {
+        let shifts: Vec<[f64; 3]> = (0..512)
+            .map(|i| [i / 64, i / 8 % 8, i % 8].map(|k| k as f64 / 8.0))
+            .collect();
+        for (it_number, hermann_mauguin, hall) in DEFAULT_SETTINGS {
+            let tabulated = hall_general_positions(hall).unwrap();
+            let database = DEFAULT_SPACE_GROUP_SYMBOLS
+                .get_hm_full_notation(it_number as usize - 1)
+                .and_then(hall_general_positions)
+                .unwrap();
+            assert!(
+                shifts
+                    .iter()
+                    .any(|shift| same_operators(&shifted(&tabulated, *shift), &database)),
+                "{it_number} {hermann_mauguin} ({hall}) differs from the database"
+            );
+        }
+    }
+
+    /// Every tabulated Wyckoff position has its multiplicity under the
+    /// operators of the group and is found again by its letter.
+    #[test]
+    fn tabulated_wyckoff_positions() {
+        let metric = [[100.0, 0.0, 0.0], [0.0, 100.0, 0.0], [0.0, 0.0, 100.0]];
+        let parameters = [0.1123, 0.2345, 0.3571];
+        for it_number in 1..=230 {
+            let Some(positions) = wyckoff_positions(it_number) else {
+                continue;
+            };
+            let setting = SpaceGroupSetting::default_of(ITNumber::new(it_number));
+            let operators = hall_general_positions(setting.hall()).unwrap();
+            let finder = SiteSymmetryFinder::new(operators, metric, 0.01).with_setting(setting);
+            for (letter, multiplicity, coordinates) in positions {
+                let representative: SymmetryOperator = coordinates.parse().unwrap();
+                let site = finder.site_symmetry(&representative.apply(&parameters));
+                assert_eq!(
+                    (site.multiplicity(), site.wyckoff_letter()),
+                    (*multiplicity, Ok(*letter)),
+                    "{it_number} {letter} {coordinates}"
+                );
+            }
+        }
+    }
+}
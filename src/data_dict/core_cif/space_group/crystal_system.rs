@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+#[cfg(feature = "chemrust-core")]
 use crystallographic_group::database::CrystalSystem as CS;
 // use serde::{Deserialize, Serialize};
 
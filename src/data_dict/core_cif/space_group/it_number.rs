@@ -13,6 +13,10 @@ impl ITNumber {
     pub fn new(number: u8) -> Self {
         Self(number.clamp(1, 230))
     }
+
+    pub fn number(&self) -> u8 {
+        self.0
+    }
 }
 
 impl Display for ITNumber {
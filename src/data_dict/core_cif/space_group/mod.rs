@@ -1,11 +1,24 @@
 use crate::data_dict::{CifTerm, LoopValueTerm, SingleValueTerm};
-use crate::grammar::{CharString, Tag, UnquotedString, UnsignedInteger, Value};
+use crate::grammar::{CharString, Tag, UnsignedInteger, Value};
 
 mod crystal_system;
 mod it_number;
+mod resolve;
+mod settings;
+mod site_symmetry;
+mod symop;
+mod wyckoff;
 
 pub use crystal_system::CrystalSystemCif;
 pub use it_number::ITNumber;
+pub use resolve::{resolve_space_group, ResolvedSpaceGroup, SpaceGroupConflict, SpaceGroupSetting};
+pub(crate) use site_symmetry::{periodic_distance, wrap};
+pub use site_symmetry::{SiteSymmetry, SiteSymmetryFinder, WyckoffLetterError};
+pub use symop::{
+    space_group_operators, symmetry_operators, SymmetryOperator, SymmetryOperatorError,
+};
+#[cfg(test)]
+pub(crate) use symop::generate_group;
 
 #[cfg(feature = "chemrust-core")]
 pub mod chemrust_impl;
@@ -21,7 +34,7 @@ pub enum SpaceGroupItem {
 }
 
 impl CifTerm for SpaceGroupItem {
-    fn tag(&self) -> Tag {
+    fn tag(&self) -> Tag<'static> {
         let suffix = match self {
             SpaceGroupItem::Crystal_system(_) => "crystal_system",
             SpaceGroupItem::Id(_) => "id",
@@ -34,7 +47,7 @@ impl CifTerm for SpaceGroupItem {
 }
 
 impl SingleValueTerm for SpaceGroupItem {
-    fn value(&self) -> Value {
+    fn value(&self) -> Value<'static> {
         let value_string = match self {
             SpaceGroupItem::Crystal_system(item) => format!("{item}"),
             SpaceGroupItem::Id(item) => format!("{item}"),
@@ -43,7 +56,7 @@ impl SingleValueTerm for SpaceGroupItem {
                 item.to_string()
             }
         };
-        Value::CharString(CharString::Unquoted(UnquotedString::new(value_string)))
+        Value::CharString(CharString::from(value_string))
     }
 }
 
@@ -51,12 +64,12 @@ impl SingleValueTerm for SpaceGroupItem {
 #[derive(Debug, Clone)]
 pub enum SpaceGroupLoopItem {
     Symop_id(Vec<UnsignedInteger>),
-    Symop_operation_xyz(Vec<CharString>),
+    Symop_operation_xyz(Vec<CharString<'static>>),
     Symop_sg_id(Vec<UnsignedInteger>),
 }
 
 impl CifTerm for SpaceGroupLoopItem {
-    fn tag(&self) -> Tag {
+    fn tag(&self) -> Tag<'static> {
         let suffix = match self {
             SpaceGroupLoopItem::Symop_id(_) => "symop_id",
             SpaceGroupLoopItem::Symop_operation_xyz(_) => "symop_operation_xyz",
@@ -67,7 +80,7 @@ impl CifTerm for SpaceGroupLoopItem {
 }
 
 impl LoopValueTerm for SpaceGroupLoopItem {
-    fn values(&self) -> Vec<Value> {
+    fn values(&self) -> Vec<Value<'static>> {
         match self {
             SpaceGroupLoopItem::Symop_sg_id(uis) | SpaceGroupLoopItem::Symop_id(uis) => {
                 uis.iter().map(|&u| u.into()).collect()
@@ -0,0 +1,391 @@
+use std::fmt::Display;
+
+use crate::{
+    data_dict::{reading::optional_single_text, DataReadError},
+    grammar::DataBlock,
+};
+
+use super::{
+    settings::{DEFAULT_SETTINGS, OTHER_SETTINGS},
+    ITNumber,
+};
+
+/// Tags giving the space group, in the order they are tried.
+const IT_NUMBER_TAGS: [&str; 2] = ["space_group_IT_number", "symmetry_Int_Tables_number"];
+const HALL_TAGS: [&str; 2] = ["space_group_name_Hall", "symmetry_space_group_name_Hall"];
+const HERMANN_MAUGUIN_TAGS: [&str; 2] =
+    ["space_group_name_H-M_alt", "symmetry_space_group_name_H-M"];
+
+/// A space group in a particular setting, with its Hermann-Mauguin and Hall
+/// symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpaceGroupSetting {
+    it_number: ITNumber,
+    hermann_mauguin: &'static str,
+    hall: &'static str,
+}
+
+impl SpaceGroupSetting {
+    fn from_entry(&(it_number, hermann_mauguin, hall): &(u8, &'static str, &'static str)) -> Self {
+        Self {
+            it_number: ITNumber::new(it_number),
+            hermann_mauguin,
+            hall,
+        }
+    }
+
+    fn all() -> impl Iterator<Item = Self> {
+        DEFAULT_SETTINGS
+            .iter()
+            .chain(OTHER_SETTINGS.iter())
+            .map(Self::from_entry)
+    }
+
+    /// The default setting of the space group, the origin or axes choice of
+    /// the space group database when it has one.
+    pub fn default_of(it_number: ITNumber) -> Self {
+        Self::database_default(it_number)
+            .unwrap_or_else(|| Self::from_entry(&DEFAULT_SETTINGS[it_number.number() as usize - 1]))
+    }
+
+    #[cfg(feature = "chemrust-core")]
+    fn database_default(it_number: ITNumber) -> Option<Self> {
+        super::chemrust_impl::database_setting(it_number.number()).map(Self::from_entry)
+    }
+
+    #[cfg(not(feature = "chemrust-core"))]
+    fn database_default(_it_number: ITNumber) -> Option<Self> {
+        None
+    }
+
+    /// Look up a Hall symbol such as `-P 2ybc`. Spacing and letter case are
+    /// ignored.
+    pub fn from_hall(symbol: &str) -> Option<Self> {
+        let normalized = normalize_hall(symbol);
+        Self::all()
+            .find(|setting| normalize_hall(setting.hall) == normalized)
+            .or_else(|| Self::from_hall_database(symbol.trim()))
+    }
+
+    #[cfg(feature = "chemrust-core")]
+    fn from_hall_database(symbol: &str) -> Option<Self> {
+        super::chemrust_impl::it_number_of_hall_symbol(symbol)
+            .map(|it_number| Self::default_of(ITNumber::new(it_number)))
+    }
+
+    #[cfg(not(feature = "chemrust-core"))]
+    fn from_hall_database(_symbol: &str) -> Option<Self> {
+        None
+    }
+
+    /// Look up a Hermann-Mauguin symbol such as `P 21/c`, `P 1 21/n 1` or
+    /// `F d -3 m :2`. Spacing, underscores and letter case are ignored. The
+    /// origin choice `:1` and hexagonal axes `:H` are the tabulated symbols
+    /// without a choice; a symbol without a choice gives the
+    /// [default setting](Self::default_of) of its group.
+    pub fn from_hermann_mauguin(symbol: &str) -> Option<Self> {
+        let (symbol, choice) = normalize_hermann_mauguin(symbol);
+        let accepts = |setting_choice: &str| {
+            choice.is_empty()
+                || setting_choice == choice
+                || setting_choice.is_empty() && matches!(choice.as_str(), "1" | "h")
+        };
+        let find = |symbol: &str| {
+            let candidates: Vec<Self> = Self::all()
+                .filter(|setting| {
+                    let (setting_symbol, setting_choice) =
+                        normalize_hermann_mauguin(setting.hermann_mauguin);
+                    setting_symbol == symbol && accepts(&setting_choice)
+                })
+                .collect();
+            let first = *candidates.first()?;
+            let default = Self::default_of(first.it_number);
+            Some(if candidates.contains(&default) {
+                default
+            } else {
+                first
+            })
+        };
+        find(&symbol).or_else(|| {
+            // Unspaced full monoclinic symbols like `P121/c1`
+            let bytes = symbol.as_bytes();
+            (bytes.len() > 3 && bytes[1] == b'1' && bytes[bytes.len() - 1] == b'1')
+                .then(|| format!("{}{}", &symbol[..1], &symbol[2..symbol.len() - 1]))
+                .and_then(|symbol| find(&symbol))
+        })
+    }
+
+    pub fn it_number(&self) -> ITNumber {
+        self.it_number
+    }
+
+    pub fn hermann_mauguin(&self) -> &'static str {
+        self.hermann_mauguin
+    }
+
+    pub fn hall(&self) -> &'static str {
+        self.hall
+    }
+}
+
+fn normalize_hall(symbol: &str) -> String {
+    symbol
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// The symbol and the origin or axes choice after `:`, both lowercase
+/// without spacing.
+fn normalize_hermann_mauguin(symbol: &str) -> (String, String) {
+    let (symbol, choice) = match symbol.split_once(':') {
+        Some((symbol, choice)) => (symbol, choice.trim().to_lowercase()),
+        None => (symbol, String::new()),
+    };
+    let tokens: Vec<&str> = symbol.split_whitespace().collect();
+    // Full monoclinic symbols like `P 1 21/c 1` keep only the unique axis.
+    let tokens: Vec<&str> = if tokens.len() == 4 && tokens[1] == "1" && tokens[3] == "1" {
+        vec![tokens[0], tokens[2]]
+    } else {
+        tokens
+    };
+    (tokens.concat().replace('_', "").to_lowercase(), choice)
+}
+
+/// A space group tag whose value disagrees with the resolved space group,
+/// or is not recognized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpaceGroupConflict {
+    tag: &'static str,
+    value: String,
+    it_number: Option<ITNumber>,
+}
+
+impl SpaceGroupConflict {
+    pub fn tag(&self) -> &str {
+        self.tag
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The IT number given by this tag, `None` if the value is not
+    /// recognized.
+    pub fn it_number(&self) -> Option<ITNumber> {
+        self.it_number
+    }
+}
+
+impl Display for SpaceGroupConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.it_number {
+            Some(it_number) => write!(
+                f,
+                "_{} '{}' gives space group number {}",
+                self.tag, self.value, it_number
+            ),
+            None => write!(
+                f,
+                "_{} '{}' is not a recognized space group",
+                self.tag, self.value
+            ),
+        }
+    }
+}
+
+/// The space group of a data block, see [`resolve_space_group`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSpaceGroup {
+    setting: SpaceGroupSetting,
+    resolved_from: &'static str,
+    conflicts: Vec<SpaceGroupConflict>,
+}
+
+impl ResolvedSpaceGroup {
+    pub fn it_number(&self) -> ITNumber {
+        self.setting.it_number
+    }
+
+    /// The setting named by the Hall or Hermann-Mauguin symbol, or the
+    /// default setting when only the IT number is given.
+    pub fn setting(&self) -> SpaceGroupSetting {
+        self.setting
+    }
+
+    /// The tag the space group was determined from.
+    pub fn resolved_from(&self) -> &str {
+        self.resolved_from
+    }
+
+    /// Other space group tags in the block which disagree with the resolved
+    /// space group.
+    pub fn conflicts(&self) -> &[SpaceGroupConflict] {
+        &self.conflicts
+    }
+}
+
+/// Determine the space group of the data block from, in this order, the IT
+/// number, the Hall symbol and the Hermann-Mauguin symbol, including the
+/// legacy `_symmetry_` tags. The first recognized value decides; every
+/// other given value which does not agree with it is reported in
+/// [`ResolvedSpaceGroup::conflicts`].
+pub fn resolve_space_group(data_block: &DataBlock) -> Result<ResolvedSpaceGroup, DataReadError> {
+    type Lookup = fn(&str) -> Option<SpaceGroupSetting>;
+    let lookups: [(&[&'static str], Lookup); 3] = [
+        (&IT_NUMBER_TAGS, |value| {
+            value
+                .parse::<u8>()
+                .ok()
+                .filter(|n| (1..=230).contains(n))
+                .map(|n| SpaceGroupSetting::default_of(ITNumber::new(n)))
+        }),
+        (&HALL_TAGS, SpaceGroupSetting::from_hall),
+        (
+            &HERMANN_MAUGUIN_TAGS,
+            SpaceGroupSetting::from_hermann_mauguin,
+        ),
+    ];
+    let given: Vec<(&'static str, String, Option<SpaceGroupSetting>)> = lookups
+        .iter()
+        .flat_map(|(tags, lookup)| {
+            tags.iter().filter_map(|tag| {
+                optional_single_text(data_block, tag).map(|value| {
+                    let setting = lookup(&value);
+                    (*tag, value, setting)
+                })
+            })
+        })
+        .collect();
+    let Some((resolved_from, it_number)) = given
+        .iter()
+        .find_map(|(tag, _, setting)| setting.map(|s| (*tag, s.it_number)))
+    else {
+        return Err(match given.first() {
+            Some((tag, value, _)) => DataReadError::InvalidValue {
+                tag: tag.to_string(),
+                row: None,
+                found: value.clone(),
+            },
+            None => DataReadError::MissingTag(IT_NUMBER_TAGS[0].to_string()),
+        });
+    };
+    let setting = given
+        .iter()
+        .filter(|(tag, _, _)| !IT_NUMBER_TAGS.contains(tag))
+        .find_map(|(_, _, setting)| setting.filter(|s| s.it_number == it_number))
+        .unwrap_or_else(|| SpaceGroupSetting::default_of(it_number));
+    let conflicts = given
+        .into_iter()
+        .filter(|(_, _, setting)| setting.map(|s| s.it_number) != Some(it_number))
+        .map(|(tag, value, setting)| SpaceGroupConflict {
+            tag,
+            value,
+            it_number: setting.map(|s| s.it_number),
+        })
+        .collect();
+    Ok(ResolvedSpaceGroup {
+        setting,
+        resolved_from,
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::grammar::{DataBlock, SyntacticUnit};
+
+    use super::{normalize_hermann_mauguin, resolve_space_group, ITNumber, SpaceGroupSetting};
+
+    #[test]
+    fn space_group_lookup() {
+        let symbols: HashSet<(String, String)> = SpaceGroupSetting::all()
+            .map(|setting| normalize_hermann_mauguin(setting.hermann_mauguin()))
+            .collect();
+        assert_eq!(symbols.len(), SpaceGroupSetting::all().count());
+        let number = |setting: Option<SpaceGroupSetting>| setting.map(|s| s.it_number().number());
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("P 21/c")),
+            Some(14)
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("P 1 21/n 1")),
+            Some(14)
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("P2_1/c")),
+            Some(14)
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("C121")),
+            Some(5)
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("Fd-3m:2")),
+            Some(227)
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("R -3 m :H")),
+            Some(166)
+        );
+        assert_eq!(number(SpaceGroupSetting::from_hall("-p 2YBC")), Some(14));
+        assert_eq!(
+            SpaceGroupSetting::from_hermann_mauguin("F d -3 m :2").map(|s| s.hall()),
+            Some("-F 4vw 2vw 3")
+        );
+        assert_eq!(number(SpaceGroupSetting::from_hermann_mauguin("P 7")), None);
+        assert_eq!(
+            SpaceGroupSetting::from_hermann_mauguin("Fd-3m:1").map(|s| s.hall()),
+            Some("F 4d 2 3 -1d")
+        );
+        assert_eq!(
+            SpaceGroupSetting::from_hermann_mauguin("Fd-3m"),
+            Some(SpaceGroupSetting::default_of(ITNumber::new(227)))
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("P121/c1:1")),
+            Some(14)
+        );
+        assert_eq!(
+            number(SpaceGroupSetting::from_hermann_mauguin("P121/c1:3")),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_from_symbols() {
+        let mut input = r#"data_hall
+_symmetry_space_group_name_Hall '-P 2yn'
+_symmetry_space_group_name_H-M 'P 21/n'
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let resolved = resolve_space_group(&block).unwrap();
+        assert_eq!(resolved.it_number().number(), 14);
+        assert_eq!(resolved.resolved_from(), "symmetry_space_group_name_Hall");
+        assert_eq!(resolved.setting().hermann_mauguin(), "P 21/n");
+        assert!(resolved.conflicts().is_empty());
+
+        let mut input = r#"data_conflict
+_space_group_IT_number 14
+_space_group_name_H-M_alt 'P 1 21 1'
+_symmetry_space_group_name_Hall '-P 2yc'
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let resolved = resolve_space_group(&block).unwrap();
+        assert_eq!(resolved.it_number().number(), 14);
+        assert_eq!(resolved.setting().hall(), "-P 2ybc");
+        let conflicts: Vec<Option<u8>> = resolved
+            .conflicts()
+            .iter()
+            .map(|c| c.it_number().map(|n| n.number()))
+            .collect();
+        assert_eq!(conflicts, vec![Some(13), Some(4)]);
+
+        let mut input = "data_none\n_cell_length_a 1.0\n";
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        assert!(resolve_space_group(&block).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use std::fmt::Display;
+
+use crate::{
+    data_dict::{
+        core_cif::cell::{metric_dot, CellParameters},
+        DataReadError,
+    },
+    grammar::DataBlock,
+};
+
+use super::{
+    resolve_space_group, space_group_operators,
+    symop::{same_operators, setting_operators},
+    wyckoff::wyckoff_positions,
+    ITNumber, SpaceGroupSetting, SymmetryOperator,
+};
+
+/// Symmetry of a site under the operators of a space group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SiteSymmetry {
+    multiplicity: usize,
+    order: usize,
+    wyckoff_letter: Result<char, WyckoffLetterError>,
+}
+
+impl SiteSymmetry {
+    /// Number of equivalent positions in the unit cell.
+    pub fn multiplicity(&self) -> usize {
+        self.multiplicity
+    }
+
+    /// Number of operators leaving the site invariant, up to lattice
+    /// translations.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// The Wyckoff letter, known only for the tabulated space groups in
+    /// their default setting.
+    pub fn wyckoff_letter(&self) -> Result<char, WyckoffLetterError> {
+        self.wyckoff_letter
+    }
+}
+
+/// Why the Wyckoff letter of a site cannot be assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WyckoffLetterError {
+    /// The operators are not known to be those of a space group setting.
+    UnknownSetting,
+    /// The Wyckoff positions are only tabulated for the default setting.
+    NonDefaultSetting(SpaceGroupSetting),
+    /// The Wyckoff positions of the space group are not tabulated.
+    UntabulatedSpaceGroup(ITNumber),
+    /// No tabulated position of the multiplicity of the site fits it.
+    NoMatchingPosition {
+        it_number: ITNumber,
+        multiplicity: usize,
+    },
+}
+
+impl Display for WyckoffLetterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WyckoffLetterError::UnknownSetting => {
+                write!(f, "the space group setting of the operators is not known")
+            }
+            WyckoffLetterError::NonDefaultSetting(setting) => write!(
+                f,
+                "Wyckoff positions are not tabulated for the setting '{}'",
+                setting.hermann_mauguin()
+            ),
+            WyckoffLetterError::UntabulatedSpaceGroup(it_number) => write!(
+                f,
+                "Wyckoff positions of space group {it_number} are not tabulated"
+            ),
+            WyckoffLetterError::NoMatchingPosition {
+                it_number,
+                multiplicity,
+            } => write!(
+                f,
+                "no Wyckoff position of multiplicity {multiplicity} of space group {it_number} fits the site"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WyckoffLetterError {}
+
+/// Finds symmetry-equivalent positions from the operators of a space group,
+/// including centring translations, and the cell metric.
+#[derive(Debug, Clone)]
+pub struct SiteSymmetryFinder {
+    operators: Vec<SymmetryOperator>,
+    metric: [[f64; 3]; 3],
+    tolerance: f64,
+    setting: Option<SpaceGroupSetting>,
+}
+
+impl SiteSymmetryFinder {
+    /// Positions closer than `tolerance`, in Å for the metric of a cell,
+    /// are taken as the same.
+    pub fn new(operators: Vec<SymmetryOperator>, metric: [[f64; 3]; 3], tolerance: f64) -> Self {
+        Self {
+            operators,
+            metric,
+            tolerance,
+            setting: None,
+        }
+    }
+
+    /// Finder for the operators of [`space_group_operators`] and the cell of
+    /// the data block. Wyckoff letters are looked up in the setting of
+    /// [`resolve_space_group`] unless the listed operators are known to
+    /// differ from it.
+    pub fn from_data_block(data_block: &DataBlock, tolerance: f64) -> Result<Self, DataReadError> {
+        let operators = space_group_operators(data_block)?;
+        let metric = CellParameters::from_data_block(data_block)?.metric_tensor();
+        let setting = resolve_space_group(data_block)
+            .ok()
+            .map(|space_group| space_group.setting())
+            .filter(|setting| {
+                setting_operators(setting.hall())
+                    .is_none_or(|positions| same_operators(&positions, &operators))
+            });
+        let finder = Self::new(operators, metric, tolerance);
+        Ok(match setting {
+            Some(setting) => finder.with_setting(setting),
+            None => finder,
+        })
+    }
+
+    /// The setting the operators come from, to look up Wyckoff letters.
+    pub fn with_setting(self, setting: SpaceGroupSetting) -> Self {
+        Self {
+            setting: Some(setting),
+            ..self
+        }
+    }
+
+    pub fn operators(&self) -> &[SymmetryOperator] {
+        &self.operators
+    }
+
+    pub fn same_position(&self, u: &[f64; 3], v: &[f64; 3]) -> bool {
+        periodic_distance(&self.metric, u, v) < self.tolerance
+    }
+
+    /// The distinct images of the position in the unit cell, wrapped into
+    /// `[0, 1)`.
+    pub fn orbit(&self, position: &[f64; 3]) -> Vec<[f64; 3]> {
+        let mut images: Vec<[f64; 3]> = Vec::new();
+        self.operators.iter().for_each(|operator| {
+            let image = operator.apply(position).map(wrap);
+            if !images.iter().any(|other| self.same_position(&image, other)) {
+                images.push(image);
+            }
+        });
+        images
+    }
+
+    pub fn site_symmetry(&self, position: &[f64; 3]) -> SiteSymmetry {
+        let multiplicity = self.orbit(position).len();
+        SiteSymmetry {
+            multiplicity,
+            order: self.operators.len() / multiplicity.max(1),
+            wyckoff_letter: self.wyckoff_letter(position, multiplicity),
+        }
+    }
+
+    /// The first tabulated Wyckoff position of the right multiplicity
+    /// whose representative coordinates some image of the site fits.
+    fn wyckoff_letter(
+        &self,
+        position: &[f64; 3],
+        multiplicity: usize,
+    ) -> Result<char, WyckoffLetterError> {
+        let setting = self.setting.ok_or(WyckoffLetterError::UnknownSetting)?;
+        let it_number = setting.it_number();
+        if setting != SpaceGroupSetting::default_of(it_number) {
+            return Err(WyckoffLetterError::NonDefaultSetting(setting));
+        }
+        wyckoff_positions(it_number.number())
+            .ok_or(WyckoffLetterError::UntabulatedSpaceGroup(it_number))?
+            .iter()
+            .filter(|(_, m, _)| *m == multiplicity)
+            .find(|(_, _, coordinates)| {
+                let Ok(representative) = coordinates.parse::<SymmetryOperator>() else {
+                    return false;
+                };
+                self.operators.iter().any(|operator| {
+                    let image = operator.apply(position).map(wrap);
+                    self.fits(&representative, &image)
+                })
+            })
+            .map(|(letter, _, _)| *letter)
+            .ok_or(WyckoffLetterError::NoMatchingPosition {
+                it_number,
+                multiplicity,
+            })
+    }
+
+    /// Whether the position is of the form of the representative
+    /// coordinates, like `x,2x,1/4`, for some values of the free parameters.
+    fn fits(&self, representative: &SymmetryOperator, position: &[f64; 3]) -> bool {
+        let rotation = representative.rotation();
+        let translation = representative.translation();
+        // Each free parameter is read from a coordinate where it stands
+        // alone, as `x` in `x,2x,1/4`.
+        let mut parameters = [0.0; 3];
+        for k in 0..3 {
+            if rotation.iter().all(|row| row[k] == 0.0) {
+                continue;
+            }
+            let Some(i) = (0..3).find(|&i| {
+                rotation[i][k].abs() == 1.0 && (0..3).all(|j| j == k || rotation[i][j] == 0.0)
+            }) else {
+                return false;
+            };
+            parameters[k] = (position[i] - translation[i]) / rotation[i][k];
+        }
+        self.same_position(&representative.apply(&parameters), position)
+    }
+
+    /// One representative for each orbit of symmetry-equivalent atoms of the
+    /// same species, as indices into `positions` in their listed order.
+    pub fn asymmetric_unit<T: PartialEq>(
+        &self,
+        species: &[T],
+        positions: &[[f64; 3]],
+    ) -> Vec<(usize, SiteSymmetry)> {
+        let mut assigned = vec![false; positions.len()];
+        let mut representatives = Vec::new();
+        for i in 0..positions.len() {
+            if assigned[i] {
+                continue;
+            }
+            self.orbit(&positions[i]).iter().for_each(|image| {
+                (i..positions.len()).for_each(|j| {
+                    if !assigned[j]
+                        && species[j] == species[i]
+                        && self.same_position(image, &positions[j])
+                    {
+                        assigned[j] = true;
+                    }
+                })
+            });
+            representatives.push((i, self.site_symmetry(&positions[i])));
+        }
+        representatives
+    }
+}
+
+/// Wrap a fractional coordinate into `[0, 1)`.
+pub(crate) fn wrap(x: f64) -> f64 {
+    let wrapped = x.rem_euclid(1.0);
+    if 1.0 - wrapped < 1e-9 {
+        0.0
+    } else {
+        wrapped
+    }
+}
+
+/// Distance between the closest lattice translates of two positions.
+pub(crate) fn periodic_distance(metric: &[[f64; 3]; 3], u: &[f64; 3], v: &[f64; 3]) -> f64 {
+    let d: [f64; 3] = std::array::from_fn(|i| {
+        let d = u[i] - v[i];
+        d - d.round()
+    });
+    metric_dot(metric, &d, &d).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data_dict::core_cif::space_group::{
+        generate_group, ITNumber, SpaceGroupSetting, SymmetryOperator,
+    };
+
+    use super::{SiteSymmetryFinder, WyckoffLetterError};
+
+    fn finder(it_number: u8, operators: &[&str]) -> SiteSymmetryFinder {
+        let operators = operators.iter().map(|op| op.parse().unwrap()).collect();
+        let metric = [[25.0, 0.0, 0.0], [0.0, 36.0, 0.0], [0.0, 0.0, 49.0]];
+        SiteSymmetryFinder::new(operators, metric, 0.01)
+            .with_setting(SpaceGroupSetting::default_of(ITNumber::new(it_number)))
+    }
+
+    /// `(multiplicity, order, letter)` of each position in a cubic cell.
+    fn cubic_sites(
+        it_number: u8,
+        generators: &[&str],
+        positions: &[[f64; 3]],
+    ) -> Vec<(usize, usize, char)> {
+        let metric = [[16.0, 0.0, 0.0], [0.0, 16.0, 0.0], [0.0, 0.0, 16.0]];
+        let finder = SiteSymmetryFinder::new(generate_group(generators), metric, 0.01)
+            .with_setting(SpaceGroupSetting::default_of(ITNumber::new(it_number)));
+        positions
+            .iter()
+            .map(|position| {
+                let site = finder.site_symmetry(position);
+                (
+                    site.multiplicity(),
+                    site.order(),
+                    site.wyckoff_letter().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wyckoff_letters() {
+        let c2c = finder(
+            15,
+            &[
+                "x,y,z",
+                "-x,y,-z+1/2",
+                "-x,-y,-z",
+                "x,-y,z+1/2",
+                "x+1/2,y+1/2,z",
+                "-x+1/2,y+1/2,-z+1/2",
+                "-x+1/2,-y+1/2,-z",
+                "x+1/2,-y+1/2,z+1/2",
+            ],
+        );
+        let symmetry = |position: [f64; 3]| {
+            let site = c2c.site_symmetry(&position);
+            (site.multiplicity(), site.order(), site.wyckoff_letter())
+        };
+        assert_eq!(symmetry([0.1, 0.2, 0.3]), (8, 1, Ok('f')));
+        assert_eq!(symmetry([0.0, 0.3, 0.25]), (4, 2, Ok('e')));
+        assert_eq!(symmetry([0.0, 0.3, 0.75]), (4, 2, Ok('e')));
+        assert_eq!(symmetry([0.25, 0.25, 0.5]), (4, 2, Ok('d')));
+        assert_eq!(symmetry([0.5, 0.5, 0.0]), (4, 2, Ok('a')));
+
+        let p21c = finder(
+            14,
+            &["x,y,z", "-x,y+1/2,-z+1/2", "-x,-y,-z", "x,-y+1/2,z+1/2"],
+        );
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [0.2, 0.3, 0.4],
+            [0.0, 0.5, 0.5],
+            [0.8, 0.8, 0.1],
+        ];
+        let unit: Vec<(usize, usize, char)> = p21c
+            .asymmetric_unit(&["Fe", "O", "Fe", "O"], &positions)
+            .iter()
+            .map(|(i, site)| (*i, site.multiplicity(), site.wyckoff_letter().unwrap()))
+            .collect();
+        assert_eq!(unit, [(0, 2, 'a'), (1, 4, 'e')]);
+        let op: SymmetryOperator = "-x,-y,-z".parse().unwrap();
+        assert_eq!(p21c.orbit(&op.apply(&positions[1])).len(), 4);
+
+        let p21n = p21c
+            .clone()
+            .with_setting(SpaceGroupSetting::from_hermann_mauguin("P 21/n").unwrap());
+        assert!(matches!(
+            p21n.site_symmetry(&positions[1]).wyckoff_letter(),
+            Err(WyckoffLetterError::NonDefaultSetting(_))
+        ));
+        let p2c = finder(13, &["x,y,z", "-x,y,-z+1/2", "-x,-y,-z", "x,-y,z+1/2"]);
+        assert_eq!(
+            p2c.site_symmetry(&positions[1]).wyckoff_letter(),
+            Err(WyckoffLetterError::UntabulatedSpaceGroup(ITNumber::new(13)))
+        );
+        let unknown = SiteSymmetryFinder::new(
+            p2c.operators().to_vec(),
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            0.01,
+        );
+        assert_eq!(
+            unknown.site_symmetry(&positions[1]).wyckoff_letter(),
+            Err(WyckoffLetterError::UnknownSetting)
+        );
+    }
+
+    #[test]
+    fn cubic_wyckoff_letters() {
+        let m3m = ["z,x,y", "-x,-y,z", "-x,y,-z", "y,x,-z", "-x,-y,-z"];
+        let fm3m = [&m3m[..], &["x,y+1/2,z+1/2", "x+1/2,y,z+1/2"]].concat();
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [0.5, 0.5, 0.5],
+            [0.25, 0.25, 0.25],
+            [0.0, 0.25, 0.25],
+            [0.2, 0.0, 0.0],
+            [0.1, 0.1, 0.1],
+            [0.2, 0.25, 0.25],
+            [0.0, 0.2, 0.2],
+            [0.5, 0.2, 0.2],
+            [0.0, 0.1, 0.3],
+            [0.1, 0.1, 0.3],
+            [0.1, 0.2, 0.35],
+        ];
+        assert_eq!(
+            cubic_sites(225, &fm3m, &positions),
+            [
+                (4, 48, 'a'),
+                (4, 48, 'b'),
+                (8, 24, 'c'),
+                (24, 8, 'd'),
+                (24, 8, 'e'),
+                (32, 6, 'f'),
+                (48, 4, 'g'),
+                (48, 4, 'h'),
+                (48, 4, 'i'),
+                (96, 2, 'j'),
+                (96, 2, 'k'),
+                (192, 1, 'l'),
+            ]
+        );
+
+        let im3m = [&m3m[..], &["x+1/2,y+1/2,z+1/2"]].concat();
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [0.0, 0.5, 0.5],
+            [0.25, 0.25, 0.25],
+            [0.25, 0.0, 0.5],
+            [0.2, 0.0, 0.0],
+            [0.1, 0.1, 0.1],
+            [0.2, 0.0, 0.5],
+            [0.0, 0.2, 0.2],
+            [0.25, 0.2, 0.3],
+            [0.0, 0.1, 0.3],
+            [0.1, 0.1, 0.3],
+            [0.1, 0.2, 0.35],
+        ];
+        let letters: Vec<char> = cubic_sites(229, &im3m, &positions)
+            .iter()
+            .map(|(_, _, letter)| *letter)
+            .collect();
+        assert_eq!(letters, "abcdefghijkl".chars().collect::<Vec<char>>());
+    }
+}
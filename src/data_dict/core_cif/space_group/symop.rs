@@ -0,0 +1,403 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    data_dict::{
+        reading::{optional_single_text, tag_values},
+        DataReadError,
+    },
+    grammar::{DataBlock, Value},
+};
+
+use super::resolve_space_group;
+
+/// A symmetry operator acting on fractional coordinates, `x' = R x + t`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SymmetryOperator {
+    rotation: [[f64; 3]; 3],
+    translation: [f64; 3],
+}
+
+impl SymmetryOperator {
+    pub fn new(rotation: [[f64; 3]; 3], translation: [f64; 3]) -> Self {
+        Self {
+            rotation,
+            translation,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            [0.0; 3],
+        )
+    }
+
+    pub fn rotation(&self) -> [[f64; 3]; 3] {
+        self.rotation
+    }
+
+    pub fn translation(&self) -> [f64; 3] {
+        self.translation
+    }
+
+    /// Apply the operator to a fractional coordinate.
+    pub fn apply(&self, coord: &[f64; 3]) -> [f64; 3] {
+        let mut result = self.translation;
+        result
+            .iter_mut()
+            .zip(self.rotation.iter())
+            .for_each(|(r, row)| {
+                *r += row
+                    .iter()
+                    .zip(coord.iter())
+                    .map(|(m, x)| m * x)
+                    .sum::<f64>()
+            });
+        result
+    }
+
+    /// Whether both operators are the same up to lattice translations.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        let same_rotation = self
+            .rotation
+            .iter()
+            .flatten()
+            .zip(other.rotation.iter().flatten())
+            .all(|(a, b)| (a - b).abs() < 1e-6);
+        same_rotation
+            && self
+                .translation
+                .iter()
+                .zip(other.translation.iter())
+                .all(|(a, b)| {
+                    let d = (a - b).rem_euclid(1.0);
+                    d.min(1.0 - d) < 1e-6
+                })
+    }
+}
+
+#[cfg(feature = "chemrust-core")]
+impl SymmetryOperator {
+    pub fn from_matrices(
+        rotation: &nalgebra::Matrix3<f64>,
+        translation: &nalgebra::Vector3<f64>,
+    ) -> Self {
+        Self::new(
+            std::array::from_fn(|i| std::array::from_fn(|j| rotation[(i, j)])),
+            std::array::from_fn(|i| translation[i]),
+        )
+    }
+
+    pub fn rotation_matrix(&self) -> nalgebra::Matrix3<f64> {
+        nalgebra::Matrix3::from_fn(|i, j| self.rotation[i][j])
+    }
+
+    pub fn translation_vector(&self) -> nalgebra::Vector3<f64> {
+        nalgebra::Vector3::from(self.translation)
+    }
+}
+
+/// Writes the operator in the `-x+1/2,y+1/2,-z+1/2` form used by
+/// `_space_group_symop_operation_xyz`.
+impl Display for SymmetryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let components: Vec<String> = self
+            .rotation
+            .iter()
+            .zip(self.translation.iter())
+            .map(|(row, t)| format_component(row, *t))
+            .collect();
+        f.write_str(&components.join(","))
+    }
+}
+
+fn format_component(row: &[f64; 3], translation: f64) -> String {
+    let mut component = String::new();
+    row.iter()
+        .zip(["x", "y", "z"])
+        .filter(|(coefficient, _)| coefficient.abs() > 1e-9)
+        .for_each(|(coefficient, axis)| {
+            let sign = if *coefficient < 0.0 { "-" } else { "+" };
+            let magnitude = coefficient.abs();
+            if (magnitude - 1.0).abs() < 1e-9 {
+                component.push_str(&format!("{sign}{axis}"));
+            } else {
+                component.push_str(&format!("{sign}{}{axis}", format_number(magnitude)));
+            }
+        });
+    if translation.abs() > 1e-9 || component.is_empty() {
+        let sign = if translation < 0.0 { "-" } else { "+" };
+        component.push_str(&format!("{sign}{}", format_number(translation.abs())));
+    }
+    component
+        .strip_prefix('+')
+        .map(str::to_string)
+        .unwrap_or(component)
+}
+
+/// A fraction with a small denominator where possible, e.g. `5/6`.
+fn format_number(value: f64) -> String {
+    [1, 2, 3, 4, 6, 8, 12]
+        .iter()
+        .find_map(|&denominator| {
+            let numerator = value * denominator as f64;
+            ((numerator - numerator.round()).abs() < 1e-6).then(|| match denominator {
+                1 => format!("{}", numerator.round()),
+                _ => format!("{}/{denominator}", numerator.round()),
+            })
+        })
+        .unwrap_or_else(|| format!("{value}"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymmetryOperatorError {
+    operator: String,
+    reason: String,
+}
+
+impl Display for SymmetryOperatorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is not a valid symmetry operator: {}",
+            self.operator, self.reason
+        )
+    }
+}
+
+impl std::error::Error for SymmetryOperatorError {}
+
+/// A number written as a fraction `1/2` or a decimal `0.5`. A zero
+/// denominator, `inf` and `NaN` are rejected.
+fn parse_number(text: &str) -> Option<f64> {
+    match text.split_once('/') {
+        Some((n, d)) => {
+            let denominator = d.parse::<f64>().ok().filter(|d| *d != 0.0)?;
+            Some(n.parse::<f64>().ok()? / denominator)
+        }
+        None => text.parse::<f64>().ok(),
+    }
+    .filter(|number| number.is_finite())
+}
+
+/// Parse one component of an operator like `-x+1/2` or `1/2-X` into the row
+/// of the rotation matrix and the translation. Coefficients may be written
+/// before the axis, as in `2x` or `2*x`.
+fn parse_component(component: &str) -> Result<([f64; 3], f64), String> {
+    let mut row = [0.0; 3];
+    let mut translation = 0.0;
+    let mut rest = component;
+    if rest.is_empty() {
+        return Err("empty component".to_string());
+    }
+    while !rest.is_empty() {
+        let sign = match rest.as_bytes()[0] {
+            b'-' => {
+                rest = &rest[1..];
+                -1.0
+            }
+            b'+' => {
+                rest = &rest[1..];
+                1.0
+            }
+            _ => 1.0,
+        };
+        let end = rest.find(['+', '-']).unwrap_or(rest.len());
+        let term = &rest[..end];
+        rest = &rest[end..];
+        let axis = match term.chars().last() {
+            Some('x') => Some(0),
+            Some('y') => Some(1),
+            Some('z') => Some(2),
+            _ => None,
+        };
+        match axis {
+            Some(axis) => {
+                let coefficient = term[..term.len() - 1].trim_end_matches('*');
+                let coefficient = match coefficient {
+                    "" => 1.0,
+                    c => parse_number(c).ok_or_else(|| format!("invalid coefficient '{c}'"))?,
+                };
+                row[axis] += sign * coefficient;
+            }
+            None if term.is_empty() => return Err(format!("missing term in '{component}'")),
+            None => {
+                translation +=
+                    sign * parse_number(term).ok_or_else(|| format!("invalid term '{term}'"))?;
+            }
+        }
+    }
+    Ok((row, translation))
+}
+
+impl FromStr for SymmetryOperator {
+    type Err = SymmetryOperatorError;
+
+    /// Whitespace and letter case are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| SymmetryOperatorError {
+            operator: s.to_string(),
+            reason,
+        };
+        let compact: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        let components: Vec<&str> = compact.split(',').collect();
+        let [x, y, z] = components.as_slice() else {
+            return Err(error(format!(
+                "expected 3 comma-separated components, found {}",
+                components.len()
+            )));
+        };
+        let (row_x, t_x) = parse_component(x).map_err(error)?;
+        let (row_y, t_y) = parse_component(y).map_err(error)?;
+        let (row_z, t_z) = parse_component(z).map_err(error)?;
+        Ok(Self::new([row_x, row_y, row_z], [t_x, t_y, t_z]))
+    }
+}
+
+/// The symmetry operators listed in the data block under
+/// `_space_group_symop_operation_xyz` or the legacy
+/// `_symmetry_equiv_pos_as_xyz`, in the listed order. Only the identity is
+/// returned when neither is present.
+pub fn symmetry_operators(data_block: &DataBlock) -> Result<Vec<SymmetryOperator>, DataReadError> {
+    [
+        "space_group_symop_operation_xyz",
+        "symmetry_equiv_pos_as_xyz",
+    ]
+    .iter()
+    .find_map(|tag| tag_values(data_block, tag).map(|values| (tag, values)))
+    .map(|(tag, values)| {
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(row, value)| {
+                let text = match value {
+                    Value::CharString(s) => s.as_ref().to_string(),
+                    Value::TextField(t) => t.raw_string(),
+                    v => v.to_string(),
+                };
+                text.parse::<SymmetryOperator>()
+                    .map_err(|_| DataReadError::invalid(tag, Some(row), value))
+            })
+            .collect()
+    })
+    .unwrap_or_else(|| Ok(vec![SymmetryOperator::identity()]))
+}
+
+/// The symmetry operators of the data block: the listed ones, or when no
+/// list is given, the general positions of the space group resolved by
+/// [`resolve_space_group`]. Only the identity is returned when the block
+/// gives no symmetry at all; a space group whose general positions cannot
+/// be derived is an error on the tag it was resolved from.
+pub fn space_group_operators(
+    data_block: &DataBlock,
+) -> Result<Vec<SymmetryOperator>, DataReadError> {
+    let listed = [
+        "space_group_symop_operation_xyz",
+        "symmetry_equiv_pos_as_xyz",
+    ]
+    .iter()
+    .any(|tag| tag_values(data_block, tag).is_some());
+    if listed {
+        return symmetry_operators(data_block);
+    }
+    match resolve_space_group(data_block) {
+        Ok(space_group) => setting_operators(space_group.setting().hall()).ok_or_else(|| {
+            let tag = space_group.resolved_from();
+            DataReadError::InvalidValue {
+                tag: tag.to_string(),
+                row: None,
+                found: optional_single_text(data_block, tag).unwrap_or_default(),
+            }
+        }),
+        Err(DataReadError::MissingTag(_)) => Ok(vec![SymmetryOperator::identity()]),
+        Err(e) => Err(e),
+    }
+}
+
+/// General positions of a Hall symbol, `None` when they cannot be derived.
+#[cfg(feature = "chemrust-core")]
+pub(crate) fn setting_operators(hall: &str) -> Option<Vec<SymmetryOperator>> {
+    super::chemrust_impl::hall_general_positions(hall)
+}
+
+#[cfg(not(feature = "chemrust-core"))]
+pub(crate) fn setting_operators(_hall: &str) -> Option<Vec<SymmetryOperator>> {
+    None
+}
+
+/// Whether both lists hold the same operators, in any order.
+pub(crate) fn same_operators(left: &[SymmetryOperator], right: &[SymmetryOperator]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .all(|op| right.iter().any(|other| op.is_equivalent(other)))
+}
+
+/// The group generated by the operators, with translations in `[0, 1)`.
+#[cfg(test)]
+pub(crate) fn generate_group(generators: &[&str]) -> Vec<SymmetryOperator> {
+    let generators: Vec<SymmetryOperator> = generators
+        .iter()
+        .map(|generator| generator.parse().unwrap())
+        .collect();
+    let mut group = vec![SymmetryOperator::identity()];
+    let mut i = 0;
+    while i < group.len() {
+        for generator in &generators {
+            let rotation = std::array::from_fn(|r| {
+                std::array::from_fn(|c| {
+                    (0..3)
+                        .map(|k| generator.rotation[r][k] * group[i].rotation[k][c])
+                        .sum()
+                })
+            });
+            let translation = generator.apply(&group[i].translation).map(super::wrap);
+            let product = SymmetryOperator::new(rotation, translation);
+            if !group
+                .iter()
+                .any(|operator| operator.is_equivalent(&product))
+            {
+                group.push(product);
+            }
+        }
+        i += 1;
+    }
+    group
+}
+
+#[cfg(test)]
+mod test {
+    use super::SymmetryOperator;
+
+    #[test]
+    fn parse_symop() {
+        let op: SymmetryOperator = "-x+1/2, y+1/2, -z+1/2".parse().unwrap();
+        let image = op.apply(&[0.1, 0.2, 0.3]);
+        [0.4, 0.7, 0.2]
+            .iter()
+            .zip(image.iter())
+            .for_each(|(expected, x)| assert!((expected - x).abs() < 1e-12));
+        let op: SymmetryOperator = "x-y,x,z+5/6".parse().unwrap();
+        assert_eq!(op.rotation()[0], [1.0, -1.0, 0.0]);
+        assert!((op.translation()[2] - 5.0 / 6.0).abs() < 1e-12);
+        assert!("x,y".parse::<SymmetryOperator>().is_err());
+        assert!("x,y,w".parse::<SymmetryOperator>().is_err());
+        assert!("x,y+,z".parse::<SymmetryOperator>().is_err());
+        assert!("x+1/0,y,z".parse::<SymmetryOperator>().is_err());
+        assert!("x+inf,y,z".parse::<SymmetryOperator>().is_err());
+        assert!("NaNx,y,z".parse::<SymmetryOperator>().is_err());
+        let op: SymmetryOperator = " 1/2 - X , Y+0.5,2*z - 1/3".parse().unwrap();
+        assert_eq!(op.rotation()[2], [0.0, 0.0, 2.0]);
+        assert_eq!(op.to_string(), "-x+1/2,y+1/2,2z-1/3");
+        ["-x+1/2,y+1/2,-z+1/2", "x-y,x,z+5/6", "-y,x-y,z", "x,y,z"]
+            .iter()
+            .for_each(|repr| {
+                let op: SymmetryOperator = repr.parse().unwrap();
+                assert_eq!(&op.to_string(), repr);
+            });
+    }
+}
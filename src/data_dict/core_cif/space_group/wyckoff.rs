@@ -0,0 +1,213 @@
+/// `(letter, multiplicity, representative coordinates)` of a Wyckoff position.
+type WyckoffPosition = (char, usize, &'static str);
+
+/// The Wyckoff positions in alphabetical order for commonly used space
+/// groups in their default setting.
+const WYCKOFF_POSITIONS: [(u8, &[WyckoffPosition]); 21] = [
+    (1, &[('a', 1, "x,y,z")]),
+    (
+        2,
+        &[
+            ('a', 1, "0,0,0"),
+            ('b', 1, "0,0,1/2"),
+            ('c', 1, "0,1/2,0"),
+            ('d', 1, "1/2,0,0"),
+            ('e', 1, "1/2,1/2,0"),
+            ('f', 1, "1/2,0,1/2"),
+            ('g', 1, "0,1/2,1/2"),
+            ('h', 1, "1/2,1/2,1/2"),
+            ('i', 2, "x,y,z"),
+        ],
+    ),
+    (4, &[('a', 2, "x,y,z")]),
+    (
+        5,
+        &[('a', 2, "0,y,0"), ('b', 2, "0,y,1/2"), ('c', 4, "x,y,z")],
+    ),
+    (9, &[('a', 4, "x,y,z")]),
+    (
+        11,
+        &[
+            ('a', 2, "0,0,0"),
+            ('b', 2, "1/2,0,0"),
+            ('c', 2, "0,0,1/2"),
+            ('d', 2, "1/2,0,1/2"),
+            ('e', 2, "x,1/4,z"),
+            ('f', 4, "x,y,z"),
+        ],
+    ),
+    (
+        12,
+        &[
+            ('a', 2, "0,0,0"),
+            ('b', 2, "0,1/2,0"),
+            ('c', 2, "0,0,1/2"),
+            ('d', 2, "0,1/2,1/2"),
+            ('e', 4, "1/4,1/4,0"),
+            ('f', 4, "1/4,1/4,1/2"),
+            ('g', 4, "0,y,0"),
+            ('h', 4, "0,y,1/2"),
+            ('i', 4, "x,0,z"),
+            ('j', 8, "x,y,z"),
+        ],
+    ),
+    (
+        14,
+        &[
+            ('a', 2, "0,0,0"),
+            ('b', 2, "1/2,0,0"),
+            ('c', 2, "0,0,1/2"),
+            ('d', 2, "1/2,0,1/2"),
+            ('e', 4, "x,y,z"),
+        ],
+    ),
+    (
+        15,
+        &[
+            ('a', 4, "0,0,0"),
+            ('b', 4, "0,1/2,0"),
+            ('c', 4, "1/4,1/4,0"),
+            ('d', 4, "1/4,1/4,1/2"),
+            ('e', 4, "0,y,1/4"),
+            ('f', 8, "x,y,z"),
+        ],
+    ),
+    (
+        18,
+        &[('a', 2, "0,0,z"), ('b', 2, "0,1/2,z"), ('c', 4, "x,y,z")],
+    ),
+    (19, &[('a', 4, "x,y,z")]),
+    (33, &[('a', 4, "x,y,z")]),
+    (
+        60,
+        &[
+            ('a', 4, "0,0,0"),
+            ('b', 4, "0,1/2,0"),
+            ('c', 4, "0,y,1/4"),
+            ('d', 8, "x,y,z"),
+        ],
+    ),
+    (
+        61,
+        &[('a', 4, "0,0,0"), ('b', 4, "0,0,1/2"), ('c', 8, "x,y,z")],
+    ),
+    (
+        62,
+        &[
+            ('a', 4, "0,0,0"),
+            ('b', 4, "0,0,1/2"),
+            ('c', 4, "x,1/4,z"),
+            ('d', 8, "x,y,z"),
+        ],
+    ),
+    (
+        139,
+        &[
+            ('a', 2, "0,0,0"),
+            ('b', 2, "0,0,1/2"),
+            ('c', 4, "0,1/2,0"),
+            ('d', 4, "0,1/2,1/4"),
+            ('e', 4, "0,0,z"),
+            ('f', 8, "1/4,1/4,1/4"),
+            ('g', 8, "0,1/2,z"),
+            ('h', 8, "x,x,0"),
+            ('i', 8, "x,0,0"),
+            ('j', 8, "x,1/2,0"),
+            ('k', 16, "x,x+1/2,1/4"),
+            ('l', 16, "x,y,0"),
+            ('m', 16, "x,x,z"),
+            ('n', 16, "0,y,z"),
+            ('o', 32, "x,y,z"),
+        ],
+    ),
+    (
+        148,
+        &[
+            ('a', 3, "0,0,0"),
+            ('b', 3, "0,0,1/2"),
+            ('c', 6, "0,0,z"),
+            ('d', 9, "1/2,0,1/2"),
+            ('e', 9, "1/2,0,0"),
+            ('f', 18, "x,y,z"),
+        ],
+    ),
+    (
+        194,
+        &[
+            ('a', 2, "0,0,0"),
+            ('b', 2, "0,0,1/4"),
+            ('c', 2, "1/3,2/3,1/4"),
+            ('d', 2, "1/3,2/3,3/4"),
+            ('e', 4, "0,0,z"),
+            ('f', 4, "1/3,2/3,z"),
+            ('g', 6, "1/2,0,0"),
+            ('h', 6, "x,2x,1/4"),
+            ('i', 12, "x,0,0"),
+            ('j', 12, "x,y,1/4"),
+            ('k', 12, "x,2x,z"),
+            ('l', 24, "x,y,z"),
+        ],
+    ),
+    (
+        221,
+        &[
+            ('a', 1, "0,0,0"),
+            ('b', 1, "1/2,1/2,1/2"),
+            ('c', 3, "0,1/2,1/2"),
+            ('d', 3, "1/2,0,0"),
+            ('e', 6, "x,0,0"),
+            ('f', 6, "x,1/2,1/2"),
+            ('g', 8, "x,x,x"),
+            ('h', 12, "x,1/2,0"),
+            ('i', 12, "0,y,y"),
+            ('j', 12, "1/2,y,y"),
+            ('k', 24, "0,y,z"),
+            ('l', 24, "1/2,y,z"),
+            ('m', 24, "x,x,z"),
+            ('n', 48, "x,y,z"),
+        ],
+    ),
+    (
+        225,
+        &[
+            ('a', 4, "0,0,0"),
+            ('b', 4, "1/2,1/2,1/2"),
+            ('c', 8, "1/4,1/4,1/4"),
+            ('d', 24, "0,1/4,1/4"),
+            ('e', 24, "x,0,0"),
+            ('f', 32, "x,x,x"),
+            ('g', 48, "x,1/4,1/4"),
+            ('h', 48, "0,y,y"),
+            ('i', 48, "1/2,y,y"),
+            ('j', 96, "0,y,z"),
+            ('k', 96, "x,x,z"),
+            ('l', 192, "x,y,z"),
+        ],
+    ),
+    (
+        229,
+        &[
+            ('a', 2, "0,0,0"),
+            ('b', 6, "0,1/2,1/2"),
+            ('c', 8, "1/4,1/4,1/4"),
+            ('d', 12, "1/4,0,1/2"),
+            ('e', 12, "x,0,0"),
+            ('f', 16, "x,x,x"),
+            ('g', 24, "x,0,1/2"),
+            ('h', 24, "0,y,y"),
+            ('i', 48, "1/4,y,-y+1/2"),
+            ('j', 48, "0,y,z"),
+            ('k', 48, "x,x,z"),
+            ('l', 96, "x,y,z"),
+        ],
+    ),
+];
+
+/// The tabulated Wyckoff positions of the space group, `None` if the group
+/// is not covered.
+pub(crate) fn wyckoff_positions(it_number: u8) -> Option<&'static [WyckoffPosition]> {
+    WYCKOFF_POSITIONS
+        .iter()
+        .find(|(number, _)| *number == it_number)
+        .map(|(_, positions)| *positions)
+}
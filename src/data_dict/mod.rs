@@ -1,21 +1,49 @@
+mod consistency;
 pub mod core_cif;
+mod reading;
+mod structure;
 
-use crate::grammar::{DataItems, LoopColumn, SingleLineData, Tag, Value};
+pub use consistency::{
+    calculated_density, check_cell_volume, check_crystal_density, crystal_density,
+    density_volume_mismatches, DerivedValueCheck,
+};
+pub(crate) use reading::find_column;
+pub use reading::{DataReadError, InvalidEnumeration};
+pub use structure::{AtomicDisplacement, MixedSiteError, Structure, StructureSite};
+
+use crate::grammar::{DataBlock, DataItems, LoopColumn, LoopColumns, SingleLineData, Tag, Value};
 
 pub trait CifTerm {
-    fn tag(&self) -> Tag;
+    fn tag(&self) -> Tag<'static>;
 }
 
 pub trait SingleValueTerm: CifTerm {
-    fn value(&self) -> Value;
-    fn to_single_value_data(&self) -> DataItems {
+    fn value(&self) -> Value<'static>;
+    fn to_single_value_data(&self) -> DataItems<'static> {
         DataItems::SingleValue(SingleLineData::from_tag_value((self.tag(), self.value())))
     }
 }
 
 pub trait LoopValueTerm: CifTerm {
-    fn values(&self) -> Vec<Value>;
-    fn to_loop_column(&self) -> LoopColumn {
+    fn values(&self) -> Vec<Value<'static>>;
+    fn to_loop_column(&self) -> LoopColumn<'static> {
         LoopColumn::new(self.tag(), self.values())
     }
 }
+
+/// A looped category whose rows are represented by typed records.
+pub trait LoopCategory: Sized {
+    /// A tag always present in the loop, used to locate it in a data block.
+    const KEY_TAG: &'static str;
+    fn from_loop_columns(columns: &LoopColumns) -> Result<Vec<Self>, DataReadError>;
+    fn to_loop_columns(records: &[Self]) -> LoopColumns<'static>;
+    fn from_data_block(data_block: &DataBlock) -> Result<Vec<Self>, DataReadError> {
+        data_block[Self::KEY_TAG]
+            .as_multi_values()
+            .ok_or_else(|| DataReadError::MissingTag(Self::KEY_TAG.to_string()))
+            .and_then(Self::from_loop_columns)
+    }
+    fn to_data_items(records: &[Self]) -> DataItems<'static> {
+        DataItems::MultiValues(Self::to_loop_columns(records))
+    }
+}
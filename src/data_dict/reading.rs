@@ -0,0 +1,260 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::grammar::{
+    CharString, DataBlock, DataBlockMember, DataItems, LoopColumn, LoopColumns, Numeric, Value,
+};
+
+/// Errors raised when reading typed dictionary items back from parsed CIF data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataReadError {
+    /// The required tag is absent from the data block or loop.
+    MissingTag(String),
+    /// The value under `tag` cannot be interpreted as the expected type.
+    /// `row` is the row index when the value comes from a loop.
+    InvalidValue {
+        tag: String,
+        row: Option<usize>,
+        found: String,
+    },
+}
+
+impl Display for DataReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataReadError::MissingTag(tag) => write!(f, "missing required tag _{tag}"),
+            DataReadError::InvalidValue { tag, row, found } => match row {
+                Some(row) => write!(f, "invalid value '{found}' of _{tag} at row {row}"),
+                None => write!(f, "invalid value '{found}' of _{tag}"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for DataReadError {}
+
+impl DataReadError {
+    pub(crate) fn invalid(tag: &str, row: Option<usize>, found: &Value) -> Self {
+        Self::InvalidValue {
+            tag: tag.to_string(),
+            row,
+            found: found.to_string(),
+        }
+    }
+}
+
+/// Get a column which must exist in the loop, comparing tags as
+/// [`find_column`] does.
+pub(crate) fn required_column<'a>(
+    columns: &'a LoopColumns<'a>,
+    tag: &str,
+) -> Result<&'a LoopColumn<'a>, DataReadError> {
+    find_column(columns, tag).ok_or_else(|| DataReadError::MissingTag(tag.to_string()))
+}
+
+/// Find a column of the loop, comparing tags case-insensitively as CIF
+/// does, for tags written like `_atom_site_U_iso_or_equiv`.
+pub(crate) fn find_column<'b, 'a>(
+    columns: &'b LoopColumns<'a>,
+    tag: &str,
+) -> Option<&'b LoopColumn<'a>> {
+    columns
+        .columns()
+        .iter()
+        .find(|column| column.tag().as_ref().eq_ignore_ascii_case(tag))
+}
+
+/// Find the loop of the data block holding the column `tag`, comparing tags
+/// as [`find_column`] does.
+pub(crate) fn find_loop<'b, 'a>(
+    data_block: &'b DataBlock<'a>,
+    tag: &str,
+) -> Option<&'b LoopColumns<'a>> {
+    data_block.members().iter().find_map(|member| match member {
+        DataBlockMember::DataItems(DataItems::MultiValues(columns)) => {
+            find_column(columns, tag).map(|_| columns)
+        }
+        _ => None,
+    })
+}
+
+/// Read the `row`-th value of an optional numeric column. Absent columns,
+/// `?` and `.` are all read as `None`.
+pub(crate) fn optional_numeric(
+    column: Option<&LoopColumn>,
+    row: usize,
+) -> Result<Option<Numeric>, DataReadError> {
+    let Some(column) = column else {
+        return Ok(None);
+    };
+    match column.values().get(row) {
+        None | Some(Value::Unknown) | Some(Value::Inapplicable) => Ok(None),
+        Some(Value::Numeric(n)) => Ok(Some(*n)),
+        Some(v) => Err(DataReadError::invalid(column.tag().as_str(), Some(row), v)),
+    }
+}
+
+/// Read the `row`-th value of an optional string column. Numbers are kept in
+/// their textual form, since labels like `1` are parsed as numerics.
+pub(crate) fn optional_char_string(
+    column: Option<&LoopColumn>,
+    row: usize,
+) -> Result<Option<CharString<'static>>, DataReadError> {
+    let Some(column) = column else {
+        return Ok(None);
+    };
+    match column.values().get(row) {
+        None | Some(Value::Unknown) | Some(Value::Inapplicable) => Ok(None),
+        Some(Value::CharString(s)) => Ok(Some(s.clone().into_owned())),
+        Some(Value::Numeric(n)) => Ok(Some(CharString::from(n.to_string()))),
+        Some(v) => Err(DataReadError::invalid(column.tag().as_str(), Some(row), v)),
+    }
+}
+
+/// Read the `row`-th value of a numeric column which must have a value.
+pub(crate) fn required_numeric(column: &LoopColumn, row: usize) -> Result<Numeric, DataReadError> {
+    match column.values().get(row) {
+        Some(Value::Numeric(n)) => Ok(*n),
+        Some(v) => Err(DataReadError::invalid(column.tag().as_str(), Some(row), v)),
+        None => Err(DataReadError::invalid(
+            column.tag().as_str(),
+            Some(row),
+            &Value::Unknown,
+        )),
+    }
+}
+
+/// Read the `row`-th value of a string column which must have a value.
+pub(crate) fn required_text(column: &LoopColumn, row: usize) -> Result<String, DataReadError> {
+    optional_char_string(Some(column), row)?
+        .map(|s| s.as_ref().to_string())
+        .ok_or_else(|| {
+            DataReadError::invalid(
+                column.tag().as_str(),
+                Some(row),
+                column.values().get(row).unwrap_or(&Value::Unknown),
+            )
+        })
+}
+
+/// Read the `row`-th value of an optional column and convert its textual form.
+pub(crate) fn optional_parsed<T: FromStr>(
+    column: Option<&LoopColumn>,
+    row: usize,
+) -> Result<Option<T>, DataReadError> {
+    optional_char_string(column, row)?
+        .map(|s| {
+            s.as_ref().parse::<T>().map_err(|_| {
+                DataReadError::invalid(
+                    column.map(|c| c.tag().as_str()).unwrap_or_default(),
+                    Some(row),
+                    &Value::CharString(s.clone()),
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Values of a tag in the data block, whether given as a single value or as
+/// a loop column. `None` if the tag is absent.
+pub(crate) fn tag_values<'a>(
+    data_block: &'a DataBlock<'a>,
+    tag: &str,
+) -> Option<Vec<&'a Value<'a>>> {
+    let items = &data_block[tag];
+    items
+        .get_single_value_by_tag(tag)
+        .map(|single| vec![single.value()])
+        .or_else(|| {
+            items
+                .as_multi_values()
+                .and_then(|columns| find_column(columns, tag))
+                .map(|column| column.values().iter().collect())
+        })
+}
+
+/// Read a numeric single value which must be present in the data block.
+pub(crate) fn required_single_numeric(
+    data_block: &DataBlock,
+    tag: &str,
+) -> Result<Numeric, DataReadError> {
+    let value = data_block[tag]
+        .get_single_value_by_tag(tag)
+        .map(|single| single.value())
+        .ok_or_else(|| DataReadError::MissingTag(tag.to_string()))?;
+    value
+        .as_numeric()
+        .copied()
+        .ok_or_else(|| DataReadError::invalid(tag, None, value))
+}
+
+/// A single value of the data block, `None` if the tag is absent or the
+/// value is `?` or `.`.
+fn optional_single_value<'a>(data_block: &'a DataBlock<'a>, tag: &str) -> Option<&'a Value<'a>> {
+    data_block[tag]
+        .get_single_value_by_tag(tag)
+        .map(|single| single.value())
+        .filter(|value| !matches!(value, Value::Unknown | Value::Inapplicable))
+}
+
+/// Read an optional numeric single value.
+pub(crate) fn optional_single_numeric(
+    data_block: &DataBlock,
+    tag: &str,
+) -> Result<Option<Numeric>, DataReadError> {
+    optional_single_value(data_block, tag)
+        .map(|value| {
+            value
+                .as_numeric()
+                .copied()
+                .ok_or_else(|| DataReadError::invalid(tag, None, value))
+        })
+        .transpose()
+}
+
+/// Read an optional single value as text, whether written as a string, a
+/// text field or a number.
+pub(crate) fn optional_single_text(data_block: &DataBlock, tag: &str) -> Option<String> {
+    optional_single_value(data_block, tag).map(|value| match value {
+        Value::CharString(s) => s.as_ref().to_string(),
+        Value::TextField(t) => t.raw_string().trim().to_string(),
+        v => v.to_string(),
+    })
+}
+
+/// Read an optional single value and convert its textual form.
+pub(crate) fn optional_single_parsed<T: FromStr>(
+    data_block: &DataBlock,
+    tag: &str,
+) -> Result<Option<T>, DataReadError> {
+    optional_single_text(data_block, tag)
+        .map(|text| {
+            text.parse::<T>().map_err(|_| DataReadError::InvalidValue {
+                tag: tag.to_string(),
+                row: None,
+                found: text,
+            })
+        })
+        .transpose()
+}
+
+/// A text value, written as a text field when no quotes can delimit it.
+pub(crate) fn text_value(text: &str) -> Value<'static> {
+    Value::from_text(text.to_string())
+}
+
+/// An enumerated option that is not in the list of allowed values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEnumeration(pub String);
+
+impl Display for InvalidEnumeration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not one of the allowed options", self.0)
+    }
+}
+
+impl std::error::Error for InvalidEnumeration {}
+
+/// Convert an optional entry to a loop value, writing `?` for missing ones.
+pub(crate) fn value_or_unknown<T: Into<Value<'static>>>(entry: Option<T>) -> Value<'static> {
+    entry.map(Into::into).unwrap_or(Value::Unknown)
+}
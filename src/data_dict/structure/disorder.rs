@@ -0,0 +1,304 @@
+use std::fmt::Display;
+
+use castep_periodic_table::element::ElementSymbol;
+
+use crate::{
+    data_dict::core_cif::{
+        atom_site::DEFAULT_SITE_TOLERANCE,
+        space_group::{periodic_distance, SiteSymmetryFinder},
+    },
+    grammar::Numeric,
+};
+
+use super::{Structure, StructureSite};
+
+/// Amount by which the summed occupancy of merged sites may exceed one, for
+/// occupancies rounded in the file.
+const OCCUPANCY_TOLERANCE: f64 = 0.01;
+
+/// Sites merged by [`Structure::collapse_mixed_sites`] whose occupancies sum
+/// to more than one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedSiteError {
+    labels: Vec<String>,
+    occupancy: f64,
+}
+
+impl MixedSiteError {
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn occupancy(&self) -> f64 {
+        self.occupancy
+    }
+}
+
+impl Display for MixedSiteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sites {} share a position with a summed occupancy of {:.3}",
+            self.labels.join(", "),
+            self.occupancy
+        )
+    }
+}
+
+impl std::error::Error for MixedSiteError {}
+
+impl Structure {
+    /// The structure with a single conformer of every disordered assembly:
+    /// sites of disorder group `group` are kept with the ordered sites, sites
+    /// of other groups are left out. Negative groups, which SHELXL uses for
+    /// disorder about a special position, count as their positive number.
+    pub fn conformer(&self, group: &str) -> Structure {
+        let group = group.trim_start_matches('-');
+        let sites = self
+            .sites
+            .iter()
+            .filter(|site| {
+                site.disorder_group
+                    .as_deref()
+                    .is_none_or(|g| g.trim_start_matches('-') == group)
+            })
+            .cloned()
+            .collect();
+        Structure {
+            sites,
+            ..self.clone()
+        }
+    }
+
+    /// Disorder groups present in the structure, in order of appearance.
+    pub fn disorder_groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = Vec::new();
+        self.sites
+            .iter()
+            .filter_map(|site| site.disorder_group())
+            .for_each(|group| {
+                if !groups.contains(&group) {
+                    groups.push(group);
+                }
+            });
+        groups
+    }
+
+    /// The structure with the sites closer than `tolerance` Å merged, as
+    /// for an atom split over two close positions or a mixed Fe/Co site.
+    /// Each species of a set keeps one site, the one of its largest
+    /// occupancy, with the summed occupancy of the species, so that the
+    /// composition is unchanged. All sites of a set are moved onto the
+    /// position of the set's largest occupancy.
+    ///
+    /// Fails when the occupancies of a set sum to more than one.
+    pub fn collapse_mixed_sites(&self, tolerance: f64) -> Result<Structure, MixedSiteError> {
+        let metric = self.cell.metric_tensor();
+        let positions: Vec<[f64; 3]> = self.sites.iter().map(fract_values).collect();
+        let mut collapsed = vec![false; self.sites.len()];
+        let mut sites = Vec::new();
+        for i in 0..self.sites.len() {
+            if collapsed[i] {
+                continue;
+            }
+            let members: Vec<usize> = (i..self.sites.len())
+                .filter(|&j| {
+                    !collapsed[j]
+                        && periodic_distance(&metric, &positions[i], &positions[j]) < tolerance
+                })
+                .collect();
+            members.iter().for_each(|&j| collapsed[j] = true);
+            let members: Vec<&StructureSite> = members.iter().map(|&j| &self.sites[j]).collect();
+            if members.len() == 1 {
+                sites.push(members[0].clone());
+                continue;
+            }
+            let occupancy: f64 = members.iter().map(|site| site.occupancy.as_f64()).sum();
+            if occupancy > 1.0 + OCCUPANCY_TOLERANCE {
+                return Err(MixedSiteError {
+                    labels: members.iter().map(|site| site.label.clone()).collect(),
+                    occupancy,
+                });
+            }
+            let fract = largest_occupancy(&members).fract;
+            let mut elements: Vec<ElementSymbol> = Vec::new();
+            members.iter().for_each(|site| {
+                if !elements.contains(&site.element) {
+                    elements.push(site.element);
+                }
+            });
+            for element in elements {
+                let species: Vec<&StructureSite> = members
+                    .iter()
+                    .filter(|site| site.element == element)
+                    .copied()
+                    .collect();
+                let representative = largest_occupancy(&species);
+                let occupancy: f64 = species.iter().map(|site| site.occupancy.as_f64()).sum();
+                sites.push(StructureSite {
+                    fract,
+                    occupancy: Numeric::with_decimals(
+                        occupancy.min(1.0),
+                        representative.occupancy.decimal_places(),
+                    ),
+                    disorder_assembly: None,
+                    disorder_group: None,
+                    ..representative.clone()
+                });
+            }
+        }
+        Ok(Structure {
+            sites,
+            ..self.clone()
+        })
+    }
+
+    /// Number of atoms of each element in the unit cell, weighted by the
+    /// occupancies, in order of first appearance. Each site counts as many
+    /// times as it has symmetry images.
+    pub fn composition(&self) -> Vec<(ElementSymbol, f64)> {
+        let finder = SiteSymmetryFinder::new(
+            self.operators.clone(),
+            self.cell.metric_tensor(),
+            DEFAULT_SITE_TOLERANCE,
+        );
+        let mut composition: Vec<(ElementSymbol, f64)> = Vec::new();
+        for site in self.sites.iter() {
+            let count = site.occupancy.as_f64() * finder.orbit(&fract_values(site)).len() as f64;
+            match composition
+                .iter_mut()
+                .find(|(element, _)| *element == site.element)
+            {
+                Some((_, total)) => *total += count,
+                None => composition.push((site.element, count)),
+            }
+        }
+        composition
+    }
+}
+
+fn largest_occupancy<'a>(sites: &[&'a StructureSite]) -> &'a StructureSite {
+    sites
+        .iter()
+        .max_by(|a, b| a.occupancy.as_f64().total_cmp(&b.occupancy.as_f64()))
+        .expect("a set of sites is never empty")
+}
+
+fn fract_values(site: &StructureSite) -> [f64; 3] {
+    site.fract.map(|x| x.as_f64())
+}
+
+#[cfg(test)]
+mod test {
+    use castep_periodic_table::element::ElementSymbol;
+
+    use crate::{
+        data_dict::Structure,
+        grammar::{DataBlock, SyntacticUnit},
+    };
+
+    #[test]
+    fn disorder() {
+        let mut input = r#"data_disorder
+_cell_length_a 5.0
+_cell_length_b 5.0
+_cell_length_c 5.0
+_cell_angle_alpha 90
+_cell_angle_beta 90
+_cell_angle_gamma 90
+loop_
+_space_group_symop_operation_xyz
+x,y,z
+-x,-y,-z
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+_atom_site_occupancy
+_atom_site_disorder_assembly
+_atom_site_disorder_group
+Fe1 Fe 0 0 0 0.7 . .
+Co1 Co 0 0 0 0.3 . .
+O1 O 0.2 0.3 0.4 1 . .
+Cl1 Cl 0.1 0.6 0.3 0.6 A 1
+Cl2 Cl 0.15 0.65 0.35 0.4 A 2
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let structure = Structure::try_from(&block).unwrap();
+        assert_eq!(structure.disorder_groups(), ["1", "2"]);
+        let labels = |structure: &Structure| -> Vec<String> {
+            structure
+                .sites()
+                .iter()
+                .map(|site| site.label().to_string())
+                .collect()
+        };
+        assert_eq!(
+            labels(&structure.conformer("2")),
+            ["Fe1", "Co1", "O1", "Cl2"]
+        );
+
+        // The Fe/Co site keeps both species, the split Cl site becomes one.
+        let collapsed = structure.collapse_mixed_sites(0.5).unwrap();
+        assert_eq!(labels(&collapsed), ["Fe1", "Co1", "O1", "Cl1"]);
+        assert_eq!(collapsed.sites()[0].occupancy().to_string(), "0.7");
+        assert_eq!(collapsed.sites()[1].occupancy().to_string(), "0.3");
+        assert_eq!(collapsed.sites()[3].occupancy().to_string(), "1.0");
+        assert_eq!(collapsed.sites()[3].disorder_group(), None);
+        let total = |structure: &Structure| -> Vec<(ElementSymbol, String)> {
+            structure
+                .composition()
+                .into_iter()
+                .map(|(element, n)| (element, format!("{n:.6}")))
+                .collect()
+        };
+        assert_eq!(total(&collapsed), total(&structure));
+
+        let composition = structure.composition();
+        let count = |element: ElementSymbol| {
+            composition
+                .iter()
+                .find(|(e, _)| *e == element)
+                .map(|(_, n)| *n)
+                .unwrap()
+        };
+        assert!((count(ElementSymbol::Fe) - 0.7).abs() < 1e-6);
+        assert!((count(ElementSymbol::Co) - 0.3).abs() < 1e-6);
+        assert!((count(ElementSymbol::O) - 2.0).abs() < 1e-6);
+        assert!((count(ElementSymbol::Cl) - 2.0).abs() < 1e-6);
+
+        let written = DataBlock::from(&structure);
+        let reread = Structure::try_from(&written).unwrap();
+        assert_eq!(reread.sites(), structure.sites());
+
+        let overfilled = r#"data_overfilled
+_cell_length_a 5.0
+_cell_length_b 5.0
+_cell_length_c 5.0
+_cell_angle_alpha 90
+_cell_angle_beta 90
+_cell_angle_gamma 90
+loop_
+_space_group_symop_operation_xyz
+x,y,z
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+_atom_site_occupancy
+Fe1 Fe 0 0 0 0.7
+Co1 Co 0 0 0 0.5
+"#;
+        let block = DataBlock::parse_str(&mut &overfilled[..]).unwrap();
+        let error = Structure::try_from(&block)
+            .unwrap()
+            .collapse_mixed_sites(0.1)
+            .unwrap_err();
+        assert_eq!(error.labels(), ["Fe1", "Co1"]);
+        assert!((error.occupancy() - 1.2).abs() < 1e-6);
+    }
+}
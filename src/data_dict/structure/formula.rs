@@ -0,0 +1,123 @@
+use crate::{
+    data_dict::{
+        core_cif::{
+            cell::CellTerms,
+            chemical::{ChemicalFormula, ChemicalItem},
+        },
+        SingleValueTerm,
+    },
+    grammar::{DataItems, UnsignedInteger},
+};
+
+use super::Structure;
+
+impl Structure {
+    /// Content of the unit cell from the [`Structure::composition`].
+    pub fn unit_cell_formula(&self) -> ChemicalFormula {
+        ChemicalFormula::new(&self.composition())
+    }
+
+    /// The formula of one formula unit with the number of units in the cell,
+    /// `_cell_formula_units_Z`. `Z` is at most the number of symmetry
+    /// operators, so a molecule on a general position gives one unit per
+    /// image.
+    pub fn formula_units(&self) -> (ChemicalFormula, u32) {
+        self.unit_cell_formula()
+            .formula_units(self.operators.len() as u32)
+    }
+
+    /// `_chemical_formula_sum`, `_chemical_formula_weight` and
+    /// `_cell_formula_units_Z` derived from the atom sites; empty when the
+    /// structure has no sites.
+    pub fn formula_data(&self) -> Vec<DataItems<'static>> {
+        if self.sites.is_empty() {
+            return Vec::new();
+        }
+        let (formula, z) = self.formula_units();
+        vec![
+            ChemicalItem::Formula_weight(formula.formula_weight()).to_single_value_data(),
+            ChemicalItem::Formula_sum(formula).to_single_value_data(),
+            CellTerms::Formula_units_Z(UnsignedInteger(z)).to_single_value_data(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use castep_periodic_table::element::ElementSymbol;
+
+    use crate::{
+        data_dict::{
+            core_cif::chemical::{read_formula_sum, ChemicalFormula},
+            Structure,
+        },
+        grammar::{DataBlock, SyntacticUnit},
+    };
+
+    #[test]
+    fn formula() {
+        let given: ChemicalFormula = "C22 H50 N4 O7".parse().unwrap();
+        assert_eq!(given.count(ElementSymbol::N), 4.0);
+        assert_eq!(given, "C22H50N4O7".parse().unwrap());
+        assert_eq!(given.to_string(), "C22 H50 N4 O7");
+        assert_eq!(
+            "O4 S Cu H10"
+                .parse::<ChemicalFormula>()
+                .unwrap()
+                .to_string(),
+            "Cu H10 O4 S"
+        );
+        assert_eq!(
+            "Si O2 Na".parse::<ChemicalFormula>().unwrap().to_string(),
+            "Na O2 Si"
+        );
+        assert!("C22 x4".parse::<ChemicalFormula>().is_err());
+        assert!("".parse::<ChemicalFormula>().is_err());
+        let (unit, z) = ChemicalFormula::new(&[(ElementSymbol::C, 20.0), (ElementSymbol::H, 16.0)])
+            .formula_units(4);
+        assert_eq!((unit.to_string(), z), ("C5 H4".to_string(), 4));
+        let rounded = ChemicalFormula::new(&[
+            (ElementSymbol::C, 2.997),
+            (ElementSymbol::Fe, 0.996),
+            (ElementSymbol::O, 2.495),
+        ]);
+        assert_eq!(rounded.to_string(), "C3 Fe O2.5");
+
+        let mut input = r#"data_formula
+_chemical_formula_sum 'Co0.3 Fe0.7 O2'
+_cell_length_a 5.0
+_cell_length_b 5.0
+_cell_length_c 5.0
+_cell_angle_alpha 90
+_cell_angle_beta 90
+_cell_angle_gamma 90
+loop_
+_space_group_symop_operation_xyz
+x,y,z
+-x,-y,-z
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+_atom_site_occupancy
+Fe1 Fe 0 0 0 0.7
+Co1 Co 0 0 0 0.3
+O1 O 0.2 0.3 0.4 1
+"#;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let structure = Structure::try_from(&block).unwrap();
+        let (derived, z) = structure.formula_units();
+        assert_eq!(z, 1);
+        assert_eq!(derived.to_string(), "Co0.3 Fe0.7 O2");
+        let given = read_formula_sum(&block).unwrap().unwrap();
+        assert!(derived.matches(&given, 0.01));
+        assert!(!derived.matches(&"Fe O2".parse().unwrap(), 0.01));
+
+        let written = DataBlock::from(&structure);
+        assert!(read_formula_sum(&written)
+            .unwrap()
+            .is_some_and(|written| written.matches(&derived, 1e-6)));
+    }
+}
@@ -0,0 +1,581 @@
+use std::{collections::HashMap, f64::consts::PI};
+
+use castep_periodic_table::element::ElementSymbol;
+
+use crate::{
+    data_dict::{
+        core_cif::{
+            atom_site::{
+                resolve_site_elements, AdpType, AtomSiteLoopItem, TypeSymbol, TypeSymbolCode,
+            },
+            cell::{measured, CellAngle, CellParameters, CellTerms},
+            space_group::{
+                resolve_space_group, space_group_operators, SpaceGroupItem, SpaceGroupLoopItem,
+                SpaceGroupSetting, SymmetryOperator,
+            },
+        },
+        reading::{
+            find_column, optional_char_string, optional_numeric, required_numeric, required_text,
+        },
+        DataReadError, LoopValueTerm, SingleValueTerm,
+    },
+    grammar::{
+        CharString, DataBlock, DataBlockHeading, DataBlockMember, DataItems, LoopColumn,
+        LoopColumns, Numeric, UnsignedInteger,
+    },
+};
+
+mod disorder;
+mod formula;
+
+pub use disorder::MixedSiteError;
+
+/// Order of the anisotropic displacement parameters in
+/// [`AtomicDisplacement::Anisotropic`].
+const ANISO_INDICES: [&str; 6] = ["11", "22", "33", "12", "13", "23"];
+
+/// Atomic displacement parameters of a site, in Å².
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AtomicDisplacement {
+    Isotropic(Numeric),
+    /// `U11, U22, U33, U12, U13, U23` with the equivalent isotropic `U`
+    /// when given.
+    Anisotropic {
+        u_equiv: Option<Numeric>,
+        u: [Numeric; 6],
+    },
+}
+
+/// An atom site of a [`Structure`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureSite {
+    label: String,
+    element: ElementSymbol,
+    oxidation_state: Option<i32>,
+    fract: [Numeric; 3],
+    occupancy: Numeric,
+    displacement: Option<AtomicDisplacement>,
+    disorder_assembly: Option<String>,
+    disorder_group: Option<String>,
+}
+
+impl StructureSite {
+    pub fn new(label: String, element: ElementSymbol, fract: [Numeric; 3]) -> Self {
+        Self {
+            label,
+            element,
+            oxidation_state: None,
+            fract,
+            occupancy: Numeric::from(1),
+            displacement: None,
+            disorder_assembly: None,
+            disorder_group: None,
+        }
+    }
+
+    pub fn with_oxidation_state(mut self, oxidation_state: i32) -> Self {
+        self.oxidation_state = Some(oxidation_state);
+        self
+    }
+
+    pub fn with_occupancy(mut self, occupancy: Numeric) -> Self {
+        self.occupancy = occupancy;
+        self
+    }
+
+    pub fn with_displacement(mut self, displacement: AtomicDisplacement) -> Self {
+        self.displacement = Some(displacement);
+        self
+    }
+
+    /// Place the site in a disorder group, as `1` or `2` for the two
+    /// conformers of a disordered assembly like `A`.
+    pub fn with_disorder(mut self, assembly: Option<String>, group: String) -> Self {
+        self.disorder_assembly = assembly;
+        self.disorder_group = Some(group);
+        self
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn element(&self) -> ElementSymbol {
+        self.element
+    }
+
+    pub fn oxidation_state(&self) -> Option<i32> {
+        self.oxidation_state
+    }
+
+    pub fn fract(&self) -> [Numeric; 3] {
+        self.fract
+    }
+
+    pub fn occupancy(&self) -> Numeric {
+        self.occupancy
+    }
+
+    pub fn displacement(&self) -> Option<AtomicDisplacement> {
+        self.displacement
+    }
+
+    pub fn disorder_assembly(&self) -> Option<&str> {
+        self.disorder_assembly.as_deref()
+    }
+
+    pub fn disorder_group(&self) -> Option<&str> {
+        self.disorder_group.as_deref()
+    }
+}
+
+/// A crystal structure read from a data block: the cell with its standard
+/// uncertainties, the space group, the symmetry operators and the atom
+/// sites.
+///
+/// Unlike using [`DataBlock`] directly as a model, reading never panics:
+/// every missing tag or unreadable value is reported as a
+/// [`DataReadError`] naming the tag and row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    name: String,
+    cell: CellParameters,
+    space_group: Option<SpaceGroupSetting>,
+    operators: Vec<SymmetryOperator>,
+    sites: Vec<StructureSite>,
+}
+
+impl Structure {
+    pub fn new(
+        name: String,
+        cell: CellParameters,
+        space_group: Option<SpaceGroupSetting>,
+        operators: Vec<SymmetryOperator>,
+        sites: Vec<StructureSite>,
+    ) -> Self {
+        Self {
+            name,
+            cell,
+            space_group,
+            operators,
+            sites,
+        }
+    }
+
+    /// Name of the data block.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn cell(&self) -> &CellParameters {
+        &self.cell
+    }
+
+    /// `None` when the data block names no space group.
+    pub fn space_group(&self) -> Option<SpaceGroupSetting> {
+        self.space_group
+    }
+
+    pub fn operators(&self) -> &[SymmetryOperator] {
+        &self.operators
+    }
+
+    pub fn sites(&self) -> &[StructureSite] {
+        &self.sites
+    }
+}
+
+impl TryFrom<&DataBlock<'_>> for Structure {
+    type Error = DataReadError;
+
+    fn try_from(data_block: &DataBlock) -> Result<Self, Self::Error> {
+        let cell = CellParameters::from_data_block(data_block)?;
+        let space_group = match resolve_space_group(data_block) {
+            Ok(space_group) => Some(space_group.setting()),
+            Err(DataReadError::MissingTag(_)) => None,
+            Err(e) => return Err(e),
+        };
+        let operators = space_group_operators(data_block)?;
+        let sites = read_sites(data_block)?;
+        Ok(Self {
+            name: data_block.heading().to_string(),
+            cell,
+            space_group,
+            operators,
+            sites,
+        })
+    }
+}
+
+fn read_sites(data_block: &DataBlock) -> Result<Vec<StructureSite>, DataReadError> {
+    let columns = data_block["atom_site_label"]
+        .as_multi_values()
+        .ok_or_else(|| DataReadError::MissingTag("atom_site_label".to_string()))?;
+    let column = |tag: &str| find_column(columns, tag);
+    let required =
+        |tag: &str| column(tag).ok_or_else(|| DataReadError::MissingTag(tag.to_string()));
+    let label = required("atom_site_label")?;
+    let fract = [
+        required("atom_site_fract_x")?,
+        required("atom_site_fract_y")?,
+        required("atom_site_fract_z")?,
+    ];
+    let occupancy = column("atom_site_occupancy");
+    let disorder_assembly = column("atom_site_disorder_assembly");
+    let disorder_group = column("atom_site_disorder_group");
+    let u_iso = column("atom_site_U_iso_or_equiv");
+    let b_iso = column("atom_site_B_iso_or_equiv");
+    let elements = resolve_site_elements(data_block)?;
+    let mut aniso = read_aniso(data_block)?;
+    let sites = (0..label.values().len())
+        .map(|row| {
+            let label_text = required_text(label, row)?;
+            let u_equiv = match optional_numeric(u_iso, row)? {
+                Some(u) => Some(u),
+                None => optional_numeric(b_iso, row)?.map(b_to_u),
+            };
+            let displacement = match aniso.remove(&label_text) {
+                Some(u) => Some(AtomicDisplacement::Anisotropic { u_equiv, u }),
+                None => u_equiv.map(AtomicDisplacement::Isotropic),
+            };
+            Ok(StructureSite {
+                label: label_text,
+                element: elements[row].element(),
+                oxidation_state: elements[row].oxidation_state(),
+                fract: [
+                    required_numeric(fract[0], row)?,
+                    required_numeric(fract[1], row)?,
+                    required_numeric(fract[2], row)?,
+                ],
+                occupancy: optional_numeric(occupancy, row)?.unwrap_or(Numeric::from(1)),
+                displacement,
+                disorder_assembly: optional_char_string(disorder_assembly, row)?
+                    .map(|code| code.as_ref().to_string()),
+                disorder_group: optional_char_string(disorder_group, row)?
+                    .map(|code| code.as_ref().to_string()),
+            })
+        })
+        .collect::<Result<Vec<StructureSite>, DataReadError>>()?;
+    // Anisotropic parameters left over refer to no atom site.
+    if let Some(label) = aniso.keys().min() {
+        return Err(DataReadError::InvalidValue {
+            tag: "atom_site_aniso_label".to_string(),
+            row: None,
+            found: label.clone(),
+        });
+    }
+    Ok(sites)
+}
+
+/// The `U` parameters of the `_atom_site_aniso_` loop by label, converted
+/// from `B` when only those are given.
+fn read_aniso(data_block: &DataBlock) -> Result<HashMap<String, [Numeric; 6]>, DataReadError> {
+    let Some(columns) = data_block["atom_site_aniso_label"].as_multi_values() else {
+        return Ok(HashMap::new());
+    };
+    let label = find_column(columns, "atom_site_aniso_label")
+        .ok_or_else(|| DataReadError::MissingTag("atom_site_aniso_label".to_string()))?;
+    let (prefix, from_b) = if find_column(columns, "atom_site_aniso_U_11").is_some() {
+        ("atom_site_aniso_U_", false)
+    } else {
+        ("atom_site_aniso_B_", true)
+    };
+    let u_columns = ANISO_INDICES
+        .iter()
+        .map(|ij| {
+            let tag = format!("{prefix}{ij}");
+            find_column(columns, &tag).ok_or(DataReadError::MissingTag(tag))
+        })
+        .collect::<Result<Vec<&LoopColumn>, DataReadError>>()?;
+    let mut aniso = HashMap::new();
+    for row in 0..label.values().len() {
+        let mut u = [Numeric::from(0); 6];
+        for (k, column) in u_columns.iter().enumerate() {
+            let value = required_numeric(column, row)?;
+            u[k] = if from_b { b_to_u(value) } else { value };
+        }
+        if aniso.insert(required_text(label, row)?, u).is_some() {
+            return Err(DataReadError::invalid(
+                "atom_site_aniso_label",
+                Some(row),
+                &label.values()[row],
+            ));
+        }
+    }
+    Ok(aniso)
+}
+
+/// `U = B / 8π²`, keeping the precision of `B`.
+fn b_to_u(b: Numeric) -> Numeric {
+    let scale = 8.0 * PI * PI;
+    measured(
+        b.as_f64() / scale,
+        b.su_value().unwrap_or(0.0) / scale,
+        b.decimal_places() + 2,
+    )
+}
+
+impl From<&Structure> for DataBlock<'static> {
+    fn from(structure: &Structure) -> Self {
+        let [a, b, c] = structure.cell.lengths();
+        let [alpha, beta, gamma] = structure.cell.angles().map(CellAngle::from_numeric);
+        let mut items: Vec<DataItems> = [
+            CellTerms::Length_a(a),
+            CellTerms::Length_b(b),
+            CellTerms::Length_c(c),
+            CellTerms::Angle_alpha(alpha),
+            CellTerms::Angle_beta(beta),
+            CellTerms::Angle_gamma(gamma),
+        ]
+        .iter()
+        .map(|term| term.to_single_value_data())
+        .collect();
+        items.extend(structure.formula_data());
+        if let Some(setting) = structure.space_group {
+            items.extend([
+                SpaceGroupItem::IT_number(setting.it_number()).to_single_value_data(),
+                SpaceGroupItem::Name_H_M_alt(setting.hermann_mauguin().to_string())
+                    .to_single_value_data(),
+                SpaceGroupItem::Name_Hall(setting.hall().to_string()).to_single_value_data(),
+            ]);
+        }
+        items.push(DataItems::MultiValues(LoopColumns::new(vec![
+            SpaceGroupLoopItem::Symop_id(
+                (1..=structure.operators.len() as u32)
+                    .map(UnsignedInteger)
+                    .collect(),
+            )
+            .to_loop_column(),
+            SpaceGroupLoopItem::Symop_operation_xyz(
+                structure
+                    .operators
+                    .iter()
+                    .map(|op| CharString::from(op.to_string()))
+                    .collect(),
+            )
+            .to_loop_column(),
+        ])));
+        items.push(DataItems::MultiValues(atom_site_columns(&structure.sites)));
+        if let Some(aniso) = aniso_columns(&structure.sites) {
+            items.push(DataItems::MultiValues(aniso));
+        }
+        DataBlock::from_heading_members((
+            DataBlockHeading::new(structure.name.clone()),
+            items.into_iter().map(DataBlockMember::DataItems).collect(),
+        ))
+    }
+}
+
+fn atom_site_columns(sites: &[StructureSite]) -> LoopColumns<'static> {
+    let [x, y, z] = [0, 1, 2].map(|k| sites.iter().map(|site| site.fract[k]).collect());
+    let columns = [
+        AtomSiteLoopItem::Label(
+            sites
+                .iter()
+                .map(|site| CharString::from(site.label.as_str()))
+                .collect(),
+        ),
+        AtomSiteLoopItem::Type_symbol(
+            sites
+                .iter()
+                .map(|site| {
+                    TypeSymbol::new(
+                        TypeSymbolCode::ElementSymbol(site.element),
+                        site.oxidation_state,
+                    )
+                })
+                .collect(),
+        ),
+        AtomSiteLoopItem::Fract_x(x),
+        AtomSiteLoopItem::Fract_y(y),
+        AtomSiteLoopItem::Fract_z(z),
+        AtomSiteLoopItem::U_iso_or_equiv(
+            sites
+                .iter()
+                .map(|site| match site.displacement {
+                    Some(AtomicDisplacement::Isotropic(u)) => Some(u),
+                    Some(AtomicDisplacement::Anisotropic { u_equiv, .. }) => u_equiv,
+                    None => None,
+                })
+                .collect(),
+        ),
+        AtomSiteLoopItem::Adp_type(
+            sites
+                .iter()
+                .map(|site| match site.displacement {
+                    Some(AtomicDisplacement::Isotropic(_)) => Some(AdpType::Uiso),
+                    Some(AtomicDisplacement::Anisotropic { .. }) => Some(AdpType::Uani),
+                    None => None,
+                })
+                .collect(),
+        ),
+        AtomSiteLoopItem::Occupancy(sites.iter().map(|site| site.occupancy).collect()),
+    ]
+    .map(|item| item.to_loop_column());
+    let mut columns = columns.to_vec();
+    // The disorder columns are only written for disordered structures.
+    if sites.iter().any(|site| site.disorder_group.is_some()) {
+        columns.extend(
+            [
+                AtomSiteLoopItem::Disorder_assembly(
+                    sites
+                        .iter()
+                        .map(|site| site.disorder_assembly.clone())
+                        .collect(),
+                ),
+                AtomSiteLoopItem::Disorder_group(
+                    sites
+                        .iter()
+                        .map(|site| site.disorder_group.clone())
+                        .collect(),
+                ),
+            ]
+            .map(|item| item.to_loop_column()),
+        );
+    }
+    LoopColumns::new(columns)
+}
+
+/// The `_atom_site_aniso_` loop, `None` when no site is anisotropic.
+fn aniso_columns(sites: &[StructureSite]) -> Option<LoopColumns<'static>> {
+    let (labels, u): (Vec<CharString>, Vec<[Numeric; 6]>) = sites
+        .iter()
+        .filter_map(|site| match site.displacement {
+            Some(AtomicDisplacement::Anisotropic { u, .. }) => {
+                Some((CharString::from(site.label.as_str()), u))
+            }
+            _ => None,
+        })
+        .unzip();
+    if labels.is_empty() {
+        return None;
+    }
+    let component = |k: usize| u.iter().map(|u| u[k]).collect::<Vec<Numeric>>();
+    let columns = [
+        AtomSiteLoopItem::Aniso_label(labels),
+        AtomSiteLoopItem::Aniso_U_11(component(0)),
+        AtomSiteLoopItem::Aniso_U_22(component(1)),
+        AtomSiteLoopItem::Aniso_U_33(component(2)),
+        AtomSiteLoopItem::Aniso_U_12(component(3)),
+        AtomSiteLoopItem::Aniso_U_13(component(4)),
+        AtomSiteLoopItem::Aniso_U_23(component(5)),
+    ]
+    .map(|item| item.to_loop_column());
+    Some(LoopColumns::new(columns.to_vec()))
+}
+
+#[cfg(test)]
+mod test {
+    use castep_periodic_table::element::ElementSymbol;
+
+    use crate::{
+        data_dict::DataReadError,
+        grammar::{DataBlock, SyntacticUnit},
+    };
+
+    use super::{AtomicDisplacement, Structure};
+
+    const INPUT: &str = r#"data_test
+_cell_length_a 5.0123(4)
+_cell_length_b 6.0
+_cell_length_c 7.0
+_cell_angle_alpha 90
+_cell_angle_beta 100.12(3)
+_cell_angle_gamma 90
+_symmetry_space_group_name_H-M 'P 21/c'
+loop_
+_symmetry_equiv_pos_as_xyz
+x,y,z
+-x,y+1/2,-z+1/2
+-x,-y,-z
+x,-y+1/2,z+1/2
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_fract_y
+_atom_site_fract_z
+_atom_site_U_iso_or_equiv
+_atom_site_occupancy
+Fe1 Fe2+ 0 0 0 0.0123(4) 1
+Cl1 Cl 0.1234(5) 0.25 0.8 0.0348(12) 0.5
+O1 ? 0.3 0.4 0.5 ? ?
+loop_
+_atom_site_aniso_label
+_atom_site_aniso_U_11
+_atom_site_aniso_U_22
+_atom_site_aniso_U_33
+_atom_site_aniso_U_12
+_atom_site_aniso_U_13
+_atom_site_aniso_U_23
+Fe1 0.0121(5) 0.0130(5) 0.0118(5) 0.0002(4) 0.0031(4) -0.0001(4)
+"#;
+
+    #[test]
+    fn structure_round_trip() {
+        let mut input = INPUT;
+        let block = DataBlock::parse_str(&mut input).unwrap();
+        let structure = Structure::try_from(&block).unwrap();
+        assert_eq!(structure.operators().len(), 4);
+        assert_eq!(structure.cell().lengths()[0].to_string(), "5.0123(4)");
+        let elements: Vec<ElementSymbol> = structure.sites().iter().map(|s| s.element()).collect();
+        assert_eq!(
+            elements,
+            [ElementSymbol::Fe, ElementSymbol::Cl, ElementSymbol::O]
+        );
+        let fe = &structure.sites()[0];
+        assert!(matches!(
+            fe.displacement(),
+            Some(AtomicDisplacement::Anisotropic {
+                u_equiv: Some(_),
+                ..
+            })
+        ));
+        assert_eq!(fe.oxidation_state(), Some(2));
+        assert_eq!(structure.sites()[1].occupancy().to_string(), "0.5");
+        assert_eq!(structure.sites()[2].displacement(), None);
+
+        let written = DataBlock::from(&structure);
+        let reread = Structure::try_from(&written).unwrap();
+        assert_eq!(reread.sites(), structure.sites());
+        assert_eq!(reread.cell(), structure.cell());
+        assert_eq!(reread.operators(), structure.operators());
+        assert_eq!(reread.space_group(), structure.space_group());
+    }
+
+    #[test]
+    fn structure_errors() {
+        let unknown_coordinate = INPUT.replace("O1 ? 0.3", "O1 ? ?");
+        let block = DataBlock::parse_str(&mut unknown_coordinate.as_str()).unwrap();
+        assert_eq!(
+            Structure::try_from(&block),
+            Err(DataReadError::InvalidValue {
+                tag: "atom_site_fract_x".to_string(),
+                row: Some(2),
+                found: "?".to_string(),
+            })
+        );
+        let no_cell = INPUT.replace("_cell_length_c 7.0\n", "");
+        let block = DataBlock::parse_str(&mut no_cell.as_str()).unwrap();
+        assert_eq!(
+            Structure::try_from(&block),
+            Err(DataReadError::MissingTag("cell_length_c".to_string()))
+        );
+        let stray_aniso = INPUT.replace("\nFe1 0.0121", "\nFe9 0.0121");
+        let block = DataBlock::parse_str(&mut stray_aniso.as_str()).unwrap();
+        assert!(matches!(
+            Structure::try_from(&block),
+            Err(DataReadError::InvalidValue { tag, .. }) if tag == "atom_site_aniso_label"
+        ));
+        let duplicate_aniso = format!("{INPUT}Fe1 0.012 0.013 0.012 0 0 0\n");
+        let block = DataBlock::parse_str(&mut duplicate_aniso.as_str()).unwrap();
+        assert_eq!(
+            Structure::try_from(&block),
+            Err(DataReadError::InvalidValue {
+                tag: "atom_site_aniso_label".to_string(),
+                row: Some(1),
+                found: "Fe1".to_string(),
+            })
+        );
+    }
+}
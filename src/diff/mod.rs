@@ -0,0 +1,713 @@
+//! Semantic differences between two CIF documents.
+//!
+//! Blocks are matched by name and single items by tag, ignoring order and
+//! the case of tags. Loops are matched by their first tag, and their rows
+//! by the key columns of the category, like `_atom_site_label` or the
+//! labels and symmetry codes of a `_geom_bond_` row, falling back to the
+//! row number when the loop has no key. Rows sharing a key are matched in
+//! order and the key is reported as duplicate. Numbers are compared by value, so
+//! `1.50` equals `1.5`, within an absolute tolerance and optionally within
+//! a multiple of their combined standard uncertainty.
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    data_dict::find_column, CifDocument, DataBlock, DataBlockMember, DataItems, LoopColumn,
+    LoopColumns, Value,
+};
+
+/// How values are compared by [`diff_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DiffOptions {
+    tolerance: f64,
+    su_multiple: Option<f64>,
+}
+
+impl DiffOptions {
+    /// Numbers differing by at most `tolerance` are taken as equal.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Numbers differing by at most `su_multiple` times their combined su
+    /// are taken as equal.
+    pub fn with_su_multiple(mut self, su_multiple: f64) -> Self {
+        self.su_multiple = Some(su_multiple);
+        self
+    }
+
+    pub(crate) fn same_value(&self, old: &Value, new: &Value) -> bool {
+        match (old.as_numeric(), new.as_numeric()) {
+            (Some(a), Some(b)) => {
+                let difference = (a.as_f64() - b.as_f64()).abs();
+                let su_allowance = self.su_multiple.map_or(0.0, |multiple| {
+                    multiple * combined_su(old, new).unwrap_or(0.0)
+                });
+                difference <= self.tolerance.max(su_allowance)
+            }
+            _ => old.text() == new.text(),
+        }
+    }
+}
+
+/// `sqrt(su_old² + su_new²)`, `None` when neither value has an su.
+fn combined_su(old: &Value, new: &Value) -> Option<f64> {
+    let su = |value: &Value| value.as_numeric().and_then(|n| n.su_value());
+    match (su(old), su(new)) {
+        (None, None) => None,
+        (a, b) => Some((a.unwrap_or(0.0).powi(2) + b.unwrap_or(0.0).powi(2)).sqrt()),
+    }
+}
+
+/// One difference between two documents. Tags are without the leading
+/// underscore; `row` is the key of a loop row, like `Fe1` or `C1 C2 .`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    BlockAdded {
+        block: String,
+    },
+    BlockRemoved {
+        block: String,
+    },
+    TagAdded {
+        block: String,
+        tag: String,
+        value: String,
+    },
+    TagRemoved {
+        block: String,
+        tag: String,
+        value: String,
+    },
+    RowAdded {
+        block: String,
+        key_tag: String,
+        row: String,
+    },
+    RowRemoved {
+        block: String,
+        key_tag: String,
+        row: String,
+    },
+    /// A key of several rows of the loop in either document.
+    DuplicateKey {
+        block: String,
+        key_tag: String,
+        row: String,
+    },
+    /// `sigma` is the difference in units of the combined su of the two
+    /// numbers, when they have one.
+    ValueChanged {
+        block: String,
+        tag: String,
+        row: Option<String>,
+        old: String,
+        new: String,
+        sigma: Option<f64>,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::BlockAdded { block } => write!(f, "+ data_{block}"),
+            Change::BlockRemoved { block } => write!(f, "- data_{block}"),
+            Change::TagAdded { block, tag, value } => write!(f, "+ {block} _{tag} {value}"),
+            Change::TagRemoved { block, tag, value } => write!(f, "- {block} _{tag} {value}"),
+            Change::RowAdded {
+                block,
+                key_tag,
+                row,
+            } => write!(f, "+ {block} _{key_tag} [{row}]"),
+            Change::RowRemoved {
+                block,
+                key_tag,
+                row,
+            } => write!(f, "- {block} _{key_tag} [{row}]"),
+            Change::DuplicateKey {
+                block,
+                key_tag,
+                row,
+            } => write!(f, "! {block} _{key_tag} [{row}] duplicate key"),
+            Change::ValueChanged {
+                block,
+                tag,
+                row,
+                old,
+                new,
+                sigma,
+            } => {
+                write!(f, "~ {block} _{tag}")?;
+                if let Some(row) = row {
+                    write!(f, " [{row}]")?;
+                }
+                write!(f, " {old} -> {new}")?;
+                match sigma {
+                    Some(sigma) => write!(f, " ({sigma:.1} su)"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// The changes from one document to another, in the order of the blocks
+/// and items of the old document followed by additions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CifDiff {
+    changes: Vec<Change>,
+}
+
+impl CifDiff {
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The changes as a JSON array of objects with a `change` kind and the
+    /// fields of the [`Change`].
+    pub fn to_json(&self) -> String {
+        let changes: Vec<String> = self.changes.iter().map(change_json).collect();
+        match changes.is_empty() {
+            true => "[]".to_string(),
+            false => format!("[\n  {}\n]", changes.join(",\n  ")),
+        }
+    }
+}
+
+/// One change per line, `+` for additions, `-` for removals and `~` for
+/// changed values.
+impl Display for CifDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.changes
+            .iter()
+            .try_for_each(|change| writeln!(f, "{change}"))
+    }
+}
+
+/// `s` as a JSON string literal, quoted and escaped.
+pub fn json_string(s: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn change_json(change: &Change) -> String {
+    let mut sigma = None;
+    let fields: Vec<(&str, &str)> = match change {
+        Change::BlockAdded { block } => vec![("change", "block_added"), ("block", block)],
+        Change::BlockRemoved { block } => vec![("change", "block_removed"), ("block", block)],
+        Change::TagAdded { block, tag, value } => vec![
+            ("change", "tag_added"),
+            ("block", block),
+            ("tag", tag),
+            ("value", value),
+        ],
+        Change::TagRemoved { block, tag, value } => vec![
+            ("change", "tag_removed"),
+            ("block", block),
+            ("tag", tag),
+            ("value", value),
+        ],
+        Change::RowAdded {
+            block,
+            key_tag,
+            row,
+        } => vec![
+            ("change", "row_added"),
+            ("block", block),
+            ("key_tag", key_tag),
+            ("row", row),
+        ],
+        Change::RowRemoved {
+            block,
+            key_tag,
+            row,
+        } => vec![
+            ("change", "row_removed"),
+            ("block", block),
+            ("key_tag", key_tag),
+            ("row", row),
+        ],
+        Change::DuplicateKey {
+            block,
+            key_tag,
+            row,
+        } => vec![
+            ("change", "duplicate_key"),
+            ("block", block),
+            ("key_tag", key_tag),
+            ("row", row),
+        ],
+        Change::ValueChanged {
+            block,
+            tag,
+            row,
+            old,
+            new,
+            sigma: su_units,
+        } => {
+            sigma = *su_units;
+            let mut fields = vec![("change", "value_changed"), ("block", block), ("tag", tag)];
+            if let Some(row) = row {
+                fields.push(("row", row));
+            }
+            fields.extend([("old", old.as_str()), ("new", new.as_str())]);
+            fields
+        }
+    };
+    let mut json: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("\"{name}\": {}", json_string(value)))
+        .collect();
+    if let Some(sigma) = sigma {
+        json.push(format!("\"sigma\": {sigma:.2}"));
+    }
+    format!("{{{}}}", json.join(", "))
+}
+
+/// Differences between two documents with exact comparison of numbers.
+pub fn diff(old: &CifDocument, new: &CifDocument) -> CifDiff {
+    diff_with_options(old, new, &DiffOptions::default())
+}
+
+/// Differences between two documents, numbers compared as in `options`.
+pub fn diff_with_options(old: &CifDocument, new: &CifDocument, options: &DiffOptions) -> CifDiff {
+    let old_blocks = old.data_blocks().map_or(&[][..], |b| b.as_slice());
+    let new_blocks = new.data_blocks().map_or(&[][..], |b| b.as_slice());
+    let mut changes = Vec::new();
+    for old_block in old_blocks.iter() {
+        match new_blocks
+            .iter()
+            .find(|block| block.heading() == old_block.heading())
+        {
+            Some(new_block) => changes.extend(diff_blocks(old_block, new_block, options)),
+            None => changes.push(Change::BlockRemoved {
+                block: old_block.heading().to_string(),
+            }),
+        }
+    }
+    changes.extend(
+        new_blocks
+            .iter()
+            .filter(|block| {
+                old_blocks
+                    .iter()
+                    .all(|old| old.heading() != block.heading())
+            })
+            .map(|block| Change::BlockAdded {
+                block: block.heading().to_string(),
+            }),
+    );
+    CifDiff { changes }
+}
+
+/// Single items and loops of a data block.
+type BlockItems<'a> = (Vec<(&'a str, &'a Value<'a>)>, Vec<&'a LoopColumns<'a>>);
+
+fn items<'a>(block: &'a DataBlock<'a>) -> BlockItems<'a> {
+    let mut singles = Vec::new();
+    let mut loops = Vec::new();
+    for member in block.members() {
+        match member {
+            DataBlockMember::DataItems(DataItems::SingleValue(item)) => {
+                singles.push((item.tag().as_str(), item.value()))
+            }
+            DataBlockMember::DataItems(DataItems::MultiValues(columns)) => loops.push(columns),
+            DataBlockMember::SaveFrame(_) => {}
+        }
+    }
+    (singles, loops)
+}
+
+fn diff_blocks(old: &DataBlock, new: &DataBlock, options: &DiffOptions) -> Vec<Change> {
+    let block = old.heading().to_string();
+    let (old_singles, old_loops) = items(old);
+    let (new_singles, new_loops) = items(new);
+    let mut changes = Vec::new();
+    for (tag, old_value) in old_singles.iter() {
+        match new_singles
+            .iter()
+            .find(|(t, _)| t.eq_ignore_ascii_case(tag))
+        {
+            Some((_, new_value)) => {
+                if let Some(change) = value_change(&block, tag, None, old_value, new_value, options)
+                {
+                    changes.push(change);
+                }
+            }
+            None => changes.push(Change::TagRemoved {
+                block: block.clone(),
+                tag: tag.to_string(),
+                value: old_value.to_string(),
+            }),
+        }
+    }
+    changes.extend(
+        new_singles
+            .iter()
+            .filter(|(tag, _)| !old_singles.iter().any(|(t, _)| t.eq_ignore_ascii_case(tag)))
+            .map(|(tag, value)| Change::TagAdded {
+                block: block.clone(),
+                tag: tag.to_string(),
+                value: value.to_string(),
+            }),
+    );
+    let first_tag = |columns: &LoopColumns| {
+        columns
+            .columns()
+            .first()
+            .map(|column| column.tag().as_str().to_string())
+            .unwrap_or_default()
+    };
+    for old_loop in old_loops.iter() {
+        let tag = first_tag(old_loop);
+        match new_loops
+            .iter()
+            .find(|columns| find_column(columns, &tag).is_some())
+        {
+            Some(new_loop) => changes.extend(diff_loops(&block, old_loop, new_loop, options)),
+            None => changes.extend(old_loop.columns().iter().map(|column| Change::TagRemoved {
+                block: block.clone(),
+                tag: column.tag().as_str().to_string(),
+                value: format!("[{} values]", column.values().len()),
+            })),
+        }
+    }
+    for new_loop in new_loops.iter() {
+        let tag = first_tag(new_loop);
+        if old_loops
+            .iter()
+            .all(|columns| find_column(columns, &tag).is_none())
+        {
+            changes.extend(new_loop.columns().iter().map(|column| Change::TagAdded {
+                block: block.clone(),
+                tag: column.tag().as_str().to_string(),
+                value: format!("[{} values]", column.values().len()),
+            }));
+        }
+    }
+    changes
+}
+
+fn value_change(
+    block: &str,
+    tag: &str,
+    row: Option<&str>,
+    old: &Value,
+    new: &Value,
+    options: &DiffOptions,
+) -> Option<Change> {
+    if options.same_value(old, new) {
+        return None;
+    }
+    let sigma = match (old.as_numeric(), new.as_numeric()) {
+        (Some(a), Some(b)) => combined_su(old, new)
+            .filter(|su| *su > 0.0)
+            .map(|su| (a.as_f64() - b.as_f64()).abs() / su),
+        _ => None,
+    };
+    Some(Change::ValueChanged {
+        block: block.to_string(),
+        tag: tag.to_string(),
+        row: row.map(str::to_string),
+        old: old.to_string(),
+        new: new.to_string(),
+        sigma,
+    })
+}
+
+/// Columns identifying the rows of a loop: those named `..._label`,
+/// `..._label_N`, `..._id` or `..._site_symmetry_N`, or `..._symbol` when
+/// there are none, as `_atom_type_symbol`.
+pub(crate) fn key_tags(columns: &LoopColumns) -> Vec<String> {
+    let tags: Vec<String> = columns
+        .columns()
+        .iter()
+        .map(|column| column.tag().as_str().to_lowercase())
+        .collect();
+    let is_numbered = |tag: &str, stem: &str| {
+        tag.rsplit_once('_').is_some_and(|(head, n)| {
+            head.ends_with(stem) && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+        })
+    };
+    let keys: Vec<String> = tags
+        .iter()
+        .filter(|tag| {
+            tag.ends_with("_label")
+                || tag.ends_with("_id")
+                || is_numbered(tag, "_label")
+                || is_numbered(tag, "_site_symmetry")
+        })
+        .cloned()
+        .collect();
+    match keys.iter().any(|tag| !tag.contains("site_symmetry")) {
+        true => keys,
+        false => tags
+            .iter()
+            .filter(|tag| tag.ends_with("_symbol"))
+            .take(1)
+            .cloned()
+            .collect(),
+    }
+}
+
+/// The key of each row, from the key columns or the row number.
+pub(crate) fn row_keys(columns: &LoopColumns, keys: &[String]) -> Vec<String> {
+    let rows = columns
+        .columns()
+        .first()
+        .map_or(0, |column| column.values().len());
+    let key_columns: Vec<&LoopColumn> = keys
+        .iter()
+        .filter_map(|tag| find_column(columns, tag))
+        .collect();
+    (0..rows)
+        .map(|row| match key_columns.is_empty() {
+            true => (row + 1).to_string(),
+            false => key_columns
+                .iter()
+                .map(|column| {
+                    column
+                        .values()
+                        .get(row)
+                        .map(Value::text)
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+        })
+        .collect()
+}
+
+/// The rows of a loop by key and by occurrence of the key, so rows sharing a
+/// key are matched in order.
+pub(crate) struct RowIndex<'k> {
+    keys: &'k [String],
+    rows: HashMap<(&'k str, usize), usize>,
+    occurrences: Vec<usize>,
+}
+
+impl<'k> RowIndex<'k> {
+    pub(crate) fn new(keys: &'k [String]) -> Self {
+        let mut rows = HashMap::with_capacity(keys.len());
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let occurrences = keys
+            .iter()
+            .enumerate()
+            .map(|(row, key)| {
+                let count = counts.entry(key.as_str()).or_default();
+                rows.insert((key.as_str(), *count), row);
+                *count += 1;
+                *count - 1
+            })
+            .collect();
+        Self {
+            keys,
+            rows,
+            occurrences,
+        }
+    }
+
+    /// The row with the key and occurrence of `row` of the other loop.
+    pub(crate) fn matching(&self, other: &RowIndex, row: usize) -> Option<usize> {
+        self.rows
+            .get(&(other.keys[row].as_str(), other.occurrences[row]))
+            .copied()
+    }
+
+    /// Keys of more than one row, in order of their second occurrence.
+    pub(crate) fn duplicates(&self) -> impl Iterator<Item = &'k str> + '_ {
+        let mut second: Vec<(usize, &str)> = self
+            .rows
+            .iter()
+            .filter(|((_, occurrence), _)| *occurrence == 1)
+            .map(|((key, _), row)| (*row, *key))
+            .collect();
+        second.sort_unstable();
+        second.into_iter().map(|(_, key)| key)
+    }
+}
+
+fn diff_loops(
+    block: &str,
+    old: &LoopColumns,
+    new: &LoopColumns,
+    options: &DiffOptions,
+) -> Vec<Change> {
+    let keys = key_tags(old);
+    let key_tag = keys
+        .first()
+        .cloned()
+        .or_else(|| old.columns().first().map(|c| c.tag().as_str().to_string()))
+        .unwrap_or_default();
+    let (old_keys, new_keys) = (row_keys(old, &keys), row_keys(new, &keys));
+    let (old_index, new_index) = (RowIndex::new(&old_keys), RowIndex::new(&new_keys));
+    let mut changes = Vec::new();
+    let mut duplicates: Vec<&str> = old_index.duplicates().collect();
+    for key in new_index.duplicates() {
+        if !duplicates.contains(&key) {
+            duplicates.push(key);
+        }
+    }
+    changes.extend(duplicates.into_iter().map(|key| Change::DuplicateKey {
+        block: block.to_string(),
+        key_tag: key_tag.clone(),
+        row: key.to_string(),
+    }));
+    for column in old.columns() {
+        if find_column(new, column.tag().as_str()).is_none() {
+            changes.push(Change::TagRemoved {
+                block: block.to_string(),
+                tag: column.tag().as_str().to_string(),
+                value: format!("[{} values]", column.values().len()),
+            });
+        }
+    }
+    for column in new.columns() {
+        if find_column(old, column.tag().as_str()).is_none() {
+            changes.push(Change::TagAdded {
+                block: block.to_string(),
+                tag: column.tag().as_str().to_string(),
+                value: format!("[{} values]", column.values().len()),
+            });
+        }
+    }
+    for (old_row, key) in old_keys.iter().enumerate() {
+        let Some(new_row) = new_index.matching(&old_index, old_row) else {
+            changes.push(Change::RowRemoved {
+                block: block.to_string(),
+                key_tag: key_tag.clone(),
+                row: key.clone(),
+            });
+            continue;
+        };
+        for old_column in old.columns() {
+            let tag = old_column.tag().as_str();
+            let (Some(new_column), Some(old_value)) =
+                (find_column(new, tag), old_column.values().get(old_row))
+            else {
+                continue;
+            };
+            if let Some(new_value) = new_column.values().get(new_row) {
+                changes.extend(value_change(
+                    block,
+                    tag,
+                    Some(key),
+                    old_value,
+                    new_value,
+                    options,
+                ));
+            }
+        }
+    }
+    changes.extend(
+        (0..new_keys.len())
+            .filter(|&row| old_index.matching(&new_index, row).is_none())
+            .map(|row| Change::RowAdded {
+                block: block.to_string(),
+                key_tag: key_tag.clone(),
+                row: new_keys[row].clone(),
+            }),
+    );
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CifDocument;
+
+    use super::{diff, diff_with_options, Change, DiffOptions};
+
+    #[test]
+    fn semantic_diff() {
+        let mut old = r#"data_I
+_cell_length_a 5.0123(4)
+_cell_length_b 6.00
+_refine_ls_R_factor_gt 0.045
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+Fe1 Fe 0.1234(3)
+O1 O 0.2
+O2 O 0.3
+data_old
+_cell_length_a 1
+"#;
+        let mut new = r#"data_I
+_CELL_LENGTH_A 5.0131(4)
+_cell_length_b 6.0
+_refine_ls_wR_factor_ref 0.11
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+O1 O 0.2
+Fe1 Fe 0.1244(3)
+O3 O 0.4
+"#;
+        let old = CifDocument::parse_from_str(&mut old).unwrap();
+        let new = CifDocument::parse_from_str(&mut new).unwrap();
+        let changes = diff(&old, &new);
+        let lines: Vec<String> = changes.changes().iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                "~ I _cell_length_a 5.0123(4) -> 5.0131(4) (1.4 su)",
+                "- I _refine_ls_R_factor_gt 0.045",
+                "+ I _refine_ls_wR_factor_ref 0.11",
+                "~ I _atom_site_fract_x [Fe1] 0.1234(3) -> 0.1244(3) (2.4 su)",
+                "- I _atom_site_label [O2]",
+                "+ I _atom_site_label [O3]",
+                "- data_old",
+            ]
+        );
+        assert!(changes.to_json().contains(r#""change": "row_added""#));
+
+        let options = DiffOptions::default().with_su_multiple(3.0);
+        let within_su = diff_with_options(&old, &new, &options);
+        assert!(!within_su
+            .changes()
+            .iter()
+            .any(|change| matches!(change, Change::ValueChanged { .. })));
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let mut old = "data_I\nloop_\n_atom_site_label\n_atom_site_occupancy\nC1 1\nC1 0.5\nO1 1\n";
+        let mut new = "data_I\nloop_\n_atom_site_label\n_atom_site_occupancy\nC1 1\nC1 0.4\nC1 1\n";
+        let old = CifDocument::parse_from_str(&mut old).unwrap();
+        let new = CifDocument::parse_from_str(&mut new).unwrap();
+        let lines: Vec<String> = diff(&old, &new)
+            .changes()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "! I _atom_site_label [C1] duplicate key",
+                "~ I _atom_site_occupancy [C1] 0.5 -> 0.4",
+                "- I _atom_site_label [O1]",
+                "+ I _atom_site_label [C1]",
+            ]
+        );
+    }
+}
@@ -10,7 +10,7 @@ use winnow::{
     Parser,
 };
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 use super::ordinary_char::OrdinaryChar;
 
@@ -65,12 +65,12 @@ impl Display for AnyPrintChar {
     }
 }
 
-impl SyntacticUnit for AnyPrintChar {
+impl<'a> SyntacticUnit<'a> for AnyPrintChar {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             OrdinaryChar::parser.map(char::from),
             '"',
@@ -196,6 +196,6 @@ mod test {
     #[test]
     fn any_print_char() {
         let mut input = "'";
-        dbg!(AnyPrintChar::parser(&mut input).unwrap());
+        dbg!(AnyPrintChar::parse_str(&mut input).unwrap());
     }
 }
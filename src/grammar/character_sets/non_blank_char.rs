@@ -9,7 +9,7 @@ use winnow::{
     Parser,
 };
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 use super::ordinary_char::OrdinaryChar;
 
@@ -130,12 +130,12 @@ impl Display for NonBlankChar {
     }
 }
 
-impl SyntacticUnit for NonBlankChar {
+impl<'a> SyntacticUnit<'a> for NonBlankChar {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             OrdinaryChar::parser.map(char::from),
             '"',
@@ -6,7 +6,7 @@ use winnow::{
     Parser,
 };
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 #[repr(u8)]
 #[allow(clippy::upper_case_acronyms, non_camel_case_types)]
@@ -220,11 +220,11 @@ impl Display for OrdinaryCharError {
 
 impl Error for OrdinaryCharError {}
 
-impl SyntacticUnit for OrdinaryChar {
+impl<'a> SyntacticUnit<'a> for OrdinaryChar {
     type ParseResult = OrdinaryChar;
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             alt(('!', '%', '&', '(', ')', '*', '+', ',', '-', '.', '/')),
             alt(('0', '1', '2', '3', '4', '5', '6', '7', '8', '9')),
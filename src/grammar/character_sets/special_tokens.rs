@@ -9,7 +9,7 @@ use winnow::{
     Parser,
 };
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 #[derive(Debug, Copy, Clone)]
 pub struct SP;
@@ -31,12 +31,12 @@ pub enum LeadingBlank {
     Eol,
 }
 
-impl SyntacticUnit for LeadingBlank {
+impl<'a> SyntacticUnit<'a> for LeadingBlank {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             SP::parser.map(|_| LeadingBlank::SP),
             HT::parser.map(|_| LeadingBlank::HT),
@@ -98,12 +98,12 @@ impl AsChar for LeadingBlank {
     }
 }
 
-impl SyntacticUnit for SP {
+impl<'a> SyntacticUnit<'a> for SP {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         ' '.map(|_| SP).parse_next(input)
     }
 
@@ -150,12 +150,12 @@ impl AsChar for SP {
     }
 }
 
-impl SyntacticUnit for HT {
+impl<'a> SyntacticUnit<'a> for HT {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         tab.map(|_| HT).parse_next(input)
     }
 
@@ -202,12 +202,12 @@ impl AsChar for HT {
     }
 }
 
-impl SyntacticUnit for Eol {
+impl<'a> SyntacticUnit<'a> for Eol {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         line_ending
             .map(|_| Eol)
             .context(StrContext::Label("<EOL>"))
@@ -257,12 +257,12 @@ impl AsChar for Eol {
     }
 }
 
-impl SyntacticUnit for SingleQuote {
+impl<'a> SyntacticUnit<'a> for SingleQuote {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         '\''.map(|_| SingleQuote).parse_next(input)
     }
 
@@ -309,12 +309,12 @@ impl AsChar for SingleQuote {
     }
 }
 
-impl SyntacticUnit for DoubleQuote {
+impl<'a> SyntacticUnit<'a> for DoubleQuote {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         '"'.map(|_| DoubleQuote).parse_next(input)
     }
 
@@ -370,13 +370,13 @@ impl NotEol {
     }
 }
 
-impl SyntacticUnit for NotEol {
+impl<'a> SyntacticUnit<'a> for NotEol {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
-        if peek(Eol::parser).parse_peek(input).is_err() {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
+        if peek(Eol::parser).parse_peek(input.clone()).is_err() {
             any.map(NotEol::new).parse_next(input)
         } else {
             Err(ErrMode::Backtrack(ContextError::new().add_context(
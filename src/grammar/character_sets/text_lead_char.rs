@@ -6,7 +6,7 @@ use winnow::{stream::Accumulate, Parser};
 
 use winnow::{ascii::tab, combinator::alt, stream::AsChar};
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 use super::ordinary_char::OrdinaryChar;
 
@@ -151,12 +151,12 @@ impl Display for TextLeadChar {
     }
 }
 
-impl SyntacticUnit for TextLeadChar {
+impl<'a> SyntacticUnit<'a> for TextLeadChar {
     type ParseResult = Self;
 
     type FormatOutput = char;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             OrdinaryChar::parser.map(char::from),
             '"',
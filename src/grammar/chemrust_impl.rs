@@ -1,5 +1,3 @@
-use std::str::FromStr;
-
 use castep_periodic_table::element::ElementSymbol;
 use chemrust_core::data::{
     atom::CoreAtomData, geom::coordinates::CoordData, lattice::{CellConstants, CrystalModel, UnitCellParameters}, symmetry::SymmetryInfo,
@@ -9,9 +7,17 @@ use nalgebra::Point3;
 use crate::{
     data_dict::
         core_cif::{
-            atom_site::chemrust_impl::basic_atom_site_data, audit::default_audit_data,
+            atom_site::{
+                chemrust_impl::{
+                    asymmetric_unit_atom_site_data, basic_atom_site_data, SingularCellError,
+                },
+                resolve_site_elements,
+            },
+            audit::default_audit_data,
             cell::chemrust_impl::basic_cell_data,
-            space_group::chemrust_impl::basic_space_group_data,
+            space_group::{
+                chemrust_impl::basic_space_group_data, resolve_space_group,
+            },
         },
     LoopColumn,
 };
@@ -21,13 +27,13 @@ use super::{
 };
 
 
-pub fn from_data_block_members(members: &[DataBlockMember], data_name:&str) ->  CifDocument {
+pub fn from_data_block_members<'a>(members: &[DataBlockMember<'a>], data_name:&str) ->  CifDocument<'a> {
     let heading = DataBlockHeading::new(data_name.to_string());
     let data_block = DataBlock::from_heading_members((heading, members.to_vec()));
     CifDocument::new(None, Some(vec![data_block]))
 }
 
-pub fn to_data_block<T: CrystalModel+SymmetryInfo>(model: &T, data_name: &str) -> DataBlock {
+pub fn to_data_block<T: CrystalModel+SymmetryInfo>(model: &T, data_name: &str) -> DataBlock<'static> {
     let datablock_members = [
         default_audit_data(),
         basic_space_group_data(model),
@@ -46,7 +52,34 @@ pub fn to_data_block<T: CrystalModel+SymmetryInfo>(model: &T, data_name: &str) -
     DataBlock::from_heading_members((heading, datablock_members))
 }
 
-pub fn to_cif_document<T: CrystalModel + SymmetryInfo>(model: &T, data_name: &str) -> CifDocument {
+/// Like [`to_data_block`], but with only the asymmetric unit of the model:
+/// one atom site for each set of symmetry-equivalent atoms, found within
+/// `tolerance` Å, with its multiplicity and Wyckoff letter, `?` when not
+/// tabulated. Fails when Cartesian coordinates meet singular lattice bases.
+pub fn to_asymmetric_unit_data_block<T: CrystalModel + SymmetryInfo>(
+    model: &T,
+    data_name: &str,
+    tolerance: f64,
+) -> Result<DataBlock<'static>, SingularCellError> {
+    let datablock_members = [
+        default_audit_data(),
+        basic_space_group_data(model),
+        basic_cell_data(model.get_cell_parameters()),
+        [asymmetric_unit_atom_site_data(model, tolerance)?].to_vec(),
+    ]
+    .into_iter()
+    .flat_map(|items| {
+        items
+            .into_iter()
+            .map(DataBlockMember::DataItems)
+            .collect::<Vec<DataBlockMember>>()
+    })
+    .collect();
+    let heading = DataBlockHeading::new(data_name.to_string());
+    Ok(DataBlock::from_heading_members((heading, datablock_members)))
+}
+
+pub fn to_cif_document<T: CrystalModel + SymmetryInfo>(model: &T, data_name: &str) -> CifDocument<'static> {
     let datablock_members = [
         default_audit_data(),
         basic_space_group_data(model),
@@ -66,7 +99,7 @@ pub fn to_cif_document<T: CrystalModel + SymmetryInfo>(model: &T, data_name: &st
     CifDocument::new(None, Some(vec![data_block]))
 }
 
-impl UnitCellParameters for DataBlock {
+impl UnitCellParameters for DataBlock<'_> {
     fn lattice_bases(&self) -> nalgebra::Matrix3<f64> {
         let length_a = self["cell_length_a"].as_single_value().and_then(|v| v.value().as_numeric())
             .and_then(|n| n.number().as_float().map(|&float| f64::from(float)))
@@ -91,7 +124,7 @@ impl UnitCellParameters for DataBlock {
     }
 }
 
-impl CoreAtomData for DataBlock {
+impl CoreAtomData for DataBlock<'_> {
     fn indices_repr(&self) -> Vec<usize> {
         let atom_sites = &self["atom_site_label"].as_multi_values().expect("this data block does not have atom sites data");
         let labels: &LoopColumn = &atom_sites["atom_site_label"];
@@ -100,21 +133,11 @@ impl CoreAtomData for DataBlock {
     }
 
     fn symbols_repr(&self) -> Vec<ElementSymbol> {
-        let atom_sites = &self["atom_site_label"].as_multi_values().expect("this data block does not have atom sites data");
-            let symbols = &atom_sites["atom_site_type_symbol"];
-            symbols
-                .values()
-                .iter()
-                .map(|value| {
-                    value
-                        .as_char_string()
-                        .map(|char_string| {
-                            
-                            ElementSymbol::from_str(char_string.as_ref()).expect("Single element symbol from periodic table. Multi-element symbol representation is not supported.")
-                        })
-                        .expect("the element symbol value should be <CharString>")
-                })
-                .collect()
+        resolve_site_elements(self)
+            .expect("element of every atom site from _atom_site_type_symbol or the label")
+            .iter()
+            .map(|resolved| resolved.element())
+            .collect()
     }
 
     fn coords_repr(&self) -> Vec<CoordData> {
@@ -127,10 +150,9 @@ impl CoreAtomData for DataBlock {
             let fract_z =  &atom_sites["atom_site_fract_z"];
             fract_x.values().iter().zip(fract_y.values().iter()).zip(fract_z.values().iter())
             .map(|((x,y), z)| {
-                    let (x, y, z) = (*x.as_numeric().expect("Numeric").number().as_float().expect("fractional coord should be float"),
-                        *y.as_numeric().expect("Numeric").number().as_float().expect("fractional coord should be float"),
-                        *z.as_numeric().expect("Numeric").number().as_float().expect("fractional coord should be float"));
-                    let (x,y,z) = ((*x).into(), ( *y ).into(), ( *z ).into());
+                    let (x, y, z) = (x.as_numeric().expect("Numeric").as_f64(),
+                        y.as_numeric().expect("Numeric").as_f64(),
+                        z.as_numeric().expect("Numeric").as_f64());
                     CoordData::Fractional(Point3::new(x, y, z))
 
                     
@@ -145,10 +167,9 @@ impl CoreAtomData for DataBlock {
             let cartn_z =  &atom_sites["atom_site_cartn_z"];
             cartn_x.values().iter().zip(cartn_y.values().iter()).zip(cartn_z.values().iter())
             .map(|((x,y), z)| {
-                    let (x, y, z) = (*x.as_numeric().expect("Numeric").number().as_float().expect("coord should be float"),
-                        *y.as_numeric().expect("Numeric").number().as_float().expect("coord should be float"),
-                        *z.as_numeric().expect("Numeric").number().as_float().expect("coord should be float"));
-                    let (x,y,z) = ((*x).into(), ( *y ).into(), ( *z ).into());
+                    let (x, y, z) = (x.as_numeric().expect("Numeric").as_f64(),
+                        y.as_numeric().expect("Numeric").as_f64(),
+                        z.as_numeric().expect("Numeric").as_f64());
                     CoordData::Cartesian(Point3::new(x, y, z))
                 }).collect()
         } else {
@@ -166,7 +187,7 @@ impl CoreAtomData for DataBlock {
 
 }
 
-impl CrystalModel for DataBlock {
+impl CrystalModel for DataBlock<'_> {
     fn get_cell_parameters(&self) -> &impl UnitCellParameters {
         self
     }
@@ -184,15 +205,14 @@ impl CrystalModel for DataBlock {
     }
 }
 
-impl SymmetryInfo for DataBlock {
+impl SymmetryInfo for DataBlock<'_> {
     fn make_symmetry(&self) -> bool {
         self.get_space_group_it_num() > 1_u8
     }
 
     fn get_space_group_it_num(&self) -> u8 {
-        self.find_single_value_by_tag("IT_number")
-            .and_then(|value| value.value().as_numeric()).map(|value| value.number())
-            .and_then(|value| value.as_integer().map(|i| i.0 as u8))
+        resolve_space_group(self)
+            .map(|space_group| space_group.it_number().number())
             .unwrap_or(1)
     }
 }
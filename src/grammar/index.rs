@@ -12,20 +12,20 @@ pub trait Index<Value>: private::Sealed {
     fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Self::Item;
 }
 
-impl Index<CifDocument> for usize {
-    type Item = DataBlock;
+impl<'a> Index<CifDocument<'a>> for usize {
+    type Item = DataBlock<'a>;
 
-    fn index_into<'v>(&self, v: &'v CifDocument) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v CifDocument<'a>) -> Option<&'v Self::Item> {
         v.data_blocks().and_then(|datablocks| datablocks.get(*self))
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut CifDocument) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut CifDocument<'a>) -> Option<&'v mut Self::Item> {
         v.data_blocks_mut()
             .as_mut()
             .and_then(|datablocks| datablocks.get_mut(*self))
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut CifDocument) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut CifDocument<'a>) -> &'v mut Self::Item {
         let len = v
             .data_blocks()
             .map(|blocks| blocks.len())
@@ -42,21 +42,21 @@ impl Index<CifDocument> for usize {
     }
 }
 
-impl Index<CifDocument> for str {
-    type Item = DataBlock;
+impl<'a> Index<CifDocument<'a>> for str {
+    type Item = DataBlock<'a>;
 
-    fn index_into<'v>(&self, v: &'v CifDocument) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v CifDocument<'a>) -> Option<&'v Self::Item> {
         v.data_blocks()
             .and_then(|datablocks| datablocks.iter().find(|block| block.heading() == self))
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut CifDocument) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut CifDocument<'a>) -> Option<&'v mut Self::Item> {
         v.data_blocks_mut()
             .as_mut()
             .and_then(|datablocks| datablocks.iter_mut().find(|block| block.heading() == self))
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut CifDocument) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut CifDocument<'a>) -> &'v mut Self::Item {
         self.index_into_mut(v).unwrap_or_else(|| {
             panic!(
                 "cannot access datablock with name '{}' in the cif document",
@@ -66,26 +66,26 @@ impl Index<CifDocument> for str {
     }
 }
 
-impl Index<CifDocument> for String {
-    type Item = DataBlock;
+impl<'a> Index<CifDocument<'a>> for String {
+    type Item = DataBlock<'a>;
 
-    fn index_into<'v>(&self, v: &'v CifDocument) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v CifDocument<'a>) -> Option<&'v Self::Item> {
         self[..].index_into(v)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut CifDocument) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut CifDocument<'a>) -> Option<&'v mut Self::Item> {
         self[..].index_into_mut(v)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut CifDocument) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut CifDocument<'a>) -> &'v mut Self::Item {
         self[..].index_or_insert(v)
     }
 }
 
-impl Index<DataBlock> for usize {
-    type Item = DataItems;
+impl<'a> Index<DataBlock<'a>> for usize {
+    type Item = DataItems<'a>;
 
-    fn index_into<'v>(&self, v: &'v DataBlock) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v DataBlock<'a>) -> Option<&'v Self::Item> {
         v.members().get(*self).and_then(|member| {
             match member {
                 DataBlockMember::DataItems(item) => Some(item),
@@ -94,7 +94,7 @@ impl Index<DataBlock> for usize {
         })
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut DataBlock) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut DataBlock<'a>) -> Option<&'v mut Self::Item> {
         v.members_mut()
             .get_mut(*self)
             .and_then(|member| match member {
@@ -103,7 +103,7 @@ impl Index<DataBlock> for usize {
             })
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut DataBlock) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut DataBlock<'a>) -> &'v mut Self::Item {
         let len = v.members().len();
         v.members_mut()
             .get_mut(*self)
@@ -120,10 +120,10 @@ impl Index<DataBlock> for usize {
     }
 }
 
-impl Index<DataBlock> for str {
-    type Item = DataItems;
+impl<'a> Index<DataBlock<'a>> for str {
+    type Item = DataItems<'a>;
 
-    fn index_into<'v>(&self, v: &'v DataBlock) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v DataBlock<'a>) -> Option<&'v Self::Item> {
         v.members().iter().find_map(|member| match member {
             DataBlockMember::DataItems(item) => match item {
                 crate::DataItems::SingleValue(v) => {
@@ -145,7 +145,7 @@ impl Index<DataBlock> for str {
         })
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut DataBlock) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut DataBlock<'a>) -> Option<&'v mut Self::Item> {
         v.members_mut().iter_mut().find_map(|member| match member {
             DataBlockMember::DataItems(item) => match item {
                 crate::DataItems::SingleValue(v) => {
@@ -167,162 +167,162 @@ impl Index<DataBlock> for str {
         })
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut DataBlock) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut DataBlock<'a>) -> &'v mut Self::Item {
         self.index_into_mut(v)
             .unwrap_or_else(|| panic!("cannot access item with tag {} of data block members", self))
     }
 }
 
-impl Index<DataBlock> for String {
-    type Item = DataItems;
+impl<'a> Index<DataBlock<'a>> for String {
+    type Item = DataItems<'a>;
 
-    fn index_into<'v>(&self, v: &'v DataBlock) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v DataBlock<'a>) -> Option<&'v Self::Item> {
         self[..].index_into(v)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut DataBlock) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut DataBlock<'a>) -> Option<&'v mut Self::Item> {
         self[..].index_into_mut(v)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut DataBlock) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut DataBlock<'a>) -> &'v mut Self::Item {
         self[..].index_or_insert(v)
     }
 }
 
-impl Index<LoopColumns> for usize {
-    type Item = LoopColumn;
+impl<'a> Index<LoopColumns<'a>> for usize {
+    type Item = LoopColumn<'a>;
 
-    fn index_into<'v>(&self, v: &'v LoopColumns) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v LoopColumns<'a>) -> Option<&'v Self::Item> {
         v.columns().get(*self)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns<'a>) -> Option<&'v mut Self::Item> {
         v.columns_mut().get_mut(*self)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns<'a>) -> &'v mut Self::Item {
         let len = v.columns().len();
         self.index_into_mut(v)
             .unwrap_or_else(|| panic!("cannot access index {} of {} loop columns", self, len))
     }
 }
 
-impl Index<LoopColumns> for str {
-    type Item = LoopColumn;
+impl<'a> Index<LoopColumns<'a>> for str {
+    type Item = LoopColumn<'a>;
 
-    fn index_into<'v>(&self, v: &'v LoopColumns) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v LoopColumns<'a>) -> Option<&'v Self::Item> {
         v.find_loop_column_by_tag(self)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns<'a>) -> Option<&'v mut Self::Item> {
         v.find_loop_column_mut_by_tag(self)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns<'a>) -> &'v mut Self::Item {
         self.index_into_mut(v)
             .unwrap_or_else(|| panic!("cannot access column with tag {} in the loop", self))
     }
 }
 
-impl Index<LoopColumns> for String {
-    type Item = LoopColumn;
+impl<'a> Index<LoopColumns<'a>> for String {
+    type Item = LoopColumn<'a>;
 
-    fn index_into<'v>(&self, v: &'v LoopColumns) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v LoopColumns<'a>) -> Option<&'v Self::Item> {
         self[..].index_into(v)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns<'a>) -> Option<&'v mut Self::Item> {
         self[..].index_into_mut(v)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns<'a>) -> &'v mut Self::Item {
         self[..].index_or_insert(v)
     }
 }
 
-impl<T> Index<CifDocument> for &T
+impl<'a, T> Index<CifDocument<'a>> for &T
 where
-    T: ?Sized + Index<CifDocument, Item = DataBlock>,
+    T: ?Sized + Index<CifDocument<'a>, Item = DataBlock<'a>>,
 {
-    type Item = DataBlock;
+    type Item = DataBlock<'a>;
 
-    fn index_into<'v>(&self, v: &'v CifDocument) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v CifDocument<'a>) -> Option<&'v Self::Item> {
         (**self).index_into(v)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut CifDocument) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut CifDocument<'a>) -> Option<&'v mut Self::Item> {
         (**self).index_into_mut(v)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut CifDocument) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut CifDocument<'a>) -> &'v mut Self::Item {
         (**self).index_or_insert(v)
     }
 }
 
-impl<T> Index<DataBlock> for &T
+impl<'a, T> Index<DataBlock<'a>> for &T
 where
-    T: ?Sized + Index<DataBlock, Item = DataItems>,
+    T: ?Sized + Index<DataBlock<'a>, Item = DataItems<'a>>,
 {
-    type Item = DataItems;
+    type Item = DataItems<'a>;
 
-    fn index_into<'v>(&self, v: &'v DataBlock) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v DataBlock<'a>) -> Option<&'v Self::Item> {
         (**self).index_into(v)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut DataBlock) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut DataBlock<'a>) -> Option<&'v mut Self::Item> {
         (**self).index_into_mut(v)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut DataBlock) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut DataBlock<'a>) -> &'v mut Self::Item {
         (**self).index_or_insert(v)
     }
 }
 
-impl<T> Index<LoopColumns> for &T
+impl<'a, T> Index<LoopColumns<'a>> for &T
 where
-    T: ?Sized + Index<LoopColumns, Item = LoopColumn>,
+    T: ?Sized + Index<LoopColumns<'a>, Item = LoopColumn<'a>>,
 {
-    type Item = LoopColumn;
+    type Item = LoopColumn<'a>;
 
-    fn index_into<'v>(&self, v: &'v LoopColumns) -> Option<&'v Self::Item> {
+    fn index_into<'v>(&self, v: &'v LoopColumns<'a>) -> Option<&'v Self::Item> {
         (**self).index_into(v)
     }
 
-    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns) -> Option<&'v mut Self::Item> {
+    fn index_into_mut<'v>(&self, v: &'v mut LoopColumns<'a>) -> Option<&'v mut Self::Item> {
         (**self).index_into_mut(v)
     }
 
-    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns) -> &'v mut Self::Item {
+    fn index_or_insert<'v>(&self, v: &'v mut LoopColumns<'a>) -> &'v mut Self::Item {
         (**self).index_or_insert(v)
     }
 }
 
-impl<I> ops::Index<I> for DataBlock
+impl<'a, I> ops::Index<I> for DataBlock<'a>
 where
-    I: Index<DataBlock, Item = DataItems>,
+    I: Index<DataBlock<'a>, Item = DataItems<'a>>,
 {
-    type Output = DataItems;
+    type Output = DataItems<'a>;
 
     fn index(&self, index: I) -> &Self::Output {
-        static NULL: DataItems = DataItems::SingleValue(SingleLineData::null());
+        static NULL: DataItems<'static> = DataItems::SingleValue(SingleLineData::null());
         index.index_into(self).unwrap_or(&NULL)
     }
 }
 
-impl<I> ops::IndexMut<I> for DataBlock
+impl<'a, I> ops::IndexMut<I> for DataBlock<'a>
 where
-    I: Index<DataBlock, Item = DataItems>,
+    I: Index<DataBlock<'a>, Item = DataItems<'a>>,
 {
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
         index.index_or_insert(self)
     }
 }
 
-impl<I> ops::Index<I> for CifDocument
+impl<'a, I> ops::Index<I> for CifDocument<'a>
 where
-    I: Index<CifDocument, Item = DataBlock> + Debug,
+    I: Index<CifDocument<'a>, Item = DataBlock<'a>> + Debug,
 {
-    type Output = DataBlock;
+    type Output = DataBlock<'a>;
 
     fn index(&self, index: I) -> &Self::Output {
         index
@@ -331,20 +331,20 @@ where
     }
 }
 
-impl<I> ops::IndexMut<I> for CifDocument
+impl<'a, I> ops::IndexMut<I> for CifDocument<'a>
 where
-    I: Index<CifDocument, Item = DataBlock> + Debug,
+    I: Index<CifDocument<'a>, Item = DataBlock<'a>> + Debug,
 {
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
         index.index_or_insert(self)
     }
 }
 
-impl<I> ops::Index<I> for LoopColumns
+impl<'a, I> ops::Index<I> for LoopColumns<'a>
 where
-    I: Index<LoopColumns, Item = LoopColumn> + Debug,
+    I: Index<LoopColumns<'a>, Item = LoopColumn<'a>> + Debug,
 {
-    type Output = LoopColumn;
+    type Output = LoopColumn<'a>;
 
     fn index(&self, index: I) -> &Self::Output {
         index.index_into(self).unwrap_or_else(|| {
@@ -356,9 +356,9 @@ where
     }
 }
 
-impl<I> ops::IndexMut<I> for LoopColumns
+impl<'a, I> ops::IndexMut<I> for LoopColumns<'a>
 where
-    I: Index<LoopColumns, Item = LoopColumn> + Debug,
+    I: Index<LoopColumns<'a>, Item = LoopColumn<'a>> + Debug,
 {
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
         index.index_or_insert(self)
@@ -16,11 +16,15 @@ mod tags_values;
 mod whitespace_comments;
 
 mod index;
+mod span;
 
 #[cfg(feature = "chemrust-core")]
 pub mod chemrust_impl;
 
 pub use numeric_values::{Float, Integer, Number, Numeric, UnsignedInteger};
+pub(crate) use span::located_at;
+pub use span::{located, Input, Position, Span, Spanned};
+pub(crate) use strings_textfields::closes_quote;
 pub use strings_textfields::{
     CharString, DoubleQuotedString, SingleQuotedString, TextField, UnquotedString,
 };
@@ -30,35 +34,44 @@ pub use structures::{
 };
 pub use tags_values::{CIFValue, Tag, Value};
 
-pub trait SyntacticUnit {
+pub trait SyntacticUnit<'a> {
     type ParseResult;
     type FormatOutput: Display;
-    fn parser(input: &mut &str) -> PResult<Self::ParseResult>;
+    fn parser(input: &mut Input<'a>) -> PResult<Self::ParseResult>;
+    /// Parse from the start of `input`, advancing it past what was parsed.
+    /// Spans are counted from the start of `input`.
+    fn parse_str(input: &mut &'a str) -> PResult<Self::ParseResult> {
+        span::parse_located(input, Self::parser)
+    }
     fn formatted_output(&self) -> Self::FormatOutput;
-    fn add_to_document(&self, doc: &mut CifDocument) {
+    fn add_to_document(&self, doc: &mut CifDocument<'a>) {
         unimplemented!()
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct CifDocument {
+pub struct CifDocument<'a> {
     comments: Option<Comments>,
-    data_blocks: Option<Vec<DataBlock>>,
+    data_blocks: Option<Vec<DataBlock<'a>>>,
 }
 
-impl CifDocument {
-    pub fn new(comments: Option<Comments>, data_blocks: Option<Vec<DataBlock>>) -> Self {
+impl<'a> CifDocument<'a> {
+    pub fn new(comments: Option<Comments>, data_blocks: Option<Vec<DataBlock<'a>>>) -> Self {
         Self {
             comments,
             data_blocks,
         }
     }
 
-    pub fn data_blocks(&self) -> Option<&Vec<DataBlock>> {
+    pub(crate) fn comments(&self) -> Option<&Comments> {
+        self.comments.as_ref()
+    }
+
+    pub fn data_blocks(&self) -> Option<&Vec<DataBlock<'a>>> {
         self.data_blocks.as_ref()
     }
 
-    pub fn get_data_block_by_name(&self, data_block_name: &str) -> Option<&DataBlock> {
+    pub fn get_data_block_by_name(&self, data_block_name: &str) -> Option<&DataBlock<'a>> {
         self.data_blocks().map(|blocks| {
             blocks
                 .iter()
@@ -66,24 +79,35 @@ impl CifDocument {
         })?
     }
 
-    pub fn data_blocks_mut(&mut self) -> &mut Option<Vec<DataBlock>> {
+    pub fn data_blocks_mut(&mut self) -> &mut Option<Vec<DataBlock<'a>>> {
         &mut self.data_blocks
     }
 
-    pub fn parse_from_str(input: &mut &str) -> PResult<CifDocument> {
-        CifDocument::parser(input)
+    /// Parse a document borrowing its strings from `input`; call
+    /// [`CifDocument::into_owned`] to keep it longer than the input.
+    pub fn parse_from_str(input: &mut &'a str) -> PResult<CifDocument<'a>> {
+        CifDocument::parse_str(input)
+    }
+
+    /// A copy not borrowing from the parsed input.
+    pub fn into_owned(self) -> CifDocument<'static> {
+        CifDocument::new(
+            self.comments,
+            self.data_blocks
+                .map(|blocks| blocks.into_iter().map(DataBlock::into_owned).collect()),
+        )
     }
 }
 
-impl SyntacticUnit for CifDocument {
+impl<'a> SyntacticUnit<'a> for CifDocument<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> PResult<Self::ParseResult> {
         let comments = opt(Comments::parser).parse_next(input)?;
         opt(WhiteSpace::parser).parse_next(input)?;
-        let data_blocks_parsing = |input: &mut &str| -> PResult<Vec<DataBlock>> {
+        let data_blocks_parsing = |input: &mut Input<'a>| -> PResult<Vec<DataBlock<'a>>> {
             terminated(
                 (
                     DataBlock::parser,
@@ -91,7 +115,7 @@ impl SyntacticUnit for CifDocument {
                 ),
                 opt(WhiteSpace::parser),
             )
-            .map(|(first, mut others): (DataBlock, Vec<DataBlock>)| {
+            .map(|(first, mut others): (DataBlock<'a>, Vec<DataBlock<'a>>)| {
                 others.insert(0, first);
                 others
             })
@@ -119,7 +143,7 @@ impl SyntacticUnit for CifDocument {
     }
 }
 
-impl Display for CifDocument {
+impl Display for CifDocument<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
@@ -138,7 +162,7 @@ mod test {
         let home = std::env::var("HOME").unwrap();
         let example = format!("{home}/Downloads/example.cif");
         let content = read_to_string(example).expect("Reading error");
-        match CifDocument::parser(&mut content.as_str()) {
+        match CifDocument::parse_str(&mut content.as_str()) {
             Ok(cif) => {
                 let output_path = "cif_parse_test.cif";
                 dbg!(&cif["global"]["audit_creation_date"]);
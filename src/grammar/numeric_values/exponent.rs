@@ -2,7 +2,7 @@ use std::{fmt::Display, ops::Deref};
 
 use winnow::{ascii::Caseless, combinator::alt, Parser};
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 use super::unsigned_integer::UnsignedInteger;
 
@@ -17,12 +17,12 @@ impl std::ops::Deref for Exponent {
     }
 }
 
-impl SyntacticUnit for Exponent {
+impl<'a> SyntacticUnit<'a> for Exponent {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         (
             alt((Caseless("e+"), Caseless("e-"), Caseless("e"))),
             UnsignedInteger::parser,
@@ -56,6 +56,6 @@ mod test {
     #[test]
     fn exponent_test() {
         let mut input = "e-3";
-        dbg!(Exponent::parser(&mut input).unwrap());
+        dbg!(Exponent::parse_str(&mut input).unwrap());
     }
 }
@@ -7,7 +7,7 @@ use winnow::{
     PResult, Parser,
 };
 
-use crate::grammar::{SyntacticUnit, Value};
+use crate::grammar::{Input, SyntacticUnit, Value};
 
 use super::{exponent::Exponent, integer::Integer, Number, Numeric};
 
@@ -28,27 +28,27 @@ impl std::ops::Deref for Float {
     }
 }
 
-fn sign_zero_or_one(input: &mut &str) -> PResult<String> {
+fn sign_zero_or_one(input: &mut Input<'_>) -> PResult<String> {
     repeat(0..=1, alt(('+', '-')))
         .context(StrContext::Label("{'+'|'-'}?"))
         .parse_next(input)
 }
 
-fn exponent_zero_or_one(input: &mut &str) -> PResult<String> {
+fn exponent_zero_or_one(input: &mut Input<'_>) -> PResult<String> {
     repeat(0..=1, Exponent::parser.map(|e| e.formatted_output()))
         .context(StrContext::Label("{<Exponent>}?"))
         .map(|exps: Vec<String>| exps.concat())
         .parse_next(input)
 }
 
-fn float_1(input: &mut &str) -> PResult<f32> {
+fn float_1(input: &mut Input<'_>) -> PResult<f32> {
     (Integer::parser, Exponent::parser)
         .map(|(i, e)| *i as f32 * 10_f32.powi(*e))
         .context(StrContext::Label("<Integer><Exponent>"))
         .parse_next(input)
 }
 
-fn float_2(input: &mut &str) -> PResult<f32> {
+fn float_2(input: &mut Input<'_>) -> PResult<f32> {
     (
         sign_zero_or_one,
         alt((
@@ -65,12 +65,12 @@ fn float_2(input: &mut &str) -> PResult<f32> {
         .parse_next(input)
 }
 
-impl SyntacticUnit for Float {
+impl<'a> SyntacticUnit<'a> for Float {
     type ParseResult = Self;
 
     type FormatOutput = f32;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((float_1, float_2)).map(Float).parse_next(input)
     }
 
@@ -97,7 +97,7 @@ impl From<Float> for Numeric {
     }
 }
 
-impl From<Float> for Value {
+impl From<Float> for Value<'_> {
     fn from(value: Float) -> Self {
         Value::Numeric(Numeric::from(value))
     }
@@ -116,7 +116,7 @@ mod test {
     #[test]
     fn float_parsing() {
         let mut input = "0.0033";
-        dbg!(Float::parser(&mut input).unwrap());
-        dbg!(Float::parser(&mut ".4254").unwrap());
+        dbg!(Float::parse_str(&mut input).unwrap());
+        dbg!(Float::parse_str(&mut ".4254").unwrap());
     }
 }
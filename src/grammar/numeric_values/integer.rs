@@ -5,7 +5,7 @@ use winnow::{
     Parser,
 };
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 use super::unsigned_integer::UnsignedInteger;
 
@@ -26,12 +26,12 @@ impl std::ops::Deref for Integer {
     }
 }
 
-impl SyntacticUnit for Integer {
+impl<'a> SyntacticUnit<'a> for Integer {
     type ParseResult = Self;
 
     type FormatOutput = i32;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         (repeat(0..=1, alt(('+', '-'))), UnsignedInteger::parser)
             .map(|(sign, u): (String, UnsignedInteger)| {
                 if sign.contains('-') {
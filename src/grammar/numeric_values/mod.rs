@@ -5,7 +5,7 @@ use winnow::{
     Parser,
 };
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 pub use self::{float::Float, integer::Integer, unsigned_integer::UnsignedInteger};
 
@@ -42,12 +42,12 @@ impl Number {
 
 impl CIFValue for Number {}
 
-impl SyntacticUnit for Number {
+impl<'a> SyntacticUnit<'a> for Number {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             Float::parser.map(Number::Float),
             Integer::parser.map(Number::Integer),
@@ -75,16 +75,44 @@ impl From<u32> for Number {
     }
 }
 
+impl From<Number> for f64 {
+    fn from(value: Number) -> Self {
+        match value {
+            Number::Integer(i) => *i as f64,
+            Number::Float(f) => f.into(),
+        }
+    }
+}
+
 impl Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy)]
 pub struct Numeric {
     number: Number,
     std_uncertainty: Option<UnsignedInteger>,
+    /// Number of decimal places written, which is also the position of the
+    /// last digit of the standard uncertainty; negative for a number like
+    /// `1.2e5` whose last digit is left of the point. `None` uses the default
+    /// format.
+    decimals: Option<i32>,
+}
+
+/// Numbers are equal by value and su, however many decimals are written:
+/// `1.0` equals `1.00`.
+impl PartialEq for Numeric {
+    fn eq(&self, other: &Self) -> bool {
+        self.number == other.number && self.std_uncertainty == other.std_uncertainty
+    }
+}
+
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (self.number, self.std_uncertainty).partial_cmp(&(other.number, other.std_uncertainty))
+    }
 }
 
 impl Numeric {
@@ -92,6 +120,41 @@ impl Numeric {
         Self {
             number,
             std_uncertainty,
+            decimals: None,
+        }
+    }
+
+    /// A float written with a fixed number of decimal places.
+    pub fn with_decimals(value: f64, decimals: usize) -> Self {
+        Self {
+            number: Number::Float(Float(value as f32)),
+            std_uncertainty: None,
+            decimals: Some(decimals as i32),
+        }
+    }
+
+    /// A float with its standard uncertainty, rounded the way
+    /// crystallographic programs report them: the su keeps two digits when
+    /// they are at most 19 and one digit otherwise, e.g. `1.4662 ± 0.0071`
+    /// gives `1.466(7)` and `115.23 ± 0.14` gives `115.23(14)`. The value is
+    /// rounded to the last digit of the su, also when that is left of the
+    /// point: `1234.6 ± 23` gives `1230(20)`.
+    /// A non-positive su gives four decimal places without uncertainty.
+    pub fn from_value_su(value: f64, su: f64) -> Self {
+        if !(su > 0.0 && su.is_finite()) {
+            return Self::with_decimals(value, 4);
+        }
+        let mut decimals = 1 - su.log10().floor() as i32;
+        if (su * 10_f64.powi(decimals)).round() >= 20.0 {
+            decimals -= 1;
+        }
+        let scale = 10_f64.powi(decimals);
+        let value = (value * scale).round() / scale;
+        let su_digits = (su * scale).round().max(1.0) / scale.min(1.0);
+        Self {
+            number: Number::Float(Float(value as f32)),
+            std_uncertainty: Some(UnsignedInteger(su_digits.round() as u32)),
+            decimals: Some(decimals.max(0)),
         }
     }
 
@@ -102,29 +165,77 @@ impl Numeric {
     pub fn std_uncertainty(&self) -> Option<UnsignedInteger> {
         self.std_uncertainty
     }
+
+    pub fn as_f64(&self) -> f64 {
+        f64::from(self.number)
+    }
+
+    /// Decimal places of the number as written.
+    pub fn decimal_places(&self) -> usize {
+        self.scale().max(0) as usize
+    }
+
+    /// Position of the last digit written, as for `decimals`.
+    fn scale(&self) -> i32 {
+        self.decimals.unwrap_or(match self.number {
+            Number::Integer(_) => 0,
+            Number::Float(_) => 3,
+        })
+    }
+
+    /// The standard uncertainty in the units of the value, e.g. `0.007` for
+    /// `1.466(7)` and `40000` for `1.2e5(4)`.
+    pub fn su_value(&self) -> Option<f64> {
+        self.std_uncertainty
+            .map(|su| *su as f64 * 10_f64.powi(-self.scale()))
+    }
+}
+
+/// Position of the last digit of a number as written: its decimal places,
+/// less the exponent. `None` for a plain integer.
+fn written_decimals(text: &str) -> Option<i32> {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (
+            &text[..i],
+            text[i + 1..].trim_start_matches('+').parse::<i32>().ok()?,
+        ),
+        None => (text, 0),
+    };
+    let fraction = mantissa.split_once('.').map_or(0, |(_, f)| f.len()) as i32;
+    (fraction != 0 || exponent != 0 || mantissa.contains('.')).then_some(fraction - exponent)
 }
 
-impl SyntacticUnit for Numeric {
+impl<'a> SyntacticUnit<'a> for Numeric {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         terminated(
             (
-                Number::parser,
+                Number::parser.with_taken(),
                 opt(delimited('(', UnsignedInteger::parser, ')')),
             ),
             peek(WhiteSpace::parser),
         )
-        .map(|(number, uncer)| Numeric::new(number, uncer))
+        .map(|((number, text), uncer)| Numeric {
+            number,
+            std_uncertainty: uncer,
+            decimals: written_decimals(text),
+        })
         .parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
+        let number = match (self.number, self.decimals) {
+            (Number::Float(f), Some(decimals)) if decimals >= 0 => {
+                format!("{:.*}", decimals as usize, f.0)
+            }
+            (number, _) => format!("{number}"),
+        };
         match self.std_uncertainty {
-            Some(c) => format!("{}({})", self.number, c),
-            None => format!("{}", self.number),
+            Some(c) => format!("{}({})", number, c),
+            None => number,
         }
     }
 }
@@ -153,7 +264,7 @@ impl From<i32> for Numeric {
     }
 }
 
-impl From<Numeric> for Value {
+impl From<Numeric> for Value<'_> {
     fn from(value: Numeric) -> Self {
         Value::Numeric(value)
     }
@@ -165,10 +276,36 @@ mod test {
 
     use super::Numeric;
 
+    #[test]
+    fn numeric_precision() {
+        let mut input = "0.0033(4) ";
+        let n = Numeric::parse_str(&mut input).unwrap();
+        assert_eq!(n.to_string(), "0.0033(4)");
+        assert!((n.su_value().unwrap() - 0.0004).abs() < 1e-12);
+        assert_eq!(
+            Numeric::from_value_su(1.4662, 0.0071).to_string(),
+            "1.466(7)"
+        );
+        assert_eq!(
+            Numeric::from_value_su(115.23, 0.14).to_string(),
+            "115.23(14)"
+        );
+        assert_eq!(Numeric::from_value_su(115.23, 0.52).to_string(), "115.2(5)");
+        assert_eq!(Numeric::from_value_su(1234.6, 23.0).to_string(), "1230(20)");
+        assert_eq!(Numeric::from_value_su(1234.6, 7.0).to_string(), "1235(7)");
+        for (text, su) in [("1.23e-3(4) ", 4e-5), ("1.2e5(4) ", 4e4), ("12e3(4) ", 4e3)] {
+            let n = Numeric::parse_str(&mut &text[..]).unwrap();
+            assert!((n.su_value().unwrap() - su).abs() < su * 1e-9, "{text}");
+        }
+        let one = Numeric::parse_str(&mut "1.0 ").unwrap();
+        assert_eq!(one, Numeric::parse_str(&mut "1.00 ").unwrap());
+        assert_ne!(one, Numeric::parse_str(&mut "1.0(1) ").unwrap());
+    }
+
     #[test]
     fn numeric_test() {
         let mut input = "482.66(9)\n";
-        match Numeric::parser(&mut input) {
+        match Numeric::parse_str(&mut input) {
             Ok(n) => println!("{n:?}"),
             Err(e) => {
                 println!("{e}");
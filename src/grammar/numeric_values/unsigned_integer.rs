@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use winnow::{ascii::digit1, error::StrContext, Parser};
 
-use crate::grammar::{SyntacticUnit, Value};
+use crate::grammar::{Input, SyntacticUnit, Value};
 
 use super::{Integer, Number, Numeric};
 
@@ -17,12 +17,12 @@ impl std::ops::Deref for UnsignedInteger {
     }
 }
 
-impl SyntacticUnit for UnsignedInteger {
+impl<'a> SyntacticUnit<'a> for UnsignedInteger {
     type ParseResult = Self;
 
     type FormatOutput = u32;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         digit1
             .map(|n: &str| {
                 n.parse::<u32>()
@@ -50,7 +50,7 @@ impl From<UnsignedInteger> for Integer {
     }
 }
 
-impl From<UnsignedInteger> for Value {
+impl From<UnsignedInteger> for Value<'_> {
     fn from(value: UnsignedInteger) -> Self {
         Value::Numeric(Numeric::new(Number::Integer(value.into()), None))
     }
@@ -2,16 +2,16 @@ use std::fmt::Display;
 
 use winnow::{ascii::Caseless, Parser};
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Loop;
-impl SyntacticUnit for Loop {
+impl<'a> SyntacticUnit<'a> for Loop {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         Caseless("loop_").map(|_| Loop).parse_next(input)
     }
 
@@ -2,17 +2,17 @@ use std::fmt::Display;
 
 use winnow::{ascii::Caseless, Parser};
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Global;
 
-impl SyntacticUnit for Global {
+impl<'a> SyntacticUnit<'a> for Global {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         Caseless("global_").map(|_| Global).parse_next(input)
     }
 
@@ -10,12 +10,9 @@ pub use data::Data;
 pub use global::Global;
 pub use save::Save;
 pub use stop::Stop;
-use winnow::{
-    combinator::{alt, peek},
-    Parser,
-};
+use winnow::{combinator::alt, Parser};
 
-use super::SyntacticUnit;
+use super::{Input, SyntacticUnit};
 
 #[derive(Debug, Clone)]
 pub enum ReservedWords {
@@ -26,12 +23,12 @@ pub enum ReservedWords {
     Stop,
 }
 
-impl SyntacticUnit for ReservedWords {
+impl<'a> SyntacticUnit<'a> for ReservedWords {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             Loop::parser.map(|_| Self::Loop),
             Data::parser.map(|_| Self::Data),
@@ -48,7 +45,12 @@ impl SyntacticUnit for ReservedWords {
 }
 
 impl ReservedWords {
-    pub fn not_reserved_words(input: &mut &str) -> bool {
-        peek(ReservedWords::parser).parse_peek(input).is_err()
+    pub fn not_reserved_words(word: &str) -> bool {
+        !["loop_", "data_", "global_", "save_", "stop_"]
+            .iter()
+            .any(|reserved| {
+                word.get(..reserved.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(reserved))
+            })
     }
 }
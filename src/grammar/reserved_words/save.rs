@@ -2,17 +2,17 @@ use std::fmt::Display;
 
 use winnow::{ascii::Caseless, Parser};
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Save;
 
-impl SyntacticUnit for Save {
+impl<'a> SyntacticUnit<'a> for Save {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         Caseless("save_").map(|_| Save).parse_next(input)
     }
 
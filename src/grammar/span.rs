@@ -0,0 +1,255 @@
+use std::{
+    ops::{Deref, Range},
+    sync::Arc,
+};
+
+use winnow::{
+    error::ContextError,
+    stream::{Located, Location, Stateful},
+    PResult, Parser,
+};
+
+/// The stream the grammar is parsed from: the text, located to record the
+/// byte offsets of the nodes, with the start of its lines to give their line
+/// and column.
+pub type Input<'a> = Stateful<Located<&'a str>, Source>;
+
+/// `text` as [`Input`], with offsets and lines counted from its start.
+pub fn located(text: &str) -> Input<'_> {
+    located_at(text, 0, 1)
+}
+
+/// `text` read from byte `offset` and `line` of a larger input, like a
+/// block of a streamed file.
+pub(crate) fn located_at(text: &str, offset: usize, line: usize) -> Input<'_> {
+    Stateful {
+        input: Located::new(text),
+        state: Source::new(text, offset, line),
+    }
+}
+
+/// A byte offset into the parsed text, with its line and column counted
+/// from 1. The column is counted in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// Where a node was parsed from, from its first byte to the byte after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+impl Span {
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// The byte range of the node in the parsed text.
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+/// A node with where it was parsed from, for nodes like [`Value`](crate::Value)
+/// which are plain enums and have no room for their span. Dereferences to
+/// the node.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spanned<T> {
+    node: T,
+    span: Option<Span>,
+}
+
+impl<T> Spanned<T> {
+    /// A node built in code, without a span.
+    pub const fn new(node: T) -> Self {
+        Self { node, span: None }
+    }
+
+    pub(crate) fn with_span(node: T, span: Option<Span>) -> Self {
+        Self { node, span }
+    }
+
+    pub fn node(&self) -> &T {
+        &self.node
+    }
+
+    pub fn into_node(self) -> T {
+        self.node
+    }
+
+    /// Where the node was parsed from; `None` when built in code.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// The node mapped by `f`, keeping its span.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned {
+            node: f(self.node),
+            span: self.span,
+        }
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+/// The start of the lines of the parsed text, shared by the clones of the
+/// stream.
+#[derive(Debug, Clone)]
+pub struct Source {
+    offset: usize,
+    line: usize,
+    line_starts: Arc<[usize]>,
+}
+
+impl Source {
+    fn new(text: &str, offset: usize, line: usize) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            offset,
+            line,
+            line_starts,
+        }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let index = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        Position {
+            offset: self.offset + offset,
+            line: self.line + index,
+            column: offset - self.line_starts[index] + 1,
+        }
+    }
+
+    pub(crate) fn span(&self, range: Range<usize>) -> Span {
+        Span {
+            start: self.position(range.start),
+            end: self.position(range.end),
+        }
+    }
+}
+
+/// `parser` with the span of what it consumed.
+pub(crate) fn spanned<'a, O>(
+    mut parser: impl Parser<Input<'a>, O, ContextError>,
+) -> impl Parser<Input<'a>, (O, Span), ContextError> {
+    move |input: &mut Input<'a>| {
+        let start = input.location();
+        let output = parser.parse_next(input)?;
+        Ok((output, input.state.span(start..input.location())))
+    }
+}
+
+/// Parse `input` with `parser` and advance it past what was consumed.
+pub(crate) fn parse_located<'a, O>(
+    input: &mut &'a str,
+    mut parser: impl Parser<Input<'a>, O, ContextError>,
+) -> PResult<O> {
+    let mut located = located(input);
+    let parsed = parser.parse_next(&mut located);
+    *input = &input[located.location()..];
+    parsed
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CifDocument;
+
+    use super::Source;
+
+    #[test]
+    fn line_columns() {
+        let source = Source::new("data_a\n_tag 1\n\n_other 2", 0, 1);
+        let span = source.span(7..11);
+        assert_eq!((span.start().line(), span.start().column()), (2, 1));
+        assert_eq!((span.end().line(), span.end().column()), (2, 5));
+        let end = source.span(16..23).end();
+        assert_eq!((end.offset(), end.line(), end.column()), (23, 4, 9));
+        let shifted = Source::new("_tag 1\n", 100, 5).span(5..6);
+        assert_eq!((shifted.start().offset(), shifted.start().line()), (105, 5));
+    }
+
+    #[test]
+    fn node_spans() {
+        let text = "data_a\n_cell_length_a  5.0(1)\nloop_\n_atom_site_label\nFe1\n  O1\n";
+        let document = CifDocument::parse_from_str(&mut &text[..]).unwrap();
+        let block = &document.data_blocks().unwrap()[0];
+        let item = block.find_single_value_by_tag("cell_length_a").unwrap();
+        assert_eq!(item.tag().span().unwrap().range(), 7..21);
+        let value = item.spanned_value();
+        assert_eq!(&text[value.span().unwrap().range()], "5.0(1)");
+        assert_eq!(value.as_f64(), Some(5.0));
+        assert_eq!(
+            &text[item.span().unwrap().range()],
+            "_cell_length_a  5.0(1)"
+        );
+        let loop_columns = block["atom_site_label"].as_multi_values().unwrap();
+        let loop_span = loop_columns.span().unwrap();
+        assert_eq!((loop_span.start().line(), loop_span.end().line()), (3, 6));
+        let labels = &loop_columns.columns()[0];
+        let o1 = labels.spanned_values()[1].span().unwrap().start();
+        assert_eq!((o1.offset(), o1.line(), o1.column()), (59, 6, 3));
+        assert!(labels.clone().into_owned().tag().span().is_some());
+    }
+
+    #[test]
+    fn value_spans_after_blanks() {
+        let text = "data_a\n_z  ;b\n";
+        let document = CifDocument::parse_from_str(&mut &text[..]).unwrap();
+        let block = &document.data_blocks().unwrap()[0];
+        let z = block.find_single_value_by_tag("z").unwrap().spanned_value();
+        assert_eq!(z.text(), ";b");
+        assert_eq!(z.span().unwrap().range(), 11..13);
+
+        let text = "data_a\nloop_\n_x\n_y\n1 # note\n# comment\n  'c d'\n.\n;\ntext\n;\n";
+        let document = CifDocument::parse_from_str(&mut &text[..]).unwrap();
+        let block = &document.data_blocks().unwrap()[0];
+        let columns = block["x"].as_multi_values().unwrap();
+        let spanned: Vec<(String, &str)> = columns
+            .columns()
+            .iter()
+            .flat_map(|column| column.spanned_values())
+            .map(|value| (value.text(), &text[value.span().unwrap().range()]))
+            .collect();
+        assert_eq!(
+            spanned,
+            [
+                ("1".to_string(), "1"),
+                (".".to_string(), "."),
+                ("c d".to_string(), "'c d'"),
+                ("text".to_string(), ";\ntext\n;"),
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{peek, preceded, repeat_till, terminated},
@@ -9,42 +9,48 @@ use winnow::{
 use crate::grammar::{
     character_sets::{AnyPrintChar, DoubleQuote},
     whitespace_comments::WhiteSpace,
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone)]
-pub struct DoubleQuotedString {
-    content: String,
+pub struct DoubleQuotedString<'a> {
+    content: Cow<'a, str>,
 }
 
-impl AsRef<str> for DoubleQuotedString {
+impl AsRef<str> for DoubleQuotedString<'_> {
     #[inline]
     fn as_ref(&self) -> &str {
-        <String as AsRef<str>>::as_ref(&self.content)
+        &self.content
     }
 }
 
-impl Display for DoubleQuotedString {
+impl Display for DoubleQuotedString<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-impl DoubleQuotedString {
-    pub fn new(content: String) -> Self {
-        Self { content }
+impl<'a> DoubleQuotedString<'a> {
+    pub fn new(content: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            content: content.into(),
+        }
+    }
+
+    pub fn into_owned(self) -> DoubleQuotedString<'static> {
+        DoubleQuotedString::new(self.content.into_owned())
     }
 }
 
-impl SyntacticUnit for DoubleQuotedString {
+impl<'a> SyntacticUnit<'a> for DoubleQuotedString<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         preceded(
             DoubleQuote::parser.context(StrContext::Label("<single_quote> Open")),
-            repeat_till(
+            repeat_till::<_, _, (), _, _, _, _>(
                 0..,
                 AnyPrintChar::parser.context(StrContext::Label(
                     "AnyPrintChar, single quote not following white space",
@@ -52,7 +58,9 @@ impl SyntacticUnit for DoubleQuotedString {
                 terminated(DoubleQuote::parser, peek(WhiteSpace::parser))
                     .context(StrContext::Label("<single_quote><WhiteSpace>")),
             )
-            .map(|(s, _open_quote): (String, DoubleQuote)| s)
+            .take()
+            // Without the closing quote
+            .map(|s: &str| &s[..s.len() - 1])
             .context(StrContext::Label("AnyPrintChar")),
             // DoubleQuote::parser.context(StrContext::Label("<single_quote> Close")),
         )
@@ -100,7 +108,7 @@ mod test {
         //     // DoubleQuote::parser.context(StrContext::Label("<single_quote> Close")),
         // );
 
-        match DoubleQuotedString::parser(&mut input) {
+        match DoubleQuotedString::parse_str(&mut input) {
             // match parser.parse_next(&mut input) {
             Ok(s) => {
                 println!("{s}");
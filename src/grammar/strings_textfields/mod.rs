@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{alt, opt, preceded},
@@ -7,7 +7,9 @@ use winnow::{
 
 use self::unquoted_string::pure_unquoted;
 
-use super::{reserved_words::ReservedWords, whitespace_comments::WhiteSpace, SyntacticUnit, Value};
+use super::{
+    reserved_words::ReservedWords, whitespace_comments::WhiteSpace, Input, SyntacticUnit, Value,
+};
 
 mod double_quoted_string;
 mod single_quoted_string;
@@ -21,13 +23,23 @@ pub use single_quoted_string::SingleQuotedString;
 pub use unquoted_string::UnquotedString;
 
 #[derive(Debug, Clone)]
-pub enum CharString {
-    Unquoted(UnquotedString),
-    SingleQuoted(SingleQuotedString),
-    DoubleQuoted(DoubleQuotedString),
+pub enum CharString<'a> {
+    Unquoted(UnquotedString<'a>),
+    SingleQuoted(SingleQuotedString<'a>),
+    DoubleQuoted(DoubleQuotedString<'a>),
 }
 
-impl AsRef<str> for CharString {
+impl CharString<'_> {
+    pub fn into_owned(self) -> CharString<'static> {
+        match self {
+            CharString::Unquoted(u) => CharString::Unquoted(u.into_owned()),
+            CharString::SingleQuoted(s) => CharString::SingleQuoted(s.into_owned()),
+            CharString::DoubleQuoted(d) => CharString::DoubleQuoted(d.into_owned()),
+        }
+    }
+}
+
+impl AsRef<str> for CharString<'_> {
     fn as_ref(&self) -> &str {
         match self {
             CharString::Unquoted(u) => u.as_ref(),
@@ -37,12 +49,27 @@ impl AsRef<str> for CharString {
     }
 }
 
-impl SyntacticUnit for CharString {
+/// [`CharString::parser`] for input whose leading blanks were parsed
+/// already, which leaves no blank to start an [`UnquotedString`].
+pub(crate) fn char_string_after_blanks<'a>(
+    input: &mut Input<'a>,
+) -> winnow::prelude::PResult<CharString<'a>> {
+    alt((
+        SingleQuotedString::parser.map(CharString::SingleQuoted),
+        DoubleQuotedString::parser.map(CharString::DoubleQuoted),
+        pure_unquoted
+            .verify(|u| ReservedWords::not_reserved_words(u.as_ref()))
+            .map(CharString::Unquoted),
+    ))
+    .parse_next(input)
+}
+
+impl<'a> SyntacticUnit<'a> for CharString<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             preceded(opt(WhiteSpace::parser), SingleQuotedString::parser)
                 .map(CharString::SingleQuoted),
@@ -52,10 +79,7 @@ impl SyntacticUnit for CharString {
                 preceded(opt(WhiteSpace::parser), pure_unquoted),
                 UnquotedString::parser,
             ))
-            .verify(|u| {
-                let mut input = u.as_ref();
-                ReservedWords::not_reserved_words(&mut input)
-            })
+            .verify(|u| ReservedWords::not_reserved_words(u.as_ref()))
             .map(CharString::Unquoted),
         ))
         .parse_next(input)
@@ -70,21 +94,67 @@ impl SyntacticUnit for CharString {
     }
 }
 
-impl Display for CharString {
+impl Display for CharString<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-impl From<CharString> for Value {
-    fn from(value: CharString) -> Self {
+impl<'a> From<CharString<'a>> for Value<'a> {
+    fn from(value: CharString<'a>) -> Self {
         Value::CharString(value)
     }
 }
 
+/// Whether `quote` followed by a blank occurs in `value`, which would end a
+/// string delimited by `quote` early.
+pub(crate) fn closes_quote(value: &str, quote: char) -> bool {
+    value
+        .char_indices()
+        .any(|(i, c)| c == quote && value[i + 1..].starts_with(char::is_whitespace))
+}
+
+impl<'a> From<Cow<'a, str>> for CharString<'a> {
+    /// Wrap the content in quotes only when it cannot be written as an
+    /// `<UnquotedString>`: double quotes when it holds a `'`, as in
+    /// `"O'Brien et al"`, single quotes otherwise. Content with both quotes
+    /// followed by blanks, or several lines, needs a text field: see
+    /// [`Value::from_text`].
+    fn from(value: Cow<'a, str>) -> Self {
+        let lowercase = value.to_lowercase();
+        let needs_quotes = value.is_empty()
+            || value == "?"
+            || value == "."
+            || value.contains(char::is_whitespace)
+            || value.starts_with(['_', '\'', '"', '#', '$', ';', '[', ']'])
+            || ["data_", "save_", "loop_", "global_", "stop_"]
+                .iter()
+                .any(|word| lowercase.starts_with(word));
+        if needs_quotes && value.contains('\'') && !closes_quote(&value, '"') {
+            CharString::DoubleQuoted(DoubleQuotedString::new(value))
+        } else if needs_quotes {
+            CharString::SingleQuoted(SingleQuotedString::new(value))
+        } else {
+            CharString::Unquoted(UnquotedString::new(value))
+        }
+    }
+}
+
+impl From<String> for CharString<'_> {
+    fn from(value: String) -> Self {
+        CharString::from(Cow::Owned(value))
+    }
+}
+
+impl From<&str> for CharString<'_> {
+    fn from(value: &str) -> Self {
+        CharString::from(value.to_string())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::grammar::{strings_textfields::CharString, SyntacticUnit};
+    use crate::grammar::{strings_textfields::CharString, DataBlock, SyntacticUnit, Value};
 
     #[test]
     fn char_string() {
@@ -94,8 +164,29 @@ mod test {
 ";
         let mut input_3 = "_symmetry_cell_setting
 ";
-        dbg!(CharString::parser(&mut input).unwrap());
-        dbg!(CharString::parser(&mut input_2).unwrap());
-        dbg!(CharString::parser(&mut input_3).unwrap());
+        dbg!(CharString::parse_str(&mut input).unwrap());
+        dbg!(CharString::parse_str(&mut input_2).unwrap());
+        dbg!(CharString::parse_str(&mut input_3).unwrap());
+    }
+
+    #[test]
+    fn quoted_round_trip() {
+        for (text, delimiter) in [
+            ("iron oxide", '\''),
+            ("O'Brien et al", '"'),
+            ("O'Brien's \"best\" crystal", '\''),
+            ("students' \"best\" crystal", ';'),
+            ("first line\nsecond line", ';'),
+        ] {
+            let written = format!("data_a\n_title {}\n", Value::from_text(text));
+            let block = DataBlock::parse_str(&mut written.as_str()).unwrap();
+            let value = block.find_single_value_by_tag("title").unwrap().value();
+            assert_eq!(value.text(), text);
+            let opening = match delimiter {
+                ';' => "\n;".to_string(),
+                quote => format!(" {quote}"),
+            };
+            assert!(written.contains(&opening), "{written}");
+        }
     }
 }
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{peek, preceded, repeat_till, terminated},
@@ -9,44 +9,50 @@ use winnow::{
 use crate::grammar::{
     character_sets::{AnyPrintChar, SingleQuote},
     whitespace_comments::WhiteSpace,
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 use super::CharString;
 
 #[derive(Debug, Clone)]
-pub struct SingleQuotedString {
-    content: String,
+pub struct SingleQuotedString<'a> {
+    content: Cow<'a, str>,
 }
 
-impl AsRef<str> for SingleQuotedString {
+impl AsRef<str> for SingleQuotedString<'_> {
     #[inline]
     fn as_ref(&self) -> &str {
-        <String as AsRef<str>>::as_ref(&self.content)
+        &self.content
     }
 }
 
-impl Display for SingleQuotedString {
+impl Display for SingleQuotedString<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-impl SingleQuotedString {
-    pub fn new(content: String) -> Self {
-        Self { content }
+impl<'a> SingleQuotedString<'a> {
+    pub fn new(content: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            content: content.into(),
+        }
+    }
+
+    pub fn into_owned(self) -> SingleQuotedString<'static> {
+        SingleQuotedString::new(self.content.into_owned())
     }
 }
 
-impl SyntacticUnit for SingleQuotedString {
+impl<'a> SyntacticUnit<'a> for SingleQuotedString<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         preceded(
             SingleQuote::parser.context(StrContext::Label("<single_quote> Open")),
-            repeat_till(
+            repeat_till::<_, _, (), _, _, _, _>(
                 0..,
                 AnyPrintChar::parser.context(StrContext::Label(
                     "AnyPrintChar, single quote not following white space",
@@ -54,7 +60,9 @@ impl SyntacticUnit for SingleQuotedString {
                 terminated(SingleQuote::parser, peek(WhiteSpace::parser))
                     .context(StrContext::Label("<single_quote><WhiteSpace>")),
             )
-            .map(|(s, _open_quote): (String, SingleQuote)| s)
+            .take()
+            // Without the closing quote
+            .map(|s: &str| &s[..s.len() - 1])
             .context(StrContext::Label("AnyPrintChar")),
             // SingleQuote::parser.context(StrContext::Label("<single_quote> Close")),
         )
@@ -67,8 +75,8 @@ impl SyntacticUnit for SingleQuotedString {
     }
 }
 
-impl From<SingleQuotedString> for CharString {
-    fn from(value: SingleQuotedString) -> Self {
+impl<'a> From<SingleQuotedString<'a>> for CharString<'a> {
+    fn from(value: SingleQuotedString<'a>) -> Self {
         Self::SingleQuoted(value)
     }
 }
@@ -83,6 +91,7 @@ mod test {
 
     use crate::grammar::{
         character_sets::{AnyPrintChar, SingleQuote},
+        located,
         whitespace_comments::WhiteSpace,
         SyntacticUnit,
     };
@@ -96,7 +105,7 @@ mod test {
     fn single_quoted_string() {
         let mut input = "'f scans, and w scans with k offsets Mr. Evan's things'
 ";
-        let mut input_2 = "'C16 H38 N4 2+, C4 H4 O5 2-, 2C H4 O'
+        let input_2 = "'C16 H38 N4 2+, C4 H4 O5 2-, 2C H4 O'
 ";
         let mut parser = preceded(
             SingleQuote::parser.context(StrContext::Label("<single_quote> Open")),
@@ -112,7 +121,7 @@ mod test {
             // SingleQuote::parser.context(StrContext::Label("<single_quote> Close")),
         );
 
-        match SingleQuotedString::parser(&mut input) {
+        match SingleQuotedString::parse_str(&mut input) {
             // match parser.parse_next(&mut input) {
             Ok(s) => {
                 println!("{s}");
@@ -123,7 +132,7 @@ mod test {
                 dbg!(input);
             }
         }
-        match parser.parse_next(&mut input_2) {
+        match parser.parse_next(&mut located(input_2)) {
             // match parser.parse_next(&mut input) {
             Ok(s) => {
                 println!("{s}");
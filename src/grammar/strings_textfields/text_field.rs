@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{delimited, opt, preceded, repeat, terminated},
@@ -9,22 +9,22 @@ use winnow::{
 use crate::grammar::{
     character_sets::{AnyPrintChar, Eol, TextLeadChar},
     whitespace_comments::WhiteSpace,
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone)]
-pub struct SemiColonTextField {
-    lines: Vec<String>,
+pub struct SemiColonTextField<'a> {
+    lines: Vec<Cow<'a, str>>,
 }
 
-impl AsRef<Vec<String>> for SemiColonTextField {
-    fn as_ref(&self) -> &Vec<String> {
-        <Vec<String> as AsRef<Vec<String>>>::as_ref(&self.lines)
+impl<'a> AsRef<Vec<Cow<'a, str>>> for SemiColonTextField<'a> {
+    fn as_ref(&self) -> &Vec<Cow<'a, str>> {
+        &self.lines
     }
 }
 
-impl From<&SemiColonTextField> for String {
-    fn from(value: &SemiColonTextField) -> Self {
+impl From<&SemiColonTextField<'_>> for String {
+    fn from(value: &SemiColonTextField<'_>) -> Self {
         value.lines.join("\n")
     }
 }
@@ -34,27 +34,43 @@ impl From<&SemiColonTextField> for String {
 /// possible future extensions to text fields, in particular the possible
 /// introduction of a bracket-delimited text value.
 #[derive(Debug, Clone)]
-pub enum TextField {
-    SemiColonTextField(SemiColonTextField),
+pub enum TextField<'a> {
+    SemiColonTextField(SemiColonTextField<'a>),
 }
 
-impl TextField {
+impl TextField<'_> {
     pub fn raw_string(&self) -> String {
         match self {
             TextField::SemiColonTextField(sm) => sm.lines.join("\n"),
         }
     }
+
+    pub fn into_owned(self) -> TextField<'static> {
+        match self {
+            TextField::SemiColonTextField(sm) => TextField::SemiColonTextField(sm.into_owned()),
+        }
+    }
 }
 
-impl From<&TextField> for String {
-    fn from(value: &TextField) -> Self {
+impl From<&TextField<'_>> for String {
+    fn from(value: &TextField<'_>) -> Self {
         match value {
             TextField::SemiColonTextField(s) => s.into(),
         }
     }
 }
 
-impl Display for TextField {
+impl From<&str> for TextField<'_> {
+    /// Text on the lines following the opening semicolon.
+    fn from(value: &str) -> Self {
+        let lines = std::iter::once(Cow::Borrowed(""))
+            .chain(value.lines().map(|line| Cow::Owned(line.to_string())))
+            .collect();
+        TextField::SemiColonTextField(SemiColonTextField::new(lines))
+    }
+}
+
+impl Display for TextField<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TextField::SemiColonTextField(t) => write!(f, "{t}"),
@@ -62,18 +78,27 @@ impl Display for TextField {
     }
 }
 
-impl SemiColonTextField {
-    pub fn new(lines: Vec<String>) -> Self {
+impl<'a> SemiColonTextField<'a> {
+    pub fn new(lines: Vec<Cow<'a, str>>) -> Self {
         Self { lines }
     }
+
+    pub fn into_owned(self) -> SemiColonTextField<'static> {
+        SemiColonTextField::new(
+            self.lines
+                .into_iter()
+                .map(|line| Cow::Owned(line.into_owned()))
+                .collect(),
+        )
+    }
 }
 
-impl SyntacticUnit for SemiColonTextField {
+impl<'a> SyntacticUnit<'a> for SemiColonTextField<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         preceded(
             opt(WhiteSpace::parser),
             delimited(
@@ -92,27 +117,33 @@ impl SyntacticUnit for SemiColonTextField {
     }
 }
 
-impl Display for SemiColonTextField {
+impl Display for SemiColonTextField<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-fn semicolon_text_field(input: &mut &str) -> PResult<Vec<String>> {
+fn semicolon_text_field<'a>(input: &mut Input<'a>) -> PResult<Vec<Cow<'a, str>>> {
     (
-        terminated(repeat(0.., AnyPrintChar::parser), Eol::parser)
-            .context(StrContext::Label("{<AnyPrintChar>}*<eol>")),
+        terminated(
+            repeat::<_, _, (), _, _>(0.., AnyPrintChar::parser).take(),
+            Eol::parser,
+        )
+        .context(StrContext::Label("{<AnyPrintChar>}*<eol>")),
         repeat(
             0..,
             terminated(
-                repeat(
+                repeat::<_, _, (), _, _>(
                     0..=1,
-                    (TextLeadChar::parser, repeat(0.., AnyPrintChar::parser))
-                        .context(StrContext::Label("<TextLeadChar>{<AnyPrintChar>}*"))
-                        .map(|(lead, follows): (TextLeadChar, String)| format!("{lead}{follows}")),
+                    (
+                        TextLeadChar::parser,
+                        repeat::<_, _, (), _, _>(0.., AnyPrintChar::parser),
+                    )
+                        .context(StrContext::Label("<TextLeadChar>{<AnyPrintChar>}*")),
                 )
+                .take()
                 .context(StrContext::Label("{<TextLeadChar>{<AnyPrintChar>}*}?"))
-                .map(|line: Vec<String>| line.concat()),
+                .map(Cow::Borrowed),
                 Eol::parser.context(StrContext::Label("Each line eol")),
             )
             .context(StrContext::Label("{<TextLeadChar>{<AnyPrintChar>}*}?<eol>")),
@@ -121,16 +152,19 @@ fn semicolon_text_field(input: &mut &str) -> PResult<Vec<String>> {
             "{{<TextLeadChar>{<AnyPrintChar>}*}?<eol>}*",
         )),
     )
-        .map(|(first_line, following)| [vec![first_line], following].concat())
+        .map(|(first_line, mut following): (&str, Vec<Cow<str>>)| {
+            following.insert(0, Cow::Borrowed(first_line));
+            following
+        })
         .parse_next(input)
 }
 
-impl SyntacticUnit for TextField {
+impl<'a> SyntacticUnit<'a> for TextField<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> PResult<Self::ParseResult> {
         SemiColonTextField::parser
             .map(TextField::SemiColonTextField)
             .parse_next(input)
@@ -167,7 +201,7 @@ PROBLEM: _A ADDSYM Suggests Possible Pseudo/New Spacegroup       P-1
 RESPONSE: .See above
 ;
 "#;
-        match SemiColonTextField::parser(&mut test) {
+        match SemiColonTextField::parse_str(&mut test) {
             Ok(t) => println!("{t}"),
             Err(e) => {
                 dbg!(e);
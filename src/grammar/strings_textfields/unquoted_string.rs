@@ -1,83 +1,95 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{alt, repeat},
     error::StrContext,
-    stream::AsChar,
     PResult, Parser,
 };
 
 use crate::grammar::{
     character_sets::{Eol, NonBlankChar, NotEol, OrdinaryChar},
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone)]
-pub struct UnquotedString {
-    content: String,
+pub struct UnquotedString<'a> {
+    content: Cow<'a, str>,
 }
 
-impl AsRef<str> for UnquotedString {
+impl AsRef<str> for UnquotedString<'_> {
     #[inline]
     fn as_ref(&self) -> &str {
-        <String as AsRef<str>>::as_ref(&self.content)
+        &self.content
     }
 }
 
-impl UnquotedString {
-    pub fn new(content: String) -> Self {
-        Self { content }
+impl<'a> UnquotedString<'a> {
+    pub fn new(content: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            content: content.into(),
+        }
+    }
+
+    pub fn into_owned(self) -> UnquotedString<'static> {
+        UnquotedString::new(self.content.into_owned())
     }
 }
 
-fn not_eol_unquoted(input: &mut &str) -> PResult<UnquotedString> {
+fn not_eol_unquoted<'a>(input: &mut Input<'a>) -> PResult<UnquotedString<'a>> {
     (
         NotEol::parser
             .verify(|c| c.char() != '_')
             .context(winnow::error::StrContext::Expected(
                 winnow::error::StrContextValue::Description("Unquoted string without '_' prefix"),
             )),
-        alt((OrdinaryChar::parser.map(|oc| oc.as_char()), ';')),
-        repeat::<_, _, String, _, _>(0.., NonBlankChar::parser),
+        (
+            alt((OrdinaryChar::parser.void(), ';'.void())),
+            repeat::<_, _, (), _, _>(0.., NonBlankChar::parser),
+        )
+            .take(),
     )
-        .map(|(_not_eol, c, content)| UnquotedString::new(format!("{c}{content}")))
+        .map(|(_not_eol, content)| UnquotedString::new(content))
         .parse_next(input)
 }
 
-fn eol_unquoted(input: &mut &str) -> PResult<UnquotedString> {
+fn eol_unquoted<'a>(input: &mut Input<'a>) -> PResult<UnquotedString<'a>> {
     (
         Eol::parser.context(StrContext::Label("Leading <EOL> for an <UnquotedString>")),
-        OrdinaryChar::parser.context(StrContext::Label("<OrdinaryChar>")),
-        repeat::<_, _, String, _, _>(0.., NonBlankChar::parser),
+        (
+            OrdinaryChar::parser.context(StrContext::Label("<OrdinaryChar>")),
+            repeat::<_, _, (), _, _>(0.., NonBlankChar::parser),
+        )
+            .take(),
     )
-        .map(|(_eol, oc, content)| UnquotedString::new(format!("{oc}{content}")))
+        .map(|(_eol, content)| UnquotedString::new(content))
         .parse_next(input)
 }
 
-pub fn pure_unquoted(input: &mut &str) -> PResult<UnquotedString> {
+pub fn pure_unquoted<'a>(input: &mut Input<'a>) -> PResult<UnquotedString<'a>> {
     (
         OrdinaryChar::parser.context(StrContext::Label("<OrdinaryChar>")),
-        repeat::<_, _, String, _, _>(0.., NonBlankChar::parser),
+        repeat::<_, _, (), _, _>(0.., NonBlankChar::parser),
     )
-        .map(|(oc, content)| UnquotedString::new(format!("{oc}{content}")))
+        .take()
+        .map(UnquotedString::new)
         .parse_next(input)
 }
 
-impl SyntacticUnit for UnquotedString {
+impl<'a> SyntacticUnit<'a> for UnquotedString<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((not_eol_unquoted, eol_unquoted)).parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
-        self.content.to_owned()
+        self.content.to_string()
     }
 }
 
-impl Display for UnquotedString {
+impl Display for UnquotedString<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
@@ -93,8 +105,8 @@ mod test {
     fn unquoted_string() {
         let mut input = " rm # known chiral centre
 ";
-        let value = UnquotedString::parser(&mut input).unwrap();
-        let white_space = WhiteSpace::parser(&mut input).unwrap();
+        let value = UnquotedString::parse_str(&mut input).unwrap();
+        let white_space = WhiteSpace::parse_str(&mut input).unwrap();
         println!("{value}{white_space}");
     }
 }
@@ -1,27 +1,53 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{preceded, repeat},
     Parser,
 };
 
-use crate::grammar::{character_sets::NonBlankChar, reserved_words::Data, SyntacticUnit};
+use crate::grammar::{
+    character_sets::NonBlankChar,
+    reserved_words::Data,
+    span::{spanned, Span},
+    Input, SyntacticUnit,
+};
 
 #[derive(Debug, Clone)]
-pub struct DataBlockHeading {
-    name: String,
+pub struct DataBlockHeading<'a> {
+    name: Cow<'a, str>,
+    span: Option<Span>,
 }
 
-impl AsRef<str> for DataBlockHeading {
+impl AsRef<str> for DataBlockHeading<'_> {
     #[inline]
     fn as_ref(&self) -> &str {
-        <String as AsRef<str>>::as_ref(&self.name)
+        &self.name
     }
 }
 
-impl DataBlockHeading {
-    pub fn new(name: String) -> Self {
-        Self { name }
+impl<'a> DataBlockHeading<'a> {
+    pub fn new(name: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            name: name.into(),
+            span: None,
+        }
+    }
+
+    pub(crate) fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Where the heading was parsed from, `None` when built in code.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn into_owned(self) -> DataBlockHeading<'static> {
+        DataBlockHeading {
+            name: Cow::Owned(self.name.into_owned()),
+            span: self.span,
+        }
     }
 
     pub fn name(&self) -> &str {
@@ -29,15 +55,18 @@ impl DataBlockHeading {
     }
 }
 
-impl SyntacticUnit for DataBlockHeading {
+impl<'a> SyntacticUnit<'a> for DataBlockHeading<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
-        preceded(Data::parser, repeat(1.., NonBlankChar::parser))
-            .map(DataBlockHeading::new)
-            .parse_next(input)
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
+        spanned(preceded(
+            Data::parser,
+            repeat::<_, _, (), _, _>(1.., NonBlankChar::parser).take(),
+        ))
+        .map(|(name, span)| DataBlockHeading::new(name).with_span(span))
+        .parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
@@ -45,7 +74,7 @@ impl SyntacticUnit for DataBlockHeading {
     }
 }
 
-impl Display for DataBlockHeading {
+impl Display for DataBlockHeading<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
@@ -4,28 +4,37 @@ use winnow::{combinator::alt, error::StrContext, Parser};
 
 use crate::grammar::{
     structures::{data_items::DataItems, save_frame::SaveFrame},
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone)]
-pub enum DataBlockMember {
-    DataItems(DataItems),
-    SaveFrame(SaveFrame),
+pub enum DataBlockMember<'a> {
+    DataItems(DataItems<'a>),
+    SaveFrame(SaveFrame<'a>),
 }
 
-impl SyntacticUnit for DataBlockMember {
+impl DataBlockMember<'_> {
+    pub fn into_owned(self) -> DataBlockMember<'static> {
+        match self {
+            DataBlockMember::DataItems(d) => DataBlockMember::DataItems(d.into_owned()),
+            DataBlockMember::SaveFrame(s) => DataBlockMember::SaveFrame(s.into_owned()),
+        }
+    }
+}
+
+impl<'a> SyntacticUnit<'a> for DataBlockMember<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             DataItems::parser
                 .map(Self::DataItems)
-                .context(StrContext::Label("DataItems")),
+                .context(StrContext::Label("DataItems<'a>")),
             SaveFrame::parser
                 .map(Self::SaveFrame)
-                .context(StrContext::Label("SaveFrame")),
+                .context(StrContext::Label("SaveFrame<'a>")),
         ))
         .parse_next(input)
     }
@@ -38,7 +47,7 @@ impl SyntacticUnit for DataBlockMember {
     }
 }
 
-impl Display for DataBlockMember {
+impl Display for DataBlockMember<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
@@ -6,7 +6,7 @@ use winnow::{
 };
 
 use crate::{
-    grammar::{tags_values::Value, whitespace_comments::WhiteSpace, SyntacticUnit},
+    grammar::{tags_values::Value, whitespace_comments::WhiteSpace, Input, SyntacticUnit},
     LoopColumn,
 };
 
@@ -18,18 +18,20 @@ mod heading;
 mod members;
 
 #[derive(Debug, Clone)]
-pub struct DataBlock {
-    heading: DataBlockHeading,
-    members: Vec<DataBlockMember>,
+pub struct DataBlock<'a> {
+    heading: DataBlockHeading<'a>,
+    members: Vec<DataBlockMember<'a>>,
 }
 
-impl DataBlock {
-    pub fn from_heading_members(heading_member: (DataBlockHeading, Vec<DataBlockMember>)) -> Self {
+impl<'a> DataBlock<'a> {
+    pub fn from_heading_members(
+        heading_member: (DataBlockHeading<'a>, Vec<DataBlockMember<'a>>),
+    ) -> Self {
         let (heading, members) = heading_member;
         Self { heading, members }
     }
 
-    pub fn find_loop_column_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<LoopColumn> {
+    pub fn find_loop_column_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<LoopColumn<'a>> {
         self.members.iter().find_map(|member| {
             if let DataBlockMember::DataItems(data_item) = member {
                 data_item.get_loop_column_values_by_tag(&tag)
@@ -39,7 +41,7 @@ impl DataBlock {
         })
     }
 
-    pub fn find_single_value_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<&SingleLineData> {
+    pub fn find_single_value_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<&SingleLineData<'a>> {
         self.members.iter().find_map(|member| {
             if let DataBlockMember::DataItems(data_item) = member {
                 data_item.get_single_value_by_tag(&tag)
@@ -53,21 +55,33 @@ impl DataBlock {
         self.heading.as_ref()
     }
 
-    pub fn members(&self) -> &[DataBlockMember] {
+    pub fn members(&self) -> &[DataBlockMember<'a>] {
         &self.members
     }
 
-    pub fn members_mut(&mut self) -> &mut Vec<DataBlockMember> {
+    pub fn members_mut(&mut self) -> &mut Vec<DataBlockMember<'a>> {
         &mut self.members
     }
+
+    /// A copy not borrowing from the parsed input, to keep or edit after
+    /// the input is dropped.
+    pub fn into_owned(self) -> DataBlock<'static> {
+        DataBlock::from_heading_members((
+            self.heading.into_owned(),
+            self.members
+                .into_iter()
+                .map(DataBlockMember::into_owned)
+                .collect(),
+        ))
+    }
 }
 
-impl SyntacticUnit for DataBlock {
+impl<'a> SyntacticUnit<'a> for DataBlock<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         (
             DataBlockHeading::parser,
             repeat(0.., preceded(WhiteSpace::parser, DataBlockMember::parser)),
@@ -87,7 +101,7 @@ impl SyntacticUnit for DataBlock {
     }
 }
 
-impl Display for DataBlock {
+impl Display for DataBlock<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
@@ -101,6 +115,7 @@ mod test {
     };
 
     use crate::grammar::{
+        located,
         structures::{
             data_block::members::DataBlockMember, data_items::DataItems,
             tag_value_line::SingleLineData,
@@ -765,7 +780,7 @@ _geom_hbond_publ_flag
 #                         END of CIF
 ####################################################################
 "#;
-        let parse_result = DataBlock::parser(&mut input);
+        let parse_result = DataBlock::parse_str(&mut input);
         match parse_result {
             Ok(d) => {
                 println!(
@@ -782,9 +797,33 @@ _geom_hbond_publ_flag
                 println!("{e}");
                 println!(
                     "{:?}",
-                    preceded(WhiteSpace::parser, SingleLineData::parser).parse_next(&mut input)
+                    preceded(WhiteSpace::parser, SingleLineData::parser)
+                        .parse_next(&mut located(input))
                 );
             }
         }
     }
+
+    #[test]
+    fn borrowed_parse() {
+        let content = String::from("data_I\n_chemical_name_common 'iron oxide'\n");
+        let block = DataBlock::parse_str(&mut content.as_str()).unwrap();
+        let within = |s: &str| content.as_bytes().as_ptr_range().contains(&s.as_ptr());
+        assert!(within(block.heading()));
+        let item = block
+            .find_single_value_by_tag("chemical_name_common")
+            .unwrap();
+        assert!(within(item.tag().as_str()));
+        let owned = block.into_owned();
+        drop(content);
+        assert_eq!(owned.heading(), "I");
+        assert_eq!(
+            owned
+                .find_single_value_by_tag("chemical_name_common")
+                .unwrap()
+                .value()
+                .to_string(),
+            "'iron oxide'"
+        );
+    }
 }
@@ -3,7 +3,7 @@ use std::fmt::Display;
 
 use winnow::{combinator::alt, error::StrContext, Parser};
 
-use crate::grammar::SyntacticUnit;
+use crate::grammar::{Input, SyntacticUnit};
 
 use super::{
     loop_struct::{LoopColumns, LoopUnit},
@@ -11,18 +11,18 @@ use super::{
 };
 
 #[derive(Debug, Clone)]
-pub enum DataItems {
-    SingleValue(SingleLineData),
-    MultiValues(LoopColumns),
+pub enum DataItems<'a> {
+    SingleValue(SingleLineData<'a>),
+    MultiValues(LoopColumns<'a>),
 }
 
 pub trait CIFDataType {}
 
-impl CIFDataType for SingleLineData {}
-impl CIFDataType for LoopUnit {}
+impl CIFDataType for SingleLineData<'_> {}
+impl CIFDataType for LoopUnit<'_> {}
 
-impl DataItems {
-    pub fn get_single_value_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<&SingleLineData> {
+impl<'a> DataItems<'a> {
+    pub fn get_single_value_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<&SingleLineData<'a>> {
         match self {
             DataItems::SingleValue(tv) => {
                 if tag.as_ref() == tv.tag().as_ref() {
@@ -35,7 +35,7 @@ impl DataItems {
         }
     }
 
-    pub fn get_loop_column_values_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<LoopColumn> {
+    pub fn get_loop_column_values_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<LoopColumn<'a>> {
         if let DataItems::MultiValues(loop_unit) = self {
             loop_unit.find_loop_column_by_tag(tag).cloned()
         } else {
@@ -43,7 +43,7 @@ impl DataItems {
         }
     }
 
-    pub fn as_single_value(&self) -> Option<&SingleLineData> {
+    pub fn as_single_value(&self) -> Option<&SingleLineData<'a>> {
         if let Self::SingleValue(v) = self {
             Some(v)
         } else {
@@ -51,21 +51,28 @@ impl DataItems {
         }
     }
 
-    pub fn as_multi_values(&self) -> Option<&LoopColumns> {
+    pub fn as_multi_values(&self) -> Option<&LoopColumns<'a>> {
         if let Self::MultiValues(v) = self {
             Some(v)
         } else {
             None
         }
     }
+
+    pub fn into_owned(self) -> DataItems<'static> {
+        match self {
+            DataItems::SingleValue(v) => DataItems::SingleValue(v.into_owned()),
+            DataItems::MultiValues(v) => DataItems::MultiValues(v.into_owned()),
+        }
+    }
 }
 
-impl SyntacticUnit for DataItems {
+impl<'a> SyntacticUnit<'a> for DataItems<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             SingleLineData::parser
                 .map(DataItems::SingleValue)
@@ -86,7 +93,7 @@ impl SyntacticUnit for DataItems {
     }
 }
 
-impl Display for DataItems {
+impl Display for DataItems<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
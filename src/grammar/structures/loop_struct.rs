@@ -4,7 +4,11 @@ use winnow::Parser;
 
 use crate::{
     data_dict::CifTerm,
-    grammar::{tags_values::Value, SyntacticUnit, Tag},
+    grammar::{
+        span::{spanned, Span},
+        tags_values::Value,
+        Input, SyntacticUnit, Tag,
+    },
 };
 
 pub use self::{body::LoopBody, header::LoopHeader};
@@ -15,30 +19,32 @@ mod body;
 mod header;
 
 #[derive(Debug, Clone)]
-pub struct LoopUnit {
-    header: LoopHeader,
-    body: LoopBody,
+pub struct LoopUnit<'a> {
+    header: LoopHeader<'a>,
+    body: LoopBody<'a>,
+    span: Option<Span>,
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct LoopUnitBuilder {
-    value_columns: Option<Vec<LoopColumn>>,
+pub struct LoopUnitBuilder<'a> {
+    value_columns: Option<Vec<LoopColumn<'a>>>,
     column_length: usize,
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct LoopColumns {
-    columns: Vec<LoopColumn>,
+pub struct LoopColumns<'a> {
+    columns: Vec<LoopColumn<'a>>,
+    span: Option<Span>,
 }
 
-impl LoopUnitBuilder {
-    pub fn with_value_columns(mut self, value_columns: Vec<LoopColumn>) -> Self {
+impl<'a> LoopUnitBuilder<'a> {
+    pub fn with_value_columns(mut self, value_columns: Vec<LoopColumn<'a>>) -> Self {
         self.column_length = value_columns[0].as_ref().len();
         self.value_columns = Some(value_columns);
         self
     }
 
-    pub fn build(self) -> LoopUnit {
+    pub fn build(self) -> LoopUnit<'a> {
         let header = self
             .value_columns
             .as_ref()
@@ -46,7 +52,7 @@ impl LoopUnitBuilder {
                 columns
                     .iter()
                     .map(|c| c.tag().clone())
-                    .collect::<Vec<Tag>>()
+                    .collect::<Vec<Tag<'a>>>()
             })
             .unwrap_or_default();
         let header = LoopHeader::new(header);
@@ -55,20 +61,30 @@ impl LoopUnitBuilder {
     }
 }
 
-impl LoopUnit {
-    pub fn new(header: LoopHeader, body: LoopBody) -> Self {
-        Self { header, body }
+impl<'a> LoopUnit<'a> {
+    pub fn new(header: LoopHeader<'a>, body: LoopBody<'a>) -> Self {
+        Self {
+            header,
+            body,
+            span: None,
+        }
     }
 
-    pub fn builder() -> LoopUnitBuilder {
+    pub fn builder() -> LoopUnitBuilder<'a> {
         LoopUnitBuilder::default()
     }
 
-    pub fn header(&self) -> &LoopHeader {
+    pub fn header(&self) -> &LoopHeader<'a> {
         &self.header
     }
 
-    pub fn find_loop_column_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<LoopColumn> {
+    /// Where the loop was parsed from, from `loop_` to its last value;
+    /// `None` when built in code.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn find_loop_column_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<LoopColumn<'a>> {
         self.header.get_tag_index(&tag).map(|index| {
             let values = self
                 .body
@@ -78,22 +94,30 @@ impl LoopUnit {
     }
 }
 
-impl SyntacticUnit for LoopUnit {
+impl<'a> SyntacticUnit<'a> for LoopUnit<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
-        (LoopHeader::parser, LoopBody::parser)
-            .map(|(header, body)| LoopUnit::new(header, body))
-            .parse_next(input)
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
+        spanned(|input: &mut Input<'a>| {
+            let header = LoopHeader::parser(input)?;
+            let body = LoopBody::rows_parser(input, header.num_of_tags())?;
+            Ok((header, body))
+        })
+        .map(|((header, body), span)| LoopUnit {
+            header,
+            body,
+            span: Some(span),
+        })
+        .parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
         let body_output = self
             .body
             .values()
-            .chunks(self.header.num_of_tags())
+            .chunks_exact(self.header.num_of_tags())
             .map(|chunk| {
                 chunk
                     .iter()
@@ -107,14 +131,26 @@ impl SyntacticUnit for LoopUnit {
     }
 }
 
-impl Display for LoopUnit {
+impl Display for LoopUnit<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-impl LoopColumns {
-    pub fn find_loop_column_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<&LoopColumn> {
+impl<'a> LoopColumns<'a> {
+    pub fn new(columns: Vec<LoopColumn<'a>>) -> Self {
+        Self {
+            columns,
+            span: None,
+        }
+    }
+
+    /// The span of the [`LoopUnit`] the columns were parsed from.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn find_loop_column_by_tag<T: AsRef<str>>(&self, tag: T) -> Option<&LoopColumn<'a>> {
         self.columns
             .iter()
             .find(|col| col.tag().as_ref() == tag.as_ref())
@@ -123,39 +159,50 @@ impl LoopColumns {
     pub fn find_loop_column_mut_by_tag<T: AsRef<str>>(
         &mut self,
         tag: T,
-    ) -> Option<&mut LoopColumn> {
+    ) -> Option<&mut LoopColumn<'a>> {
         self.columns_mut()
             .iter_mut()
             .find(|col| col.tag().as_ref() == tag.as_ref())
     }
 
-    pub fn columns(&self) -> &[LoopColumn] {
+    pub fn columns(&self) -> &[LoopColumn<'a>] {
         &self.columns
     }
 
-    pub fn columns_mut(&mut self) -> &mut Vec<LoopColumn> {
+    pub fn columns_mut(&mut self) -> &mut Vec<LoopColumn<'a>> {
         &mut self.columns
     }
+
+    pub fn into_owned(self) -> LoopColumns<'static> {
+        LoopColumns {
+            columns: self
+                .columns
+                .into_iter()
+                .map(LoopColumn::into_owned)
+                .collect(),
+            span: self.span,
+        }
+    }
 }
 
-impl From<LoopColumns> for LoopUnit {
-    fn from(value: LoopColumns) -> Self {
+impl<'a> From<LoopColumns<'a>> for LoopUnit<'a> {
+    fn from(value: LoopColumns<'a>) -> Self {
         LoopUnit::builder()
             .with_value_columns(value.columns)
             .build()
     }
 }
 
-impl From<&LoopColumns> for LoopUnit {
-    fn from(value: &LoopColumns) -> Self {
+impl<'a> From<&LoopColumns<'a>> for LoopUnit<'a> {
+    fn from(value: &LoopColumns<'a>) -> Self {
         LoopUnit::builder()
             .with_value_columns(value.columns.clone())
             .build()
     }
 }
 
-impl From<LoopUnit> for LoopColumns {
-    fn from(value: LoopUnit) -> Self {
+impl<'a> From<LoopUnit<'a>> for LoopColumns<'a> {
+    fn from(value: LoopUnit<'a>) -> Self {
         let column_width = value.header().tags().len();
         LoopColumns {
             columns: value
@@ -165,8 +212,10 @@ impl From<LoopUnit> for LoopColumns {
                 .enumerate()
                 .map(|(i, tag)| {
                     LoopColumn::new(tag.clone(), value.body.nth_column_values(i, column_width))
+                        .with_value_spans(value.body.nth_column_spans(i, column_width))
                 })
-                .collect::<Vec<LoopColumn>>(),
+                .collect::<Vec<LoopColumn<'a>>>(),
+            span: value.span,
         }
     }
 }
@@ -198,7 +247,7 @@ _symmetry_cell_setting            triclinic
 _symmetry_space_group_name_H-M    'P 1'
 _symmetry_space_group_name_Hall   'P 1'
 "#;
-        let parse_result = LoopUnit::parser(&mut input);
+        let parse_result = LoopUnit::parse_str(&mut input);
         match parse_result {
             Ok(l) => {
                 l.body.values().iter().for_each(|v| println!("{v:?}"));
@@ -1,35 +1,104 @@
 use winnow::{
     combinator::{preceded, repeat},
+    error::{AddContext, ContextError, ErrMode, StrContext},
+    stream::Stream,
     Parser,
 };
 
 use crate::{
     data_dict::{CifTerm, LoopValueTerm},
-    grammar::{tags_values::Value, whitespace_comments::WhiteSpace, SyntacticUnit, Tag},
+    grammar::{
+        span::{Span, Spanned},
+        tags_values::{spanned_value, Value},
+        whitespace_comments::WhiteSpace,
+        Input, SyntacticUnit, Tag,
+    },
 };
 
 #[derive(Debug, Clone)]
-pub struct LoopBody {
-    values: Vec<Value>,
+pub struct LoopBody<'a> {
+    values: Vec<Value<'a>>,
+    /// One for each value when parsed.
+    spans: Vec<Span>,
 }
 
-impl LoopBody {
-    pub fn new(values: Vec<Value>) -> Self {
-        Self { values }
+impl<'a> LoopBody<'a> {
+    pub fn new(values: Vec<Value<'a>>) -> Self {
+        Self {
+            values,
+            spans: Vec::new(),
+        }
     }
 
-    pub fn values(&self) -> &[Value] {
+    pub fn values(&self) -> &[Value<'a>] {
         &self.values
     }
 
     /// Get nth column value, suitable for viewing data of the same tag in the loop.
-    pub fn nth_column_values(&self, nth: usize, column_width: usize) -> Vec<Value> {
+    /// Values of an incomplete last row are left out.
+    pub fn nth_column_values(&self, nth: usize, column_width: usize) -> Vec<Value<'a>> {
         self.values
-            .chunks(column_width)
+            .chunks_exact(column_width)
             .map(|chunk| chunk[nth].clone())
             .collect()
     }
 
+    /// The spans of the nth column values, empty when the body was not
+    /// parsed.
+    pub fn nth_column_spans(&self, nth: usize, column_width: usize) -> Vec<Span> {
+        self.spans
+            .chunks_exact(column_width)
+            .map(|chunk| chunk[nth])
+            .collect()
+    }
+
+    /// Parse the values of complete rows of `column_width` values. The
+    /// values of a last row which breaks off are left in the input; a body
+    /// without a complete row fails.
+    pub(crate) fn rows_parser(
+        input: &mut Input<'a>,
+        column_width: usize,
+    ) -> winnow::prelude::PResult<Self> {
+        let column_width = column_width.max(1);
+        let mut body = LoopBody::new(Vec::new());
+        let mut complete = input.checkpoint();
+        loop {
+            let before = input.checkpoint();
+            let value = if body.values.is_empty() {
+                spanned_value.parse_next(input)
+            } else {
+                preceded(WhiteSpace::parser, spanned_value).parse_next(input)
+            };
+            match value {
+                Ok(value) => {
+                    body.spans
+                        .push(value.span().expect("parsed values have a span"));
+                    body.values.push(value.into_node());
+                    if body.values.len().is_multiple_of(column_width) {
+                        complete = input.checkpoint();
+                    }
+                }
+                Err(ErrMode::Backtrack(_)) => {
+                    input.reset(&before);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        let length = body.values.len() / column_width * column_width;
+        if length == 0 {
+            return Err(ErrMode::Backtrack(ContextError::new().add_context(
+                input,
+                &input.checkpoint(),
+                StrContext::Label("loop row"),
+            )));
+        }
+        body.values.truncate(length);
+        body.spans.truncate(length);
+        input.reset(&complete);
+        Ok(body)
+    }
+
     /// Create `LoopBody` from a uniform array of `Vec<Value>` columns.
     pub fn from_columns<T: LoopValueTerm>(columns: &[T], column_length: usize) -> Self {
         Self::new(
@@ -38,27 +107,32 @@ impl LoopBody {
                     columns
                         .iter()
                         .map(|c| c.values()[i].clone())
-                        .collect::<Vec<Value>>()
+                        .collect::<Vec<Value<'a>>>()
                 })
-                .collect::<Vec<Value>>(),
+                .collect::<Vec<Value<'a>>>(),
         )
     }
 }
 
-impl SyntacticUnit for LoopBody {
+impl<'a> SyntacticUnit<'a> for LoopBody<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         (
-            Value::parser,
-            repeat(0.., preceded(WhiteSpace::parser, Value::parser)),
+            spanned_value,
+            repeat(0.., preceded(WhiteSpace::parser, spanned_value)),
         )
-            .map(|(first, following): (Value, Vec<Value>)| {
-                let mut values = vec![first];
-                values.extend(following);
-                LoopBody::new(values)
+            .map(|(first, following): (Spanned<Value<'a>>, Vec<_>)| {
+                let (values, spans) = std::iter::once(first)
+                    .chain(following)
+                    .map(|value| {
+                        let span = value.span().expect("parsed values have a span");
+                        (value.into_node(), span)
+                    })
+                    .unzip();
+                LoopBody { values, spans }
             })
             .parse_next(input)
     }
@@ -73,47 +147,78 @@ impl SyntacticUnit for LoopBody {
 }
 
 #[derive(Debug, Clone)]
-pub struct LoopColumn {
-    tag: Tag,
-    values: Vec<Value>,
+pub struct LoopColumn<'a> {
+    tag: Tag<'a>,
+    values: Vec<Value<'a>>,
+    value_spans: Vec<Span>,
 }
 
-impl AsRef<[Value]> for LoopColumn {
-    fn as_ref(&self) -> &[Value] {
-        <Vec<Value> as AsRef<[Value]>>::as_ref(&self.values)
+impl<'a> AsRef<[Value<'a>]> for LoopColumn<'a> {
+    fn as_ref(&self) -> &[Value<'a>] {
+        &self.values
     }
 }
 
-impl LoopColumn {
-    pub fn new(tag: Tag, values: Vec<Value>) -> Self {
-        Self { tag, values }
+impl<'a> LoopColumn<'a> {
+    pub fn new(tag: Tag<'a>, values: Vec<Value<'a>>) -> Self {
+        Self {
+            tag,
+            values,
+            value_spans: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_value_spans(mut self, value_spans: Vec<Span>) -> Self {
+        self.value_spans = value_spans;
+        self
     }
 
-    pub fn values(&self) -> &[Value] {
+    pub fn values(&self) -> &[Value<'a>] {
         &self.values
     }
 
-    pub fn tag(&self) -> &Tag {
+    pub fn tag(&self) -> &Tag<'a> {
         &self.tag
     }
 
-    pub fn tag_mut(&mut self) -> &mut Tag {
+    pub fn tag_mut(&mut self) -> &mut Tag<'a> {
         &mut self.tag
     }
 
-    pub fn values_mut(&mut self) -> &mut Vec<Value> {
+    pub fn values_mut(&mut self) -> &mut Vec<Value<'a>> {
         &mut self.values
     }
+
+    /// The values with where each was parsed from; the spans are `None`
+    /// when the column was built in code.
+    pub fn spanned_values(&self) -> Vec<Spanned<&Value<'a>>> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Spanned::with_span(value, self.value_spans.get(i).copied()))
+            .collect()
+    }
+
+    pub fn into_owned(self) -> LoopColumn<'static> {
+        LoopColumn::new(
+            self.tag.into_owned(),
+            self.values.into_iter().map(Value::into_owned).collect(),
+        )
+        .with_value_spans(self.value_spans)
+    }
 }
 
-impl CifTerm for LoopColumn {
-    fn tag(&self) -> Tag {
-        self.tag.clone()
+impl CifTerm for LoopColumn<'_> {
+    fn tag(&self) -> Tag<'static> {
+        self.tag.clone().into_owned()
     }
 }
 
-impl LoopValueTerm for LoopColumn {
-    fn values(&self) -> Vec<Value> {
-        self.values().to_vec()
+impl LoopValueTerm for LoopColumn<'_> {
+    fn values(&self) -> Vec<Value<'static>> {
+        self.values()
+            .iter()
+            .map(|v| v.clone().into_owned())
+            .collect()
     }
 }
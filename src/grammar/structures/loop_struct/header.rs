@@ -6,23 +6,23 @@ use winnow::{
 };
 
 use crate::grammar::{
-    reserved_words::Loop, tags_values::Tag, whitespace_comments::WhiteSpace, SyntacticUnit,
+    reserved_words::Loop, tags_values::Tag, whitespace_comments::WhiteSpace, Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone)]
-pub struct LoopHeader {
-    tags: Vec<Tag>,
+pub struct LoopHeader<'a> {
+    tags: Vec<Tag<'a>>,
 }
 
-impl LoopHeader {
-    pub fn new(tags: Vec<Tag>) -> Self {
+impl<'a> LoopHeader<'a> {
+    pub fn new(tags: Vec<Tag<'a>>) -> Self {
         Self { tags }
     }
     pub fn num_of_tags(&self) -> usize {
         self.tags.len()
     }
 
-    pub fn tags(&self) -> &[Tag] {
+    pub fn tags(&self) -> &[Tag<'a>] {
         &self.tags
     }
 
@@ -31,12 +31,12 @@ impl LoopHeader {
     }
 }
 
-impl SyntacticUnit for LoopHeader {
+impl<'a> SyntacticUnit<'a> for LoopHeader<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         preceded(
             Loop::parser,
             repeat(1.., preceded(WhiteSpace::parser, Tag::parser)),
@@ -56,7 +56,7 @@ impl SyntacticUnit for LoopHeader {
     }
 }
 
-impl Display for LoopHeader {
+impl Display for LoopHeader<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.formatted_output())
     }
@@ -79,7 +79,9 @@ mod test {
 ";
         println!(
             "{}",
-            LoopHeader::parser(&mut input).unwrap().formatted_output()
+            LoopHeader::parse_str(&mut input)
+                .unwrap()
+                .formatted_output()
         );
     }
 }
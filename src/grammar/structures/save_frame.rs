@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{preceded, repeat, terminated},
@@ -6,39 +6,66 @@ use winnow::{
 };
 
 use crate::grammar::{
-    character_sets::NonBlankChar, reserved_words::Save, whitespace_comments::WhiteSpace,
-    SyntacticUnit,
+    character_sets::NonBlankChar,
+    reserved_words::Save,
+    span::{spanned, Span},
+    whitespace_comments::WhiteSpace,
+    Input, SyntacticUnit,
 };
 
 use super::data_items::DataItems;
 
 #[derive(Debug, Clone)]
-pub struct SaveFrameHeading {
-    heading: String,
+pub struct SaveFrameHeading<'a> {
+    heading: Cow<'a, str>,
+    span: Option<Span>,
 }
 
-impl AsRef<str> for SaveFrameHeading {
+impl AsRef<str> for SaveFrameHeading<'_> {
     #[inline]
     fn as_ref(&self) -> &str {
-        <String as AsRef<str>>::as_ref(&self.heading)
+        &self.heading
     }
 }
 
-impl SaveFrameHeading {
-    pub fn new(heading: String) -> Self {
-        Self { heading }
+impl<'a> SaveFrameHeading<'a> {
+    pub fn new(heading: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            heading: heading.into(),
+            span: None,
+        }
+    }
+
+    pub(crate) fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Where the heading was parsed from, `None` when built in code.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn into_owned(self) -> SaveFrameHeading<'static> {
+        SaveFrameHeading {
+            heading: Cow::Owned(self.heading.into_owned()),
+            span: self.span,
+        }
     }
 }
 
-impl SyntacticUnit for SaveFrameHeading {
+impl<'a> SyntacticUnit<'a> for SaveFrameHeading<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
-        preceded(Save::parser, repeat(1.., NonBlankChar::parser))
-            .map(SaveFrameHeading::new)
-            .parse_next(input)
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
+        spanned(preceded(
+            Save::parser,
+            repeat::<_, _, (), _, _>(1.., NonBlankChar::parser).take(),
+        ))
+        .map(|(name, span)| SaveFrameHeading::new(name).with_span(span))
+        .parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
@@ -46,26 +73,26 @@ impl SyntacticUnit for SaveFrameHeading {
     }
 }
 
-impl Display for SaveFrameHeading {
+impl Display for SaveFrameHeading<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct SaveFrame {
-    heading: SaveFrameHeading,
-    data_items: Vec<DataItems>,
+pub struct SaveFrame<'a> {
+    heading: SaveFrameHeading<'a>,
+    data_items: Vec<DataItems<'a>>,
 }
 
-impl SaveFrame {
+impl<'a> SaveFrame<'a> {
     // pub fn new(heading: SaveFrameHeading, data_items: Vec<DataItems>) -> Self {
     //     Self {
     //         heading,
     //         data_items,
     //     }
     // }
-    pub fn from_heading_items(input: (SaveFrameHeading, Vec<DataItems>)) -> Self {
+    pub fn from_heading_items(input: (SaveFrameHeading<'a>, Vec<DataItems<'a>>)) -> Self {
         let (heading, data_items) = input;
         Self {
             heading,
@@ -73,25 +100,35 @@ impl SaveFrame {
         }
     }
 
-    pub fn heading(&self) -> &SaveFrameHeading {
+    pub fn heading(&self) -> &SaveFrameHeading<'a> {
         &self.heading
     }
 
-    pub fn data_items(&self) -> &[DataItems] {
+    pub fn data_items(&self) -> &[DataItems<'a>] {
         &self.data_items
     }
 
-    pub fn data_items_mut(&mut self) -> &mut Vec<DataItems> {
+    pub fn data_items_mut(&mut self) -> &mut Vec<DataItems<'a>> {
         &mut self.data_items
     }
+
+    pub fn into_owned(self) -> SaveFrame<'static> {
+        SaveFrame::from_heading_items((
+            self.heading.into_owned(),
+            self.data_items
+                .into_iter()
+                .map(DataItems::into_owned)
+                .collect(),
+        ))
+    }
 }
 
-impl SyntacticUnit for SaveFrame {
+impl<'a> SyntacticUnit<'a> for SaveFrame<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         terminated(
             (
                 SaveFrameHeading::parser,
@@ -116,7 +153,7 @@ impl SyntacticUnit for SaveFrame {
     }
 }
 
-impl Display for SaveFrame {
+impl Display for SaveFrame<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
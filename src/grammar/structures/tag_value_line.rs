@@ -9,73 +9,103 @@ use winnow::{
 use crate::{
     data_dict::{CifTerm, SingleValueTerm},
     grammar::{
-        tags_values::{Tag, Value},
+        span::{spanned, Span, Spanned},
+        tags_values::{spanned_value, Tag, Value},
         whitespace_comments::WhiteSpace,
-        SyntacticUnit,
+        Input, SyntacticUnit,
     },
 };
 
 #[derive(Debug, Clone, Default)]
-pub struct SingleLineData {
-    tag: Tag,
-    value: Value,
+pub struct SingleLineData<'a> {
+    tag: Tag<'a>,
+    value: Spanned<Value<'a>>,
+    span: Option<Span>,
 }
 
-impl SingleLineData {
-    pub fn from_tag_value(tag_value: (Tag, Value)) -> Self {
+impl<'a> SingleLineData<'a> {
+    pub fn from_tag_value(tag_value: (Tag<'a>, Value<'a>)) -> Self {
         let (tag, value) = tag_value;
-        Self { tag, value }
+        Self {
+            tag,
+            value: Spanned::new(value),
+            span: None,
+        }
     }
 
-    pub fn tag(&self) -> &Tag {
+    pub fn tag(&self) -> &Tag<'a> {
         &self.tag
     }
 
-    pub fn value(&self) -> &Value {
-        &self.value
+    pub fn value(&self) -> &Value<'a> {
+        self.value.node()
     }
     pub const fn null() -> Self {
         SingleLineData {
             tag: Tag::null(),
-            value: Value::Unknown,
+            value: Spanned::new(Value::Unknown),
+            span: None,
+        }
+    }
+
+    /// Where the item was parsed from, from its tag to the end of its value;
+    /// `None` when built in code.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// The value with where it was parsed from.
+    pub fn spanned_value(&self) -> &Spanned<Value<'a>> {
+        &self.value
+    }
+
+    pub fn into_owned(self) -> SingleLineData<'static> {
+        SingleLineData {
+            tag: self.tag.into_owned(),
+            value: self.value.map(Value::into_owned),
+            span: self.span,
         }
     }
 }
 
-impl SyntacticUnit for SingleLineData {
+impl<'a> SyntacticUnit<'a> for SingleLineData<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
-        separated_pair(
-            Tag::parser.context(StrContext::Label("Tag")),
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
+        spanned(separated_pair(
+            Tag::parser.context(StrContext::Label("Tag<'a>")),
             peek(WhiteSpace::parser.context(StrContext::Label("Whitespace"))),
-            Value::parser.context(StrContext::Label("Value")),
-        )
-        .map(SingleLineData::from_tag_value)
+            spanned_value.context(StrContext::Label("Value<'a>")),
+        ))
+        .map(|((tag, value), span)| SingleLineData {
+            tag,
+            value,
+            span: Some(span),
+        })
         .parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
-        format!("{:<33} {}", self.tag.to_string(), self.value)
+        format!("{:<33} {}", self.tag.to_string(), self.value())
     }
 }
 
-impl Display for SingleLineData {
+impl Display for SingleLineData<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
 }
 
-impl CifTerm for SingleLineData {
-    fn tag(&self) -> Tag {
-        self.tag.clone()
+impl CifTerm for SingleLineData<'_> {
+    fn tag(&self) -> Tag<'static> {
+        self.tag.clone().into_owned()
     }
 }
 
-impl SingleValueTerm for SingleLineData {
-    fn value(&self) -> Value {
-        self.value().clone()
+impl SingleValueTerm for SingleLineData<'_> {
+    fn value(&self) -> Value<'static> {
+        self.value().clone().into_owned()
     }
 }
@@ -2,4 +2,5 @@ mod tag;
 mod values;
 
 pub use tag::Tag;
+pub(crate) use values::spanned_value;
 pub use values::{CIFValue, Value};
@@ -1,52 +1,90 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use winnow::{
     combinator::{preceded, repeat},
     Parser,
 };
 
-use crate::grammar::{character_sets::NonBlankChar, SyntacticUnit};
+use crate::grammar::{
+    character_sets::NonBlankChar,
+    span::{spanned, Span},
+    Input, SyntacticUnit,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Tag<'a> {
+    name: Cow<'a, str>,
+    span: Option<Span>,
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct Tag {
-    name: String,
+/// Tags are equal by name, wherever they were parsed from.
+impl PartialEq for Tag<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
 }
 
-impl AsRef<str> for Tag {
+impl Eq for Tag<'_> {}
+
+impl AsRef<str> for Tag<'_> {
     #[inline]
     fn as_ref(&self) -> &str {
-        <String as AsRef<str>>::as_ref(&self.name)
+        &self.name
     }
 }
 
-impl Tag {
-    pub fn new(name: String) -> Self {
-        Self { name }
+impl<'a> Tag<'a> {
+    pub fn new(name: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            name: name.into(),
+            span: None,
+        }
+    }
+
+    pub(crate) fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
     }
 
     pub fn as_str(&self) -> &str {
-        self.name.as_str()
+        &self.name
     }
     pub const fn null() -> Self {
         Self {
-            name: String::new(),
+            name: Cow::Borrowed(""),
+            span: None,
         }
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Where the tag was parsed from, `None` when built in code.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn into_owned(self) -> Tag<'static> {
+        Tag {
+            name: Cow::Owned(self.name.into_owned()),
+            span: self.span,
+        }
+    }
 }
 
-impl SyntacticUnit for Tag {
+impl<'a> SyntacticUnit<'a> for Tag<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
-        preceded('_', repeat(1.., NonBlankChar::parser))
-            .map(Tag::new)
-            .parse_next(input)
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
+        spanned(preceded(
+            '_',
+            repeat::<_, _, (), _, _>(1.., NonBlankChar::parser).take(),
+        ))
+        .map(|(name, span)| Tag::new(name).with_span(span))
+        .parse_next(input)
     }
 
     fn formatted_output(&self) -> Self::FormatOutput {
@@ -54,7 +92,7 @@ impl SyntacticUnit for Tag {
     }
 }
 
-impl Display for Tag {
+impl Display for Tag<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.formatted_output())
     }
@@ -1,31 +1,38 @@
-use std::fmt::{Display, Write};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Write},
+};
 
 use winnow::{
-    combinator::{alt, opt, peek, preceded, terminated},
+    combinator::{alt, eof, opt, peek, preceded, repeat, terminated},
+    stream::Location,
     PResult, Parser,
 };
 
 use crate::grammar::{
-    character_sets::Eol,
+    character_sets::{LeadingBlank, NonBlankChar},
     numeric_values::Numeric,
-    strings_textfields::{CharString, TextField},
+    span::Spanned,
+    strings_textfields::{
+        char_string_after_blanks, closes_quote, CharString, TextField, UnquotedString,
+    },
     whitespace_comments::WhiteSpace,
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone, Default)]
-pub enum Value {
+pub enum Value<'a> {
     /// '.'
     Inapplicable,
     #[default]
     /// '?'
     Unknown,
     Numeric(Numeric),
-    CharString(CharString),
-    TextField(TextField),
+    CharString(CharString<'a>),
+    TextField(TextField<'a>),
 }
 
-impl Value {
+impl<'a> Value<'a> {
     pub fn as_numeric(&self) -> Option<&Numeric> {
         if let Self::Numeric(v) = self {
             Some(v)
@@ -34,7 +41,7 @@ impl Value {
         }
     }
 
-    pub fn as_char_string(&self) -> Option<&CharString> {
+    pub fn as_char_string(&self) -> Option<&CharString<'a>> {
         if let Self::CharString(v) = self {
             Some(v)
         } else {
@@ -42,41 +49,114 @@ impl Value {
         }
     }
 
-    pub fn as_text_field(&self) -> Option<&TextField> {
+    pub fn as_text_field(&self) -> Option<&TextField<'a>> {
         if let Self::TextField(v) = self {
             Some(v)
         } else {
             None
         }
     }
+
+    /// Numeric value as `f64`, whether it was written as an integer or a float.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_numeric().map(|n| f64::from(n.number()))
+    }
+
+    /// The value as text, without the quotes or semicolons delimiting it.
+    pub fn text(&self) -> String {
+        match self {
+            Value::CharString(s) => s.as_ref().to_string(),
+            Value::TextField(t) => t.raw_string().trim().to_string(),
+            v => v.to_string(),
+        }
+    }
+
+    /// `text` as a character string, quoted as needed, or as a text field
+    /// when it spans several lines or holds both `'` and `"` followed by a
+    /// blank, which no quotes can delimit.
+    pub fn from_text(text: impl Into<Cow<'a, str>>) -> Self {
+        let text = text.into();
+        if text.contains('\n') || (closes_quote(&text, '\'') && closes_quote(&text, '"')) {
+            Value::TextField(TextField::from(text.as_ref()))
+        } else {
+            Value::CharString(CharString::from(text))
+        }
+    }
+
+    /// A copy not borrowing from the parsed input.
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Inapplicable => Value::Inapplicable,
+            Value::Unknown => Value::Unknown,
+            Value::Numeric(n) => Value::Numeric(n),
+            Value::CharString(s) => Value::CharString(s.into_owned()),
+            Value::TextField(t) => Value::TextField(t.into_owned()),
+        }
+    }
 }
 
 pub trait CIFValue {}
 
 impl CIFValue for Numeric {}
-impl CIFValue for CharString {}
-impl CIFValue for TextField {}
+impl CIFValue for CharString<'_> {}
+impl CIFValue for TextField<'_> {}
+
+/// A blank or the end of input, which ends a one-character value like '?' or
+/// '.' in a loop row.
+fn value_end(input: &mut Input<'_>) -> PResult<()> {
+    alt((LeadingBlank::parser.void(), eof.void())).parse_next(input)
+}
 
 /// Parse value '?' when the it does not have trailing chars.
-fn unknown_parser(input: &mut &str) -> PResult<Value> {
-    terminated('?', peek(Eol::parser))
+fn unknown_parser<'a>(input: &mut Input<'a>) -> PResult<Value<'a>> {
+    terminated('?', peek(value_end))
         .map(|_| Value::Unknown)
         .parse_next(input)
 }
 
 /// Parse value '.' when the it does not have trailing chars.
-fn inapplicable_parser(input: &mut &str) -> PResult<Value> {
-    terminated('.', peek(Eol::parser))
+fn inapplicable_parser<'a>(input: &mut Input<'a>) -> PResult<Value<'a>> {
+    terminated('.', peek(value_end))
         .map(|_| Value::Inapplicable)
         .parse_next(input)
 }
 
-impl SyntacticUnit for Value {
+/// An unquoted string starting with `;`, which only begins a text field at
+/// the start of a line.
+fn semicolon_unquoted<'a>(input: &mut Input<'a>) -> PResult<Value<'a>> {
+    (';', repeat::<_, _, (), _, _>(0.., NonBlankChar::parser))
+        .take()
+        .map(|s| Value::CharString(CharString::Unquoted(UnquotedString::new(s))))
+        .parse_next(input)
+}
+
+/// [`Value::parser`] with the span of the value, which starts after the
+/// blanks and comments before it. The blanks are parsed once, here, and the
+/// value after them.
+pub(crate) fn spanned_value<'a>(input: &mut Input<'a>) -> PResult<Spanned<Value<'a>>> {
+    let blanks = opt(WhiteSpace::parser.take()).parse_next(input)?;
+    let start = input.location();
+    let mut value = alt((
+        unknown_parser,
+        inapplicable_parser,
+        Numeric::parser.map(Value::Numeric),
+        TextField::parser.map(Value::TextField),
+        char_string_after_blanks.map(Value::CharString),
+    ));
+    let value = match blanks.is_some_and(|blanks| !blanks.ends_with('\n')) {
+        true => alt((value, semicolon_unquoted)).parse_next(input)?,
+        false => value.parse_next(input)?,
+    };
+    let span = input.state.span(start..input.location());
+    Ok(Spanned::with_span(value, Some(span)))
+}
+
+impl<'a> SyntacticUnit<'a> for Value<'a> {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         alt((
             preceded(opt(WhiteSpace::parser), unknown_parser),
             preceded(opt(WhiteSpace::parser), inapplicable_parser),
@@ -98,7 +178,7 @@ impl SyntacticUnit for Value {
     }
 }
 
-impl Display for Value {
+impl Display for Value<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Inapplicable => f.write_char('.'),
@@ -151,8 +231,14 @@ RESPONSE: .See above
 ",
             "1_445",
         ];
-        inputs.iter_mut().map(Value::parser).for_each(|res| {
+        inputs.iter_mut().map(Value::parse_str).for_each(|res| {
             res.map(|v| println!("{v:?}")).unwrap();
-        })
+        });
+        let mut row = " . ? no\n";
+        assert!(matches!(
+            Value::parse_str(&mut row),
+            Ok(Value::Inapplicable)
+        ));
+        assert!(matches!(Value::parse_str(&mut row), Ok(Value::Unknown)));
     }
 }
@@ -9,7 +9,7 @@ use winnow::{
 
 use crate::grammar::{
     character_sets::{AnyPrintChar, LeadingBlank},
-    SyntacticUnit,
+    Input, SyntacticUnit,
 };
 
 #[derive(Debug, Clone)]
@@ -39,12 +39,12 @@ impl Comments {
     }
 }
 
-impl SyntacticUnit for Comments {
+impl<'a> SyntacticUnit<'a> for Comments {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         repeat(
             1..,
             delimited(
@@ -72,12 +72,12 @@ impl Display for Comments {
     }
 }
 
-impl SyntacticUnit for TokenizedComments {
+impl<'a> SyntacticUnit<'a> for TokenizedComments {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         (repeat(1.., LeadingBlank::parser), Comments::parser)
             .map(|(blanks, comments)| TokenizedComments::new(blanks, comments))
             .parse_next(input)
@@ -103,6 +103,6 @@ impl Accumulate<TokenizedComments> for String {
     }
 
     fn accumulate(&mut self, acc: TokenizedComments) {
-        *self = [self.clone(), acc.formatted_output()].concat()
+        self.push_str(&acc.formatted_output())
     }
 }
@@ -9,7 +9,7 @@ use winnow::{
     Parser,
 };
 
-use super::{character_sets::LeadingBlank, SyntacticUnit};
+use super::{character_sets::LeadingBlank, Input, SyntacticUnit};
 
 enum WhiteSpaceItem {
     LeadingBlank(LeadingBlank),
@@ -27,9 +27,7 @@ impl Accumulate<WhiteSpaceItem> for String {
     fn accumulate(&mut self, acc: WhiteSpaceItem) {
         match acc {
             WhiteSpaceItem::LeadingBlank(lb) => self.push(lb.as_char()),
-            WhiteSpaceItem::TokenizedComments(t) => {
-                *self = [self.clone(), t.formatted_output()].concat()
-            }
+            WhiteSpaceItem::TokenizedComments(t) => self.push_str(&t.formatted_output()),
         }
     }
 }
@@ -45,12 +43,12 @@ impl WhiteSpace {
     }
 }
 
-impl SyntacticUnit for WhiteSpace {
+impl<'a> SyntacticUnit<'a> for WhiteSpace {
     type ParseResult = Self;
 
     type FormatOutput = String;
 
-    fn parser(input: &mut &str) -> winnow::prelude::PResult<Self::ParseResult> {
+    fn parser(input: &mut Input<'a>) -> winnow::prelude::PResult<Self::ParseResult> {
         repeat(
             1..,
             alt((
@@ -86,13 +84,13 @@ mod test {
 # 1. SUBMISSION DETAILS
 #===================================================================
 ";
-        let comment = Comments::parser(&mut input);
+        let comment = Comments::parse_str(&mut input);
         if let Ok(comment) = comment {
             println!("{}", comment);
         }
         let mut input = " #File kfb29v03.cif
 ";
-        let tokenized_comment = TokenizedComments::parser(&mut input);
+        let tokenized_comment = TokenizedComments::parse_str(&mut input);
         if let Ok(comment) = tokenized_comment {
             println!("{}", comment);
         }
@@ -1,13 +1,24 @@
 pub mod data_dict;
+pub mod diff;
 
 mod grammar;
+pub mod merge;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod query;
+pub mod reader;
+pub mod split;
+pub mod tolerant;
+pub mod write;
 
 pub use grammar::{
     CIFDataType, CIFValue, CharString, CifDocument, DataBlock, DataBlockHeading, DataBlockMember,
     DataItems, DoubleQuotedString, Float, Integer, LoopColumn, LoopColumns, LoopUnit, Number,
-    Numeric, SingleLineData, SingleQuotedString, Tag, TextField, UnquotedString, UnsignedInteger,
-    Value,
+    Numeric, Position, SingleLineData, SingleQuotedString, Span, Spanned, Tag, TextField,
+    UnquotedString, UnsignedInteger, Value,
 };
 
 #[cfg(feature = "chemrust-core")]
-pub use grammar::chemrust_impl::{from_data_block_members, to_cif_document, to_data_block};
+pub use grammar::chemrust_impl::{
+    from_data_block_members, to_asymmetric_unit_data_block, to_cif_document, to_data_block,
+};
@@ -0,0 +1,416 @@
+//! Merge two CIF documents, as a structure written by
+//! [`to_cif_document`](crate::to_cif_document) with the experimental
+//! details from the diffractometer.
+//!
+//! Blocks are combined by name. Within a block the items of the base keep
+//! their order and the new items of the overlay follow in theirs. Loops are
+//! matched by their tags and their rows by the key columns of the category,
+//! like `_atom_site_label`, as in [`diff`](crate::diff): new columns and rows
+//! are added, with `?` where one side has no value. When the loop of the
+//! overlay lacks a key column of the base, rows are merged by position. A
+//! value known on one side only, `?` on the other, is no conflict.
+
+use std::fmt::Display;
+
+use crate::{
+    data_dict::find_column,
+    diff::{key_tags, row_keys, DiffOptions, RowIndex},
+    CifDocument, DataBlock, DataBlockHeading, DataBlockMember, DataItems, LoopColumn, LoopColumns,
+    SingleLineData, Tag, Value,
+};
+
+/// What to do when both documents give a different value for the same item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Keep the value of the base document.
+    #[default]
+    KeepLeft,
+    /// Take the value of the overlay.
+    KeepRight,
+    /// Stop with a [`MergeConflict`].
+    Error,
+}
+
+/// Different values for the same item under [`ConflictPolicy::Error`].
+/// `row` is the key of the loop row, as in [`diff`](crate::diff).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    block: String,
+    tag: String,
+    row: Option<String>,
+    left: String,
+    right: String,
+}
+
+impl MergeConflict {
+    pub fn block(&self) -> &str {
+        &self.block
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn row(&self) -> Option<&str> {
+        self.row.as_deref()
+    }
+
+    pub fn left(&self) -> &str {
+        &self.left
+    }
+
+    pub fn right(&self) -> &str {
+        &self.right
+    }
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "conflicting values of _{} in data_{}",
+            self.tag, self.block
+        )?;
+        if let Some(row) = &self.row {
+            write!(f, " for [{row}]")?;
+        }
+        write!(f, ": {} and {}", self.left, self.right)
+    }
+}
+
+impl std::error::Error for MergeConflict {}
+
+/// Merge `overlay` into `base`, resolving conflicting values by `policy`.
+/// The comments of `base` are kept.
+pub fn merge<'a>(
+    base: &CifDocument<'a>,
+    overlay: &CifDocument<'a>,
+    policy: ConflictPolicy,
+) -> Result<CifDocument<'a>, MergeConflict> {
+    let base_blocks = base.data_blocks().map_or(&[][..], |b| b.as_slice());
+    let overlay_blocks = overlay.data_blocks().map_or(&[][..], |b| b.as_slice());
+    let mut blocks = base_blocks
+        .iter()
+        .map(|block| {
+            match overlay_blocks
+                .iter()
+                .find(|other| other.heading() == block.heading())
+            {
+                Some(other) => merge_blocks(block, other, policy),
+                None => Ok(block.clone()),
+            }
+        })
+        .collect::<Result<Vec<DataBlock>, MergeConflict>>()?;
+    blocks.extend(
+        overlay_blocks
+            .iter()
+            .filter(|block| base_blocks.iter().all(|b| b.heading() != block.heading()))
+            .cloned(),
+    );
+    let mut merged = base.clone();
+    *merged.data_blocks_mut() = (!blocks.is_empty()).then_some(blocks);
+    Ok(merged)
+}
+
+struct Merger<'a> {
+    block: &'a str,
+    policy: ConflictPolicy,
+}
+
+impl Merger<'_> {
+    fn value<'v>(
+        &self,
+        tag: &str,
+        row: Option<&str>,
+        left: &Value<'v>,
+        right: &Value<'v>,
+    ) -> Result<Value<'v>, MergeConflict> {
+        let is_unknown = |value: &Value| matches!(value, Value::Unknown);
+        if is_unknown(right) || DiffOptions::default().same_value(left, right) {
+            return Ok(left.clone());
+        }
+        if is_unknown(left) {
+            return Ok(right.clone());
+        }
+        match self.policy {
+            ConflictPolicy::KeepLeft => Ok(left.clone()),
+            ConflictPolicy::KeepRight => Ok(right.clone()),
+            ConflictPolicy::Error => Err(MergeConflict {
+                block: self.block.to_string(),
+                tag: tag.to_string(),
+                row: row.map(str::to_string),
+                left: left.to_string(),
+                right: right.to_string(),
+            }),
+        }
+    }
+
+    fn loops<'v>(
+        &self,
+        left: &LoopColumns<'v>,
+        right: &LoopColumns<'v>,
+    ) -> Result<LoopColumns<'v>, MergeConflict> {
+        let mut keys = key_tags(left);
+        if !keys.iter().all(|tag| find_column(right, tag).is_some()) {
+            keys.clear();
+        }
+        let (left_keys, right_keys) = (row_keys(left, &keys), row_keys(right, &keys));
+        let (left_index, right_index) = (RowIndex::new(&left_keys), RowIndex::new(&right_keys));
+        let right_rows: Vec<Option<usize>> = (0..left_keys.len())
+            .map(|row| right_index.matching(&left_index, row))
+            .collect();
+        let added_rows: Vec<usize> = (0..right_keys.len())
+            .filter(|&row| left_index.matching(&right_index, row).is_none())
+            .collect();
+        let unknown = Value::Unknown;
+        let mut columns = left
+            .columns()
+            .iter()
+            .map(|column| {
+                let tag = column.tag().as_str();
+                let other = find_column(right, tag);
+                let mut values = column
+                    .values()
+                    .iter()
+                    .zip(right_rows.iter())
+                    .enumerate()
+                    .map(|(row, (value, right_row))| {
+                        match other.zip(*right_row).and_then(|(o, r)| o.values().get(r)) {
+                            Some(right_value) => {
+                                self.value(tag, Some(&left_keys[row]), value, right_value)
+                            }
+                            None => Ok(value.clone()),
+                        }
+                    })
+                    .collect::<Result<Vec<Value>, MergeConflict>>()?;
+                values.extend(added_rows.iter().map(|&row| {
+                    other
+                        .and_then(|o| o.values().get(row))
+                        .unwrap_or(&unknown)
+                        .clone()
+                }));
+                Ok(LoopColumn::new(column.tag().clone(), values))
+            })
+            .collect::<Result<Vec<LoopColumn>, MergeConflict>>()?;
+        columns.extend(
+            right
+                .columns()
+                .iter()
+                .filter(|column| find_column(left, column.tag().as_str()).is_none())
+                .map(|column| {
+                    let values = right_rows
+                        .iter()
+                        .map(|row| row.and_then(|r| column.values().get(r)))
+                        .chain(added_rows.iter().map(|&row| column.values().get(row)))
+                        .map(|value| value.unwrap_or(&unknown).clone())
+                        .collect();
+                    LoopColumn::new(column.tag().clone(), values)
+                }),
+        );
+        Ok(LoopColumns::new(columns))
+    }
+}
+
+/// The loop of `members` sharing a tag with `columns`.
+fn matching_loop<'b, 'a>(
+    members: &'b [DataBlockMember<'a>],
+    columns: &LoopColumns,
+) -> Option<&'b LoopColumns<'a>> {
+    members.iter().find_map(|member| match member {
+        DataBlockMember::DataItems(DataItems::MultiValues(other))
+            if columns
+                .columns()
+                .iter()
+                .any(|column| find_column(other, column.tag().as_str()).is_some()) =>
+        {
+            Some(other)
+        }
+        _ => None,
+    })
+}
+
+fn matching_single<'b, 'a>(
+    members: &'b [DataBlockMember<'a>],
+    tag: &Tag,
+) -> Option<&'b SingleLineData<'a>> {
+    members.iter().find_map(|member| match member {
+        DataBlockMember::DataItems(DataItems::SingleValue(item))
+            if item.tag().as_str().eq_ignore_ascii_case(tag.as_str()) =>
+        {
+            Some(item)
+        }
+        _ => None,
+    })
+}
+
+fn merge_blocks<'a>(
+    left: &DataBlock<'a>,
+    right: &DataBlock<'a>,
+    policy: ConflictPolicy,
+) -> Result<DataBlock<'a>, MergeConflict> {
+    let merger = Merger {
+        block: left.heading(),
+        policy,
+    };
+    let mut members = left
+        .members()
+        .iter()
+        .map(|member| match member {
+            DataBlockMember::DataItems(DataItems::SingleValue(item)) => {
+                match matching_single(right.members(), item.tag()) {
+                    Some(other) => {
+                        let value =
+                            merger.value(item.tag().as_str(), None, item.value(), other.value())?;
+                        Ok(DataBlockMember::DataItems(DataItems::SingleValue(
+                            SingleLineData::from_tag_value((item.tag().clone(), value)),
+                        )))
+                    }
+                    None => Ok(member.clone()),
+                }
+            }
+            DataBlockMember::DataItems(DataItems::MultiValues(columns)) => {
+                match matching_loop(right.members(), columns) {
+                    Some(other) => Ok(DataBlockMember::DataItems(DataItems::MultiValues(
+                        merger.loops(columns, other)?,
+                    ))),
+                    None => Ok(member.clone()),
+                }
+            }
+            DataBlockMember::SaveFrame(_) => Ok(member.clone()),
+        })
+        .collect::<Result<Vec<DataBlockMember>, MergeConflict>>()?;
+    members.extend(
+        right
+            .members()
+            .iter()
+            .filter(|member| match member {
+                DataBlockMember::DataItems(DataItems::SingleValue(item)) => {
+                    matching_single(left.members(), item.tag()).is_none()
+                }
+                DataBlockMember::DataItems(DataItems::MultiValues(columns)) => {
+                    matching_loop(left.members(), columns).is_none()
+                }
+                DataBlockMember::SaveFrame(_) => true,
+            })
+            .cloned(),
+    );
+    Ok(DataBlock::from_heading_members((
+        DataBlockHeading::new(left.heading().to_string()),
+        members,
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CifDocument;
+
+    use super::{merge, ConflictPolicy};
+
+    #[test]
+    fn merge_documents() {
+        let mut base = r#"data_I
+_cell_length_a 5.0123(4)
+_cell_measurement_temperature ?
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+Fe1 Fe 0.1
+O1 O 0.2
+"#;
+        let mut overlay = r#"data_I
+_diffrn_radiation_type 'Mo K\a'
+_cell_measurement_temperature 100(2)
+_cell_length_a 5.0131(4)
+loop_
+_atom_site_label
+_atom_site_fract_x
+_atom_site_U_iso_or_equiv
+O1 0.2 0.012
+N1 0.3 0.020
+data_extra
+_computing_structure_refinement 'SHELXL-2018/3'
+"#;
+        let base = CifDocument::parse_from_str(&mut base).unwrap();
+        let overlay = CifDocument::parse_from_str(&mut overlay).unwrap();
+
+        let merged = merge(&base, &overlay, ConflictPolicy::KeepLeft).unwrap();
+        let blocks = merged.data_blocks().unwrap();
+        assert_eq!(blocks.len(), 2);
+        let block = &blocks[0];
+        let single = |tag: &str| {
+            block
+                .find_single_value_by_tag(tag)
+                .unwrap()
+                .value()
+                .to_string()
+        };
+        assert_eq!(single("cell_length_a"), "5.0123(4)");
+        assert_eq!(single("cell_measurement_temperature"), "100(2)");
+        assert_eq!(single("diffrn_radiation_type"), "'Mo K\\a'");
+        let column = |tag: &str| -> Vec<String> {
+            block
+                .find_loop_column_by_tag(tag)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+        assert_eq!(column("atom_site_label"), ["Fe1", "O1", "N1"]);
+        assert_eq!(column("atom_site_type_symbol"), ["Fe", "O", "?"]);
+        assert_eq!(column("atom_site_U_iso_or_equiv"), ["?", "0.012", "0.020"]);
+        let tags: Vec<String> = block
+            .members()
+            .iter()
+            .filter_map(|member| match member {
+                crate::DataBlockMember::DataItems(crate::DataItems::SingleValue(item)) => {
+                    Some(item.tag().as_str().to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                "cell_length_a",
+                "cell_measurement_temperature",
+                "diffrn_radiation_type"
+            ]
+        );
+
+        let merged = merge(&base, &overlay, ConflictPolicy::KeepRight).unwrap();
+        let value = merged.data_blocks().unwrap()[0]
+            .find_single_value_by_tag("cell_length_a")
+            .unwrap()
+            .value()
+            .to_string();
+        assert_eq!(value, "5.0131(4)");
+
+        let conflict = merge(&base, &overlay, ConflictPolicy::Error).unwrap_err();
+        assert_eq!(conflict.tag(), "cell_length_a");
+        assert_eq!(conflict.row(), None);
+    }
+
+    #[test]
+    fn merge_without_key_column() {
+        let mut base = "data_I\nloop_\n_atom_site_label\n_atom_site_fract_x\nFe1 0.1\nO1 0.2\n";
+        let mut overlay =
+            "data_I\nloop_\n_atom_site_fract_x\n_atom_site_occupancy\n0.1 1\n0.2 0.5\n";
+        let base = CifDocument::parse_from_str(&mut base).unwrap();
+        let overlay = CifDocument::parse_from_str(&mut overlay).unwrap();
+        let merged = merge(&base, &overlay, ConflictPolicy::Error).unwrap();
+        let block = &merged.data_blocks().unwrap()[0];
+        let column = |tag: &str| -> Vec<String> {
+            block
+                .find_loop_column_by_tag(tag)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        };
+        assert_eq!(column("atom_site_label"), ["Fe1", "O1"]);
+        assert_eq!(column("atom_site_occupancy"), ["1", "0.5"]);
+    }
+}
@@ -0,0 +1,170 @@
+//! Parse with [`rayon`] across files, or across the data blocks of one large
+//! file. Behind the `rayon` feature.
+//!
+//! [`parse_blocks`] splits the input at the `data_` headings outside `;`
+//! text fields, as [`CifBlockReader`](crate::reader::CifBlockReader) does,
+//! and parses the pieces concurrently. When a piece does not parse on its
+//! own the whole input is parsed again sequentially, so the result is always
+//! that of [`CifDocument::parse_from_str`].
+
+use std::{fmt::Display, fs::read_to_string, path::Path};
+
+use rayon::prelude::*;
+use winnow::{
+    combinator::{opt, terminated},
+    stream::{Location, Stream},
+    PResult, Parser,
+};
+
+use crate::{
+    grammar::{located, Comments, SyntacticUnit, WhiteSpace},
+    reader::is_heading,
+    tolerant::line_column,
+    CifDocument, DataBlock,
+};
+
+/// Failure to read or parse one file.
+#[derive(Debug)]
+pub enum FileParseError {
+    Io(std::io::Error),
+    /// The parser stopped at this line and column, counted from 1.
+    Syntax {
+        line: usize,
+        column: usize,
+    },
+}
+
+impl Display for FileParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileParseError::Io(e) => write!(f, "{e}"),
+            FileParseError::Syntax { line, column } => {
+                write!(f, "syntax error at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FileParseError {}
+
+impl From<std::io::Error> for FileParseError {
+    fn from(value: std::io::Error) -> Self {
+        FileParseError::Io(value)
+    }
+}
+
+/// Read and parse the files in parallel, with one result for each path in
+/// the same order.
+pub fn parse_files<P: AsRef<Path> + Sync>(
+    paths: &[P],
+) -> Vec<Result<CifDocument<'static>, FileParseError>> {
+    paths
+        .par_iter()
+        .map(|path| parse_file(path.as_ref()))
+        .collect()
+}
+
+fn parse_file(path: &Path) -> Result<CifDocument<'static>, FileParseError> {
+    let content = read_to_string(path)?;
+    let mut input = content.as_str();
+    match CifDocument::parse_from_str(&mut input) {
+        Ok(document) if input.trim().is_empty() => Ok(document.into_owned()),
+        _ => {
+            let (line, column) = line_column(&content, content.len() - input.len());
+            Err(FileParseError::Syntax { line, column })
+        }
+    }
+}
+
+/// Parse a document with its data blocks parsed in parallel. Like
+/// [`CifDocument::parse_from_str`], `input` is advanced past what was
+/// parsed.
+pub fn parse_blocks<'a>(input: &mut &'a str) -> PResult<CifDocument<'a>> {
+    let mut located = located(input);
+    let comments = opt(Comments::parser).parse_next(&mut located)?;
+    opt(WhiteSpace::parser).parse_next(&mut located)?;
+    let start = located.location();
+    let offsets = block_offsets(&input[start..]);
+    if offsets.first() != Some(&0) {
+        return CifDocument::parse_from_str(input);
+    }
+    // Each block is parsed from its own clone of the stream, for the spans
+    // to count from the start of `input`.
+    let blocks = offsets
+        .par_iter()
+        .enumerate()
+        .map(|(i, &offset)| {
+            let end = offsets.get(i + 1).map_or(input.len(), |next| start + next);
+            let mut block_input = located.clone();
+            block_input.next_slice(offset);
+            let block = terminated(DataBlock::parser, opt(WhiteSpace::parser))
+                .parse_next(&mut block_input)
+                .ok()?;
+            (block_input.location() == end).then_some(block)
+        })
+        .collect::<Option<Vec<DataBlock<'a>>>>();
+    match blocks {
+        Some(blocks) => {
+            *input = &input[input.len()..];
+            Ok(CifDocument::new(comments, Some(blocks)))
+        }
+        None => CifDocument::parse_from_str(input),
+    }
+}
+
+/// The byte offsets of the lines starting a data block.
+fn block_offsets(input: &str) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut in_text_field = false;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if line.starts_with(';') {
+            in_text_field = !in_text_field;
+        } else if !in_text_field && is_heading(line) {
+            offsets.push(offset);
+        }
+        offset += line.len();
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CifDocument;
+
+    use super::parse_blocks;
+
+    #[test]
+    fn parallel_blocks() {
+        let input = r#"#\#CIF_2.0
+# two structures
+data_1
+_cell_length_a 5.0
+_publ_section_title
+;
+data_2 is no block here
+;
+
+data_2
+loop_
+_atom_site_label
+_atom_site_fract_x
+Fe1 0.1
+O1 0.2
+# trailing comment
+"#;
+        let (mut parallel, mut sequential) = (input, input);
+        let document = parse_blocks(&mut parallel).unwrap();
+        let expected = CifDocument::parse_from_str(&mut sequential).unwrap();
+        assert_eq!(format!("{document:?}"), format!("{expected:?}"));
+        assert_eq!(document.data_blocks().unwrap().len(), 2);
+        assert_eq!(parallel, sequential);
+
+        let broken = "data_1\n_cell_length_a 5.0\ndata_2\n_cell_length_a 6.0 6.5\n";
+        let (mut parallel, mut sequential) = (broken, broken);
+        let document = parse_blocks(&mut parallel).unwrap();
+        let expected = CifDocument::parse_from_str(&mut sequential).unwrap();
+        assert_eq!(format!("{document:?}"), format!("{expected:?}"));
+        assert_eq!(parallel, sequential);
+    }
+}
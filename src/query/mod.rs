@@ -0,0 +1,372 @@
+//! Select values across data blocks and loops with a path-like query.
+//!
+//! A query has a block pattern, an optional category with row conditions,
+//! and a tag pattern, separated by `/`:
+//!
+//! - `I/_cell_length_*` selects `_cell_length_a`, `_b` and `_c` of block `I`;
+//! - `*/_atom_site[_atom_site_type_symbol='Fe']/_atom_site_fract_x` selects
+//!   the x coordinates of the iron sites in every block.
+//!
+//! Patterns match block names and tags case-insensitively, with `*` for any
+//! run of characters and `?` for one character. Conditions compare a column
+//! of the same loop with `=`, `!=`, `<`, `<=`, `>` or `>=`, and are joined
+//! with `&`; the ordering operators compare numerically.
+
+use std::{fmt::Display, str::FromStr};
+
+use winnow::{
+    ascii::space0,
+    combinator::{alt, delimited, opt, preceded, separated, terminated},
+    token::{take_till, take_while},
+    PResult, Parser,
+};
+
+use crate::{data_dict::find_column, CifDocument, DataBlockMember, DataItems, LoopColumns, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// `_tag op value`, checked against the row of a loop.
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    tag: String,
+    comparison: Comparison,
+    value: String,
+}
+
+/// A parsed query, to evaluate against documents with [`Query::evaluate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    block: String,
+    category: Option<String>,
+    conditions: Vec<Condition>,
+    tag: String,
+}
+
+/// A value selected by a query, with the block, tag and loop row it comes
+/// from. `row` is `None` for single values.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryMatch<'a> {
+    block: &'a str,
+    tag: &'a str,
+    row: Option<usize>,
+    value: &'a Value<'a>,
+}
+
+impl<'a> QueryMatch<'a> {
+    pub fn block(&self) -> &'a str {
+        self.block
+    }
+
+    /// Tag without the leading underscore.
+    pub fn tag(&self) -> &'a str {
+        self.tag
+    }
+
+    pub fn row(&self) -> Option<usize> {
+        self.row
+    }
+
+    pub fn value(&self) -> &'a Value<'a> {
+        self.value
+    }
+}
+
+/// A query string which cannot be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParseError(pub String);
+
+impl Display for QueryParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a valid query", self.0)
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+impl FromStr for Query {
+    type Err = QueryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        query
+            .parse(s.trim())
+            .map_err(|_| QueryParseError(s.to_string()))
+    }
+}
+
+fn query(input: &mut &str) -> PResult<Query> {
+    let block = terminated(take_till(1.., '/'), '/').parse_next(input)?;
+    let category = opt(terminated((tag_pattern, opt(conditions)), '/')).parse_next(input)?;
+    let tag = tag_pattern.parse_next(input)?;
+    let (category, conditions) = match category {
+        Some((category, conditions)) => (Some(category), conditions.unwrap_or_default()),
+        None => (None, Vec::new()),
+    };
+    Ok(Query {
+        block: block.to_string(),
+        category,
+        conditions,
+        tag,
+    })
+}
+
+/// `_name` with `*` and `?` allowed, returned without the underscore.
+fn tag_pattern(input: &mut &str) -> PResult<String> {
+    preceded(
+        '_',
+        take_while(1.., |c: char| {
+            !c.is_whitespace() && !matches!(c, '/' | '[' | ']' | '=' | '!' | '<' | '>' | '&')
+        }),
+    )
+    .map(str::to_string)
+    .parse_next(input)
+}
+
+fn conditions(input: &mut &str) -> PResult<Vec<Condition>> {
+    delimited('[', separated(1.., condition, '&'), ']').parse_next(input)
+}
+
+fn condition(input: &mut &str) -> PResult<Condition> {
+    let tag = delimited(space0, tag_pattern, space0).parse_next(input)?;
+    let comparison = alt((
+        "!=".value(Comparison::NotEqual),
+        "<=".value(Comparison::LessOrEqual),
+        ">=".value(Comparison::GreaterOrEqual),
+        "=".value(Comparison::Equal),
+        "<".value(Comparison::Less),
+        ">".value(Comparison::Greater),
+    ))
+    .parse_next(input)?;
+    let value = delimited(space0, literal, space0).parse_next(input)?;
+    Ok(Condition {
+        tag,
+        comparison,
+        value,
+    })
+}
+
+/// A quoted or bare literal.
+fn literal(input: &mut &str) -> PResult<String> {
+    alt((
+        delimited('\'', take_till(0.., '\''), '\''),
+        delimited('"', take_till(0.., '"'), '"'),
+        take_while(1.., |c: char| !c.is_whitespace() && !matches!(c, ']' | '&')),
+    ))
+    .map(str::to_string)
+    .parse_next(input)
+}
+
+/// Case-insensitive match of `text` against `pattern` with `*` and `?`.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl Condition {
+    fn holds(&self, value: &Value) -> bool {
+        let text = value.text();
+        match self.comparison {
+            Comparison::Equal => text == self.value,
+            Comparison::NotEqual => text != self.value,
+            ordering => {
+                let (Some(found), Ok(limit)) = (
+                    value.as_numeric().map(|n| n.as_f64()),
+                    self.value.parse::<f64>(),
+                ) else {
+                    return false;
+                };
+                match ordering {
+                    Comparison::Less => found < limit,
+                    Comparison::LessOrEqual => found <= limit,
+                    Comparison::Greater => found > limit,
+                    _ => found >= limit,
+                }
+            }
+        }
+    }
+}
+
+impl Query {
+    /// Every value selected by the query, in document order: blocks in
+    /// order, then items in order, then loop rows.
+    pub fn evaluate<'a>(&self, document: &'a CifDocument<'a>) -> Vec<QueryMatch<'a>> {
+        let mut matches = Vec::new();
+        let blocks = document.data_blocks().map_or(&[][..], |b| b.as_slice());
+        for block in blocks
+            .iter()
+            .filter(|block| matches_pattern(&self.block, block.heading()))
+        {
+            for member in block.members() {
+                match member {
+                    DataBlockMember::DataItems(DataItems::SingleValue(item))
+                        if self.conditions.is_empty() && self.selects(item.tag().as_str()) =>
+                    {
+                        matches.push(QueryMatch {
+                            block: block.heading(),
+                            tag: item.tag().as_str(),
+                            row: None,
+                            value: item.value(),
+                        })
+                    }
+                    DataBlockMember::DataItems(DataItems::MultiValues(columns)) => {
+                        matches.extend(self.loop_matches(block.heading(), columns))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        matches
+    }
+
+    fn selects(&self, tag: &str) -> bool {
+        let in_category = self.category.as_ref().is_none_or(|category| {
+            tag.len() > category.len() && matches_pattern(&format!("{category}_*"), tag)
+        });
+        in_category && matches_pattern(&self.tag, tag)
+    }
+
+    fn loop_matches<'a>(
+        &self,
+        block: &'a str,
+        columns: &'a LoopColumns<'a>,
+    ) -> Vec<QueryMatch<'a>> {
+        let Some(condition_columns) = self
+            .conditions
+            .iter()
+            .map(|condition| find_column(columns, &condition.tag).map(|column| (condition, column)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Vec::new();
+        };
+        let selected: Vec<_> = columns
+            .columns()
+            .iter()
+            .filter(|column| self.selects(column.tag().as_str()))
+            .collect();
+        let rows = columns
+            .columns()
+            .first()
+            .map_or(0, |column| column.values().len());
+        (0..rows)
+            .filter(|&row| {
+                condition_columns.iter().all(|(condition, column)| {
+                    column.values().get(row).is_some_and(|v| condition.holds(v))
+                })
+            })
+            .flat_map(|row| {
+                selected.iter().filter_map(move |column| {
+                    column.values().get(row).map(|value| QueryMatch {
+                        block,
+                        tag: column.tag().as_str(),
+                        row: Some(row),
+                        value,
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse `query` and evaluate it against the document.
+pub fn select<'a>(
+    document: &'a CifDocument<'a>,
+    query: &str,
+) -> Result<Vec<QueryMatch<'a>>, QueryParseError> {
+    Ok(query.parse::<Query>()?.evaluate(document))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CifDocument;
+
+    use super::{select, Query};
+
+    #[test]
+    fn queries() {
+        let mut input = r#"data_I
+_cell_length_a 5.0
+_cell_length_b 6.0
+_cell_length_c 7.0
+_cell_angle_alpha 90
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+_atom_site_occupancy
+Fe1 Fe 0.1 1
+O1 O 0.2 1
+Fe2 Fe 0.3 0.5
+data_II
+_cell_length_a 8.0
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+Fe3 Fe 0.4
+"#;
+        let document = CifDocument::parse_from_str(&mut input).unwrap();
+        let texts = |query: &str| -> Vec<String> {
+            select(&document, query)
+                .unwrap()
+                .iter()
+                .map(|m| format!("{} {} {:?} {}", m.block(), m.tag(), m.row(), m.value()))
+                .collect()
+        };
+        assert_eq!(
+            texts("I/_cell_length_*"),
+            [
+                "I cell_length_a None 5.0",
+                "I cell_length_b None 6.0",
+                "I cell_length_c None 7.0"
+            ]
+        );
+        assert_eq!(
+            texts("*/_atom_site[_atom_site_type_symbol='Fe']/_atom_site_fract_x"),
+            [
+                "I atom_site_fract_x Some(0) 0.1",
+                "I atom_site_fract_x Some(2) 0.3",
+                "II atom_site_fract_x Some(0) 0.4"
+            ]
+        );
+        assert_eq!(
+            texts("*/_atom_site[_atom_site_type_symbol = Fe & _atom_site_occupancy < 1]/_atom_site_label"),
+            ["I atom_site_label Some(2) Fe2"]
+        );
+        assert_eq!(texts("i*/_CELL_LENGTH_A").len(), 2);
+        assert!(texts("III/_cell_length_a").is_empty());
+        assert!("I/cell_length_a".parse::<Query>().is_err());
+        assert!("I/_atom_site[_atom_site_label]/_x"
+            .parse::<Query>()
+            .is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Read the data blocks of a CIF one at a time, for archives too large to
+//! hold in memory like whole COD or CSD exports.
+//!
+//! [`CifBlockReader`] collects the lines of one block, from its `data_`
+//! heading to the next heading outside a `;` text field, and parses them
+//! with the same grammar as [`CifDocument`](crate::CifDocument). Only one
+//! block is held at a time. Anything before the first heading, like the
+//! leading comments, is skipped.
+
+use std::{fmt::Display, io::BufRead};
+
+use winnow::{
+    combinator::opt,
+    stream::{Location, Stream},
+    Parser,
+};
+
+use crate::{
+    grammar::{located_at, SyntacticUnit, WhiteSpace},
+    DataBlock,
+};
+
+/// Failure to read the next block.
+#[derive(Debug)]
+pub enum BlockReadError {
+    Io(std::io::Error),
+    /// The block starting at `line` could not be parsed; `at` is the line
+    /// the parser stopped at. Reading goes on with the next block.
+    Syntax {
+        block: String,
+        line: usize,
+        at: usize,
+    },
+}
+
+impl Display for BlockReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockReadError::Io(e) => write!(f, "{e}"),
+            BlockReadError::Syntax { block, line, at } => write!(
+                f,
+                "syntax error at line {at} in data_{block} starting at line {line}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlockReadError {}
+
+impl From<std::io::Error> for BlockReadError {
+    fn from(value: std::io::Error) -> Self {
+        BlockReadError::Io(value)
+    }
+}
+
+/// An iterator over the data blocks of a CIF read from `reader`.
+#[derive(Debug)]
+pub struct CifBlockReader<R> {
+    reader: R,
+    /// The line number and byte offset of the heading line of the next
+    /// block, and the line.
+    heading: Option<(usize, usize, String)>,
+    line_number: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<R: BufRead> CifBlockReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            heading: None,
+            line_number: 0,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// The next line, or `None` at the end of the input.
+    fn read_line(&mut self) -> Result<Option<String>, std::io::Error> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line)? {
+            0 => Ok(None),
+            n => {
+                self.line_number += 1;
+                self.offset += n;
+                Ok(Some(line))
+            }
+        }
+    }
+
+    /// The lines up to the next heading, which is kept for the next call.
+    fn block_lines(&mut self, heading: String) -> Result<String, std::io::Error> {
+        let mut lines = heading;
+        let mut in_text_field = false;
+        while let Some(line) = self.read_line()? {
+            if line.starts_with(';') {
+                in_text_field = !in_text_field;
+            } else if !in_text_field && is_heading(&line) {
+                self.heading = Some((self.line_number, self.offset - line.len(), line));
+                return Ok(lines);
+            }
+            lines.push_str(&line);
+        }
+        Ok(lines)
+    }
+
+    fn next_block(&mut self) -> Result<Option<DataBlock<'static>>, BlockReadError> {
+        // Skip what comes before the first heading.
+        while self.heading.is_none() {
+            match self.read_line()? {
+                Some(line) if is_heading(&line) => {
+                    self.heading = Some((self.line_number, self.offset - line.len(), line))
+                }
+                Some(_) => {}
+                None => return Ok(None),
+            }
+        }
+        let (line, offset, heading) = self.heading.take().expect("heading is read");
+        let lines = self.block_lines(heading)?;
+        let mut input = located_at(&lines, offset, line);
+        input.next_slice(lines.len() - lines.trim_start().len());
+        let parsed = (DataBlock::parser, opt(WhiteSpace::parser))
+            .map(|(block, _)| block)
+            .parse_next(&mut input);
+        match parsed {
+            Ok(block) if input.location() == lines.len() => Ok(Some(block.into_owned())),
+            _ => {
+                let consumed = &lines[..input.location()];
+                Err(BlockReadError::Syntax {
+                    block: block_code(&lines),
+                    line,
+                    at: line + consumed.matches('\n').count(),
+                })
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CifBlockReader<R> {
+    type Item = Result<DataBlock<'static>, BlockReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_block();
+        // Stop on read errors, which would repeat.
+        if matches!(next, Ok(None) | Err(BlockReadError::Io(_))) {
+            self.done = true;
+        }
+        next.transpose()
+    }
+}
+
+pub(crate) fn is_heading(line: &str) -> bool {
+    line.trim_start()
+        .get(..5)
+        .is_some_and(|start| start.eq_ignore_ascii_case("data_"))
+}
+
+fn block_code(lines: &str) -> String {
+    lines.trim_start()[5..]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BlockReadError, CifBlockReader};
+
+    #[test]
+    fn read_blocks() {
+        let input = r#"#\#CIF_2.0
+# three structures
+data_1000001
+_cell_length_a 5.0
+_publ_section_title
+;
+data_1000002 is no block here
+;
+DATA_1000002
+_cell_length_a 6.0 6.5
+data_1000003
+loop_
+_atom_site_label
+_atom_site_fract_x
+Fe1 0.1
+# trailing comment
+"#;
+        let blocks: Vec<_> = CifBlockReader::new(input.as_bytes()).collect();
+        assert_eq!(blocks.len(), 3);
+        let first = blocks[0].as_ref().unwrap();
+        assert_eq!(first.heading(), "1000001");
+        assert!(first
+            .find_single_value_by_tag("publ_section_title")
+            .is_some());
+        match &blocks[1] {
+            Err(BlockReadError::Syntax { block, line, at }) => {
+                assert_eq!((block.as_str(), *line, *at), ("1000002", 9, 10));
+            }
+            other => panic!("expected a syntax error, got {other:?}"),
+        }
+        let third = blocks[2].as_ref().unwrap();
+        assert_eq!(third.heading(), "1000003");
+        let label = third.find_loop_column_by_tag("atom_site_label").unwrap();
+        let fe1 = label.spanned_values()[0].span().unwrap().start();
+        assert_eq!((fe1.offset(), fe1.line()), (input.find("Fe1").unwrap(), 15));
+        assert!(third.find_loop_column_by_tag("atom_site_fract_x").is_some());
+        assert_eq!(CifBlockReader::new("# empty\n".as_bytes()).count(), 0);
+    }
+}
@@ -0,0 +1,142 @@
+//! Split multi-block documents, like COD and CSD exports, into one document
+//! per data block, and join documents into one.
+//!
+//! Block codes are compared case-insensitively, as in CIF. A block code
+//! seen before is renamed deterministically by appending `_2`, `_3`... the
+//! first free number, so joining the same files in the same order always
+//! gives the same names.
+
+use std::collections::HashSet;
+
+use crate::{CifDocument, DataBlock, DataBlockHeading};
+
+/// Unique names for `names`, in order and compared case-insensitively: the
+/// first occurrence of a name is kept and later ones get the first free
+/// `_N` suffix. Used for block codes, and for file names made from them.
+pub fn unique_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut taken: HashSet<String> = HashSet::new();
+    names
+        .into_iter()
+        .map(|name| {
+            let name = match taken.contains(&name.to_ascii_lowercase()) {
+                false => name.to_string(),
+                true => (2..)
+                    .map(|n| format!("{name}_{n}"))
+                    .find(|name| !taken.contains(&name.to_ascii_lowercase()))
+                    .expect("some suffix is free"),
+            };
+            taken.insert(name.to_ascii_lowercase());
+            name
+        })
+        .collect()
+}
+
+fn renamed<'a>(block: &DataBlock<'a>, code: &str) -> DataBlock<'a> {
+    match block.heading() == code {
+        true => block.clone(),
+        false => DataBlock::from_heading_members((
+            DataBlockHeading::new(code.to_string()),
+            block.members().to_vec(),
+        )),
+    }
+}
+
+/// One document for every data block, with its block code. Each document
+/// keeps the leading comments of `document`; blocks repeating a code are
+/// renamed as for [`join`].
+pub fn split<'a>(document: &CifDocument<'a>) -> Vec<(String, CifDocument<'a>)> {
+    let blocks = document.data_blocks().map_or(&[][..], |b| b.as_slice());
+    let codes = unique_names(blocks.iter().map(|block| block.heading()));
+    blocks
+        .iter()
+        .zip(codes)
+        .map(|(block, code)| {
+            let mut single = document.clone();
+            *single.data_blocks_mut() = Some(vec![renamed(block, &code)]);
+            (code, single)
+        })
+        .collect()
+}
+
+/// The data blocks of all documents in order, in one document with the
+/// leading comments of the first. Clashing block codes are renamed.
+pub fn join<'a>(documents: &[CifDocument<'a>]) -> CifDocument<'a> {
+    let blocks: Vec<&DataBlock> = documents
+        .iter()
+        .flat_map(|document| document.data_blocks().map_or(&[][..], |b| b.as_slice()))
+        .collect();
+    let codes = unique_names(blocks.iter().map(|block| block.heading()));
+    let mut joined = documents
+        .first()
+        .cloned()
+        .unwrap_or_else(|| CifDocument::new(None, None));
+    *joined.data_blocks_mut() = (!blocks.is_empty()).then(|| {
+        blocks
+            .iter()
+            .zip(codes.iter())
+            .map(|(block, code)| renamed(block, code))
+            .collect()
+    });
+    joined
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CifDocument;
+
+    use super::{join, split, unique_names};
+
+    #[test]
+    fn split_and_join() {
+        let mut first = r#"data_1000001
+_cell_length_a 5.0
+data_1000002
+_cell_length_a 6.0
+data_1000001
+_cell_length_a 7.0
+"#;
+        let mut second = r#"data_1000002
+_cell_length_a 8.0
+data_1000001_2
+_cell_length_a 9.0
+"#;
+        let first = CifDocument::parse_from_str(&mut first).unwrap();
+        let second = CifDocument::parse_from_str(&mut second).unwrap();
+
+        let parts = split(&first);
+        let codes: Vec<&str> = parts.iter().map(|(code, _)| code.as_str()).collect();
+        assert_eq!(codes, ["1000001", "1000002", "1000001_2"]);
+        let value = parts[2].1.data_blocks().unwrap()[0]
+            .find_single_value_by_tag("cell_length_a")
+            .unwrap()
+            .value()
+            .to_string();
+        assert_eq!(value, "7.0");
+        assert!(parts
+            .iter()
+            .all(|(_, document)| document.data_blocks().unwrap().len() == 1));
+
+        let joined = join(&[first, second]);
+        let codes: Vec<&str> = joined
+            .data_blocks()
+            .unwrap()
+            .iter()
+            .map(|block| block.heading())
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                "1000001",
+                "1000002",
+                "1000001_2",
+                "1000002_2",
+                "1000001_2_2"
+            ]
+        );
+        assert!(join(&[]).data_blocks().is_none());
+        assert_eq!(
+            unique_names(["a_b", "A_B", "a_b_2"]),
+            ["a_b", "A_B_2", "a_b_2_2"]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Parse a document past malformed items, to salvage what can be read, like
+//! the atom sites of a file with one unterminated quote.
+//!
+//! Where an item does not parse a [`Diagnostic`] is recorded and parsing
+//! goes on at the next tag, `loop_` or `data_` token, outside comments and
+//! `;` text fields. A loop whose last row breaks off keeps the complete
+//! rows, the values after them being skipped.
+
+use std::fmt::Display;
+
+use winnow::{
+    combinator::opt,
+    stream::{Location, Stream},
+    Parser,
+};
+
+use crate::{
+    grammar::{located, Comments, SyntacticUnit, WhiteSpace},
+    CifDocument, DataBlock, DataBlockHeading, DataBlockMember,
+};
+
+/// Text skipped because it could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    block: Option<String>,
+    line: usize,
+    column: usize,
+    skipped: String,
+}
+
+impl Diagnostic {
+    /// The code of the data block, or `None` before the first heading.
+    pub fn block(&self) -> Option<&str> {
+        self.block.as_deref()
+    }
+
+    /// The line of the skipped text, counted from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the skipped text, counted from 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn skipped(&self) -> &str {
+        &self.skipped
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: syntax error", self.line, self.column)?;
+        if let Some(block) = &self.block {
+            write!(f, " in data_{block}")?;
+        }
+        let first_line = self.skipped.lines().next().unwrap_or_default();
+        write!(f, ", skipped `{first_line}`")?;
+        if self.skipped.lines().nth(1).is_some() {
+            write!(f, "...")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse `input`, skipping what does not parse. The document holds every
+/// item read; the diagnostics are in the order of the input.
+pub fn parse(input: &str) -> (CifDocument<'_>, Vec<Diagnostic>) {
+    let mut rest = located(input);
+    let comments = opt(Comments::parser)
+        .parse_next(&mut rest)
+        .unwrap_or_default();
+    let mut blocks: Vec<DataBlock> = Vec::new();
+    let mut diagnostics = Vec::new();
+    loop {
+        let _ = opt(WhiteSpace::parser).parse_next(&mut rest);
+        if rest.eof_offset() == 0 {
+            break;
+        }
+        let start = rest.checkpoint();
+        if let Ok(heading) = DataBlockHeading::parser.parse_next(&mut rest) {
+            blocks.push(DataBlock::from_heading_members((heading, Vec::new())));
+            continue;
+        }
+        rest.reset(&start);
+        if let Some(block) = blocks.last_mut() {
+            if let Ok(member) = DataBlockMember::parser.parse_next(&mut rest) {
+                block.members_mut().push(member);
+                continue;
+            }
+            rest.reset(&start);
+        }
+        let offset = rest.location();
+        let skipped = next_item(&input[offset..]);
+        let (line, column) = line_column(input, offset);
+        diagnostics.push(Diagnostic {
+            block: blocks.last().map(|block| block.heading().to_string()),
+            line,
+            column,
+            skipped: input[offset..offset + skipped].trim_end().to_string(),
+        });
+        rest.next_slice(skipped);
+    }
+    let blocks = (!blocks.is_empty()).then_some(blocks);
+    (CifDocument::new(comments, blocks), diagnostics)
+}
+
+/// The offset in `input` of the first tag, `loop_` or `data_` token after
+/// its first character, or the end of `input`.
+fn next_item(input: &str) -> usize {
+    let mut chars = input.char_indices().peekable();
+    let mut previous = chars.next().map(|(_, c)| c);
+    while let Some((i, c)) = chars.next() {
+        let after_blank = previous.is_some_and(char::is_whitespace);
+        let line_start = previous == Some('\n');
+        previous = Some(c);
+        let skip_to = match c {
+            ';' if line_start => input[i..].find("\n;").map(|j| i + j + 2),
+            '#' if after_blank => input[i..].find('\n').map(|j| i + j),
+            _ if after_blank && is_item_start(&input[i..]) => return i,
+            _ => None,
+        };
+        if let Some(end) = skip_to {
+            while chars.peek().is_some_and(|&(j, _)| j < end) {
+                previous = chars.next().map(|(_, c)| c);
+            }
+        }
+    }
+    input.len()
+}
+
+fn is_item_start(input: &str) -> bool {
+    let reserved = |word: &str| {
+        input
+            .get(..word.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(word))
+    };
+    let mut chars = input.chars();
+    (chars.next() == Some('_') && chars.next().is_some_and(|c| !c.is_whitespace()))
+        || reserved("loop_")
+        || reserved("data_")
+}
+
+/// The line and column of the byte `offset` in `content`, counted from 1.
+pub(crate) fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+
+    #[test]
+    fn skip_malformed_items() {
+        let input = r#"#\#CIF_1.1
+stray text
+data_broken
+_chemical_name_common 'iron oxide
+_cell_length_a 5.0
+_publ_section_title
+;
+_not_a_tag inside text
+;
+loop_
+_atom_site_label
+_atom_site_fract_x
+Fe1 0.1
+O1 0.2
+_cell_length_b 6.0 6.5 'still bad # _no_tag
+_cell_length_c 7.0
+"#;
+        let (document, diagnostics) = parse(input);
+        let block = &document.data_blocks().unwrap()[0];
+        assert_eq!(block.heading(), "broken");
+        assert!(block
+            .find_single_value_by_tag("chemical_name_common")
+            .is_none());
+        assert!(block.find_single_value_by_tag("cell_length_a").is_some());
+        assert!(block
+            .find_single_value_by_tag("publ_section_title")
+            .is_some());
+        let labels = block.find_loop_column_by_tag("atom_site_label").unwrap();
+        assert_eq!(labels.values().len(), 2);
+        assert!(block.find_single_value_by_tag("cell_length_b").is_some());
+        assert!(block.find_single_value_by_tag("cell_length_c").is_some());
+        let found: Vec<(Option<&str>, usize, usize, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.block(), d.line(), d.column(), d.skipped()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (None, 2, 1, "stray text"),
+                (Some("broken"), 4, 1, "_chemical_name_common 'iron oxide"),
+                (Some("broken"), 15, 20, "6.5 'still bad # _no_tag"),
+            ]
+        );
+
+        let input = "data_ragged\nloop_\n_atom_site_label\n_atom_site_fract_x\nFe1 0.1\nO1\n_cell_length_a 5.0\n";
+        let (document, diagnostics) = parse(input);
+        let block = &document.data_blocks().unwrap()[0];
+        let labels = block.find_loop_column_by_tag("atom_site_label").unwrap();
+        assert_eq!(labels.values().len(), 1);
+        assert!(block.find_single_value_by_tag("cell_length_a").is_some());
+        let found: Vec<(usize, usize, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.line(), d.column(), d.skipped()))
+            .collect();
+        assert_eq!(found, [(6, 1, "O1")]);
+    }
+}
@@ -0,0 +1,270 @@
+//! Write a document with a chosen layout. With the default
+//! [`WriteOptions`] the output is the same as the `Display` of
+//! [`CifDocument`]; save frames are always written as displayed.
+
+use crate::{
+    grammar::closes_quote, CharString, CifDocument, DataBlock, DataBlockMember, DataItems,
+    DoubleQuotedString, LoopColumns, SingleLineData, SingleQuotedString, Value,
+};
+
+/// How quoted strings are written by [`write_document`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteStyle {
+    /// Keep the quotes of the input.
+    #[default]
+    Preserve,
+    /// Quote with `'`, or `"` when the content closes a `'`.
+    Single,
+    /// Quote with `"`, or `'` when the content closes a `"`.
+    Double,
+}
+
+/// The layout of [`write_document`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    tag_width: usize,
+    loop_indent: usize,
+    align_loops: bool,
+    quotes: QuoteStyle,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            tag_width: 33,
+            loop_indent: 0,
+            align_loops: false,
+            quotes: QuoteStyle::default(),
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Tags of single items are padded to `tag_width` before their value.
+    pub fn with_tag_width(mut self, tag_width: usize) -> Self {
+        self.tag_width = tag_width;
+        self
+    }
+
+    /// Loop tags and rows are indented by `loop_indent` spaces.
+    pub fn with_loop_indent(mut self, loop_indent: usize) -> Self {
+        self.loop_indent = loop_indent;
+        self
+    }
+
+    /// Pad loop values to the widest value of their column.
+    pub fn with_aligned_loops(mut self, align_loops: bool) -> Self {
+        self.align_loops = align_loops;
+        self
+    }
+
+    pub fn with_quotes(mut self, quotes: QuoteStyle) -> Self {
+        self.quotes = quotes;
+        self
+    }
+
+    pub fn tag_width(&self) -> usize {
+        self.tag_width
+    }
+
+    pub fn loop_indent(&self) -> usize {
+        self.loop_indent
+    }
+
+    pub fn align_loops(&self) -> bool {
+        self.align_loops
+    }
+
+    pub fn quotes(&self) -> QuoteStyle {
+        self.quotes
+    }
+}
+
+/// `document` in the layout of `options`.
+pub fn write_document(document: &CifDocument, options: &WriteOptions) -> String {
+    let comment = match document.comments() {
+        Some(c) => c.to_string(),
+        None => "#\\#CIF_1.1".to_string(),
+    };
+    let data_blocks = document
+        .data_blocks()
+        .map(|blocks| {
+            blocks
+                .iter()
+                .map(|block| write_block(block, options))
+                .collect::<Vec<String>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+    [comment, data_blocks].join("\n")
+}
+
+/// `block` in the layout of `options`.
+pub fn write_block(block: &DataBlock, options: &WriteOptions) -> String {
+    let members = block
+        .members()
+        .iter()
+        .map(|member| match member {
+            DataBlockMember::DataItems(DataItems::SingleValue(item)) => write_item(item, options),
+            DataBlockMember::DataItems(DataItems::MultiValues(columns)) => {
+                format!("\n{}\n", write_loop(columns, options))
+            }
+            DataBlockMember::SaveFrame(frame) => frame.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    [format!("\ndata_{}\n", block.heading()), members].join("\n")
+}
+
+fn write_item(item: &SingleLineData, options: &WriteOptions) -> String {
+    format!(
+        "{:<width$} {}",
+        item.tag().to_string(),
+        requoted(item.value(), options.quotes),
+        width = options.tag_width
+    )
+}
+
+fn write_loop(columns: &LoopColumns, options: &WriteOptions) -> String {
+    let indent = " ".repeat(options.loop_indent);
+    let tags = columns
+        .columns()
+        .iter()
+        .map(|column| format!("{indent}{}\n", column.tag()))
+        .collect::<String>();
+    let values: Vec<Vec<String>> = columns
+        .columns()
+        .iter()
+        .map(|column| {
+            column
+                .values()
+                .iter()
+                .map(|value| requoted(value, options.quotes))
+                .collect()
+        })
+        .collect();
+    // Text fields start on a line of their own and take no part in the
+    // alignment.
+    let widths: Vec<usize> = values
+        .iter()
+        .map(|column| match options.align_loops {
+            true => column
+                .iter()
+                .filter(|value| !value.contains('\n'))
+                .map(|value| value.chars().count())
+                .max()
+                .unwrap_or(0),
+            false => 0,
+        })
+        .collect();
+    let row_count = values.first().map_or(0, Vec::len);
+    let rows = (0..row_count)
+        .map(|row| {
+            let last = values.len() - 1;
+            let line = values
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (column, &width))| match i == last {
+                    true => column[row].clone(),
+                    false => format!("{:<width$}", column[row]),
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            match line.starts_with('\n') {
+                true => line,
+                false => format!("{indent}{line}"),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("loop_\n{tags}{rows}")
+}
+
+/// `value` as written, a quoted string requoted by `quotes`. Content closing
+/// both quotes is written as a text field.
+fn requoted(value: &Value, quotes: QuoteStyle) -> String {
+    let content = match value {
+        Value::CharString(CharString::SingleQuoted(_) | CharString::DoubleQuoted(_))
+            if quotes != QuoteStyle::Preserve =>
+        {
+            value.text()
+        }
+        _ => return value.to_string(),
+    };
+    let (single, double) = (closes_quote(&content, '\''), closes_quote(&content, '"'));
+    let requoted = match (quotes, single, double) {
+        (_, true, true) => Value::from_text(content),
+        (QuoteStyle::Single, false, _) | (QuoteStyle::Double, _, true) => {
+            CharString::SingleQuoted(SingleQuotedString::new(content)).into()
+        }
+        _ => CharString::DoubleQuoted(DoubleQuotedString::new(content)).into(),
+    };
+    requoted.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{diff::diff, CharString, CifDocument, SingleQuotedString, Value};
+
+    use super::{requoted, write_document, QuoteStyle, WriteOptions};
+
+    const INPUT: &str = "data_test
+_cell_length_a 5.4(2)
+_journal_name_full 'Acta Cryst.'
+_publ_contact_author \"O'Brien\"
+loop_
+_atom_site_label
+_atom_site_type_symbol
+_atom_site_fract_x
+Fe1 Fe 0.5
+O12 O 0.25(3)
+";
+
+    fn parse(input: &str) -> CifDocument<'_> {
+        let mut input = input;
+        CifDocument::parse_from_str(&mut input).unwrap()
+    }
+
+    #[test]
+    fn default_options_write_as_display() {
+        let document = parse(INPUT);
+        assert_eq!(
+            write_document(&document, &WriteOptions::default()),
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn aligned_requoted_layout() {
+        let document = parse(INPUT);
+        let options = WriteOptions::default()
+            .with_tag_width(21)
+            .with_loop_indent(2)
+            .with_aligned_loops(true)
+            .with_quotes(QuoteStyle::Double);
+        let written = write_document(&document, &options);
+        assert!(written.contains("_cell_length_a        5.4(2)\n"));
+        assert!(written.contains("_journal_name_full    \"Acta Cryst.\"\n"));
+        assert!(written.contains("_publ_contact_author  \"O'Brien\"\n"));
+        assert!(written.contains(
+            "loop_\n  _atom_site_label\n  _atom_site_type_symbol\n  _atom_site_fract_x\n  Fe1 Fe 0.5\n  O12 O  0.25(3)"
+        ));
+        assert!(diff(&document, &parse(&written)).is_empty());
+    }
+
+    #[test]
+    fn quotes_fall_back() {
+        let document = parse("data_q\n_a \"it's\"\n_b \"x' 'y\"\n");
+        let written = write_document(
+            &document,
+            &WriteOptions::default().with_quotes(QuoteStyle::Single),
+        );
+        assert!(written.contains("'it's'"));
+        assert!(written.contains("\"x' 'y\""));
+        let both = Value::CharString(CharString::SingleQuoted(SingleQuotedString::new(
+            "a\" b' c",
+        )));
+        assert_eq!(requoted(&both, QuoteStyle::Double), "\n;\na\" b' c\n;");
+    }
+}